@@ -0,0 +1,4 @@
+-- Each employee's manager, notified when the employee submits a leave request
+ALTER TABLE employees
+    ADD COLUMN manager_id BIGINT UNSIGNED NULL,
+    ADD INDEX idx_employees_manager (manager_id);
@@ -0,0 +1,10 @@
+-- Remaining days per employee, year and leave type; seeded on the first approval
+CREATE TABLE leave_balances (
+    employee_id BIGINT UNSIGNED NOT NULL,
+    year INT NOT NULL,
+    leave_type VARCHAR(20) NOT NULL,
+    remaining_days INT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (employee_id, year, leave_type),
+    CONSTRAINT fk_leave_balances_employee FOREIGN KEY (employee_id) REFERENCES employees (id) ON DELETE CASCADE
+);
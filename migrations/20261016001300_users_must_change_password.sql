@@ -0,0 +1,3 @@
+-- Set when HR issues a temporary password; cleared by change-password
+ALTER TABLE users
+    ADD COLUMN must_change_password BOOLEAN NOT NULL DEFAULT FALSE;
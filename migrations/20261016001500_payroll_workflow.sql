@@ -0,0 +1,7 @@
+-- Payroll moves draft -> approved -> paid, recording who did each step and when
+ALTER TABLE payroll
+    ADD COLUMN status VARCHAR(20) NOT NULL DEFAULT 'draft',
+    ADD COLUMN approved_by BIGINT UNSIGNED NULL,
+    ADD COLUMN approved_at TIMESTAMP NULL,
+    ADD COLUMN paid_by BIGINT UNSIGNED NULL,
+    ADD COLUMN paid_at TIMESTAMP NULL;
@@ -0,0 +1,24 @@
+-- Organization scoping: every core table carries the tenant it belongs to.
+-- Existing rows are assigned to organization 1.
+ALTER TABLE users
+    ADD COLUMN org_id BIGINT UNSIGNED NOT NULL DEFAULT 1 AFTER employee_id,
+    ADD INDEX idx_users_org (org_id);
+
+ALTER TABLE departments
+    ADD COLUMN org_id BIGINT UNSIGNED NOT NULL DEFAULT 1 AFTER id,
+    ADD INDEX idx_departments_org_name (org_id, name);
+
+ALTER TABLE job_titles
+    ADD COLUMN org_id BIGINT UNSIGNED NOT NULL DEFAULT 1 AFTER id,
+    ADD INDEX idx_job_titles_org_name (org_id, name);
+
+ALTER TABLE employees
+    ADD COLUMN org_id BIGINT UNSIGNED NOT NULL DEFAULT 1 AFTER id,
+    ADD INDEX idx_employees_org_status (org_id, status);
+
+ALTER TABLE leave_requests
+    ADD COLUMN org_id BIGINT UNSIGNED NOT NULL DEFAULT 1 AFTER id,
+    ADD INDEX idx_leave_requests_org_created (org_id, created_at);
+
+ALTER TABLE payroll
+    ADD COLUMN org_id BIGINT UNSIGNED NOT NULL DEFAULT 1 AFTER id;
@@ -0,0 +1,12 @@
+-- Every change to leave_balances, with who made it and why
+CREATE TABLE leave_balance_ledger (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    employee_id BIGINT UNSIGNED NOT NULL,
+    year INT NOT NULL,
+    leave_type VARCHAR(20) NOT NULL,
+    delta_days INT NOT NULL,
+    reason VARCHAR(255) NOT NULL,
+    actor_user_id BIGINT UNSIGNED NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_leave_balance_ledger_employee (employee_id, year, leave_type)
+);
@@ -0,0 +1,8 @@
+-- Normalized employee photos, one per employee
+CREATE TABLE employee_photos (
+    employee_id BIGINT UNSIGNED NOT NULL PRIMARY KEY,
+    content_type VARCHAR(50) NOT NULL,
+    data MEDIUMBLOB NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    CONSTRAINT fk_employee_photos_employee FOREIGN KEY (employee_id) REFERENCES employees (id) ON DELETE CASCADE
+);
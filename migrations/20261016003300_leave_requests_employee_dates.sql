@@ -0,0 +1,3 @@
+-- Serves the overlap check run when a leave request is created
+ALTER TABLE leave_requests
+    ADD INDEX idx_leave_requests_employee_dates (employee_id, start_date, end_date);
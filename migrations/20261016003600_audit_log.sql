@@ -0,0 +1,12 @@
+CREATE TABLE audit_log (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    org_id BIGINT UNSIGNED NOT NULL,
+    actor_user_id BIGINT UNSIGNED NULL,
+    entity VARCHAR(50) NOT NULL,
+    entity_id BIGINT UNSIGNED NULL,
+    action VARCHAR(50) NOT NULL,
+    details JSON NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_audit_log_org_created (org_id, created_at),
+    INDEX idx_audit_log_entity (org_id, entity, entity_id)
+);
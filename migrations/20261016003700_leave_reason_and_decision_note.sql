@@ -0,0 +1,4 @@
+-- Reason given by the employee and note left by the approver
+ALTER TABLE leave_requests
+    ADD COLUMN reason VARCHAR(500) NULL,
+    ADD COLUMN decision_note VARCHAR(500) NULL;
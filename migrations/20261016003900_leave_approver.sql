@@ -0,0 +1,4 @@
+-- Who approved or rejected a leave request, and when
+ALTER TABLE leave_requests
+    ADD COLUMN approved_by BIGINT UNSIGNED NULL,
+    ADD COLUMN approved_at TIMESTAMP NULL;
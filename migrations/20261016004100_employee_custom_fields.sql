@@ -0,0 +1,3 @@
+-- Free-form per-employee attributes, validated against CUSTOM_FIELDS
+ALTER TABLE employees
+    ADD COLUMN custom_fields JSON NULL;
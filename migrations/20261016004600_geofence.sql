@@ -0,0 +1,13 @@
+-- Check-in geofence: per-department overrides of the company-wide GEOFENCE,
+-- an opt-in flag per employee and the location recorded at check-in
+ALTER TABLE departments
+    ADD COLUMN geofence_lat DOUBLE NULL,
+    ADD COLUMN geofence_lng DOUBLE NULL,
+    ADD COLUMN geofence_radius_m DOUBLE NULL;
+
+ALTER TABLE employees
+    ADD COLUMN geofence_required BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE attendance
+    ADD COLUMN check_in_lat DOUBLE NULL,
+    ADD COLUMN check_in_lng DOUBLE NULL;
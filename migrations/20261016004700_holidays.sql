@@ -0,0 +1,8 @@
+CREATE TABLE holidays (
+    id BIGINT UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+    org_id BIGINT UNSIGNED NOT NULL,
+    date DATE NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    -- create_holiday answers 409 on a clash
+    UNIQUE KEY uq_holidays_org_date (org_id, date)
+);
@@ -0,0 +1,3 @@
+-- Cleared when an account is disabled, by HR or after a period of inactivity
+ALTER TABLE users
+    ADD COLUMN is_active BOOLEAN NOT NULL DEFAULT TRUE;
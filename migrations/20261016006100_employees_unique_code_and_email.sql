@@ -0,0 +1,4 @@
+-- duplicate_employee_field() recognises these key names in MySQL's duplicate-entry message
+ALTER TABLE employees
+    ADD UNIQUE KEY uq_employees_org_employee_code (org_id, employee_code),
+    ADD UNIQUE KEY uq_employees_org_email (org_id, email);
@@ -0,0 +1,3 @@
+-- Soft delete: set instead of removing the row, cleared by restore
+ALTER TABLE employees
+    ADD COLUMN deleted_at TIMESTAMP NULL;
@@ -0,0 +1,3 @@
+-- One payroll per employee and month; create_payroll answers 409 on a clash
+ALTER TABLE payroll
+    ADD UNIQUE KEY uq_payroll_org_employee_month (org_id, employee_id, month);
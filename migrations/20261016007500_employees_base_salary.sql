@@ -0,0 +1,3 @@
+-- Monthly salary used by bulk payroll generation
+ALTER TABLE employees
+    ADD COLUMN base_salary DOUBLE NULL;
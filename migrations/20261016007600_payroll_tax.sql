@@ -0,0 +1,2 @@
+ALTER TABLE payroll
+    ADD COLUMN tax DOUBLE NOT NULL DEFAULT 0;
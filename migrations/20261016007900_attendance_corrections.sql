@@ -0,0 +1,4 @@
+-- Who last corrected an attendance record, and when
+ALTER TABLE attendance
+    ADD COLUMN corrected_by BIGINT UNSIGNED NULL,
+    ADD COLUMN corrected_at TIMESTAMP NULL;
@@ -0,0 +1,3 @@
+-- Deleting an employee disables the accounts linked to it
+ALTER TABLE users
+    ADD INDEX idx_users_employee (employee_id);
@@ -0,0 +1,2 @@
+ALTER TABLE employees
+    ADD COLUMN status_changed_at TIMESTAMP NULL;
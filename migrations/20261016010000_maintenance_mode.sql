@@ -0,0 +1,7 @@
+-- Maintenance switch shared by every instance; a single row with id 1
+CREATE TABLE maintenance_mode (
+    id TINYINT UNSIGNED NOT NULL PRIMARY KEY,
+    enabled BOOLEAN NOT NULL,
+    updated_by BIGINT UNSIGNED NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+);
@@ -0,0 +1,9 @@
+-- Names are unique per organization; the batch and single create endpoints turn a
+-- duplicate-key error into a skip or a 409. Rename existing duplicates before applying.
+ALTER TABLE departments
+    DROP INDEX idx_departments_org_name,
+    ADD UNIQUE KEY uq_departments_org_name (org_id, name);
+
+ALTER TABLE job_titles
+    DROP INDEX idx_job_titles_org_name,
+    ADD UNIQUE KEY uq_job_titles_org_name (org_id, name);
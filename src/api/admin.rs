@@ -0,0 +1,599 @@
+use crate::api::leave_request::days_per_year;
+use crate::auth::auth::AuthUser;
+use crate::config::Config;
+use crate::maintenance;
+use crate::utils::audit::{self, AuditEntry};
+use crate::utils::calendar;
+use actix_web::{HttpResponse, Responder, error::ErrorInternalServerError, web};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+use std::collections::{HashMap, HashSet};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct MaintenanceState {
+    /// true = non-GET requests are rejected with 503
+    #[schema(example = true)]
+    pub enabled: bool,
+}
+
+/// Current maintenance mode state (System)
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/maintenance",
+    responses(
+        (status = 200, description = "Maintenance mode state", body = MaintenanceState),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin"
+)]
+pub async fn get_maintenance(auth: AuthUser) -> actix_web::Result<impl Responder> {
+    auth.require_permission("system.maintenance")?;
+
+    Ok(HttpResponse::Ok().json(MaintenanceState {
+        enabled: maintenance::is_enabled(),
+    }))
+}
+
+/// Switch maintenance mode on or off for every organization and instance (System)
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/maintenance",
+    request_body = MaintenanceState,
+    responses(
+        (status = 200, description = "Maintenance mode updated", body = MaintenanceState),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin"
+)]
+pub async fn set_maintenance(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    body: web::Json<MaintenanceState>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("system.maintenance")?;
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to start maintenance change");
+        ErrorInternalServerError("Internal Server Error")
+    })?;
+    maintenance::store(&mut *tx, body.enabled, Some(auth.user_id))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to store maintenance mode");
+            ErrorInternalServerError("Internal Server Error")
+        })?;
+    audit::record(
+        &mut *tx,
+        AuditEntry {
+            org_id: auth.org_id,
+            actor_user_id: Some(auth.user_id),
+            entity: "system",
+            entity_id: None,
+            action: if body.enabled { "maintenance_on" } else { "maintenance_off" },
+            details: None,
+        },
+    )
+    .await?;
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to commit maintenance change");
+        ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    // this instance at once, the others on their next sync
+    maintenance::set_enabled(body.enabled);
+    tracing::warn!(enabled = body.enabled, user_id = auth.user_id, "Maintenance mode changed");
+
+    Ok(HttpResponse::Ok().json(MaintenanceState {
+        enabled: body.enabled,
+    }))
+}
+
+/// Payrolls whose stored net differs from base + bonus - tax - deductions (to the cent)
+const NET_SALARY_MISMATCH_SQL: &str = r#"
+    SELECT id, status
+    FROM payroll
+    WHERE org_id = ?
+    AND ROUND(net_salary, 2) <> ROUND(base_salary + bonus - tax - deductions, 2)
+    ORDER BY id
+"#;
+
+#[derive(Serialize, ToSchema)]
+pub struct Anomaly {
+    /// Which consistency check found it
+    #[schema(example = "payroll_net_salary_mismatch")]
+    pub check: &'static str,
+    /// Table/entity the id refers to
+    #[schema(example = "payroll")]
+    pub entity: &'static str,
+    #[schema(example = 42)]
+    pub id: u64,
+    #[schema(example = "status draft")]
+    pub detail: Option<String>,
+    /// true = `POST /admin/reconcile/fix` corrects it
+    pub fixable: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReconcileReport {
+    #[schema(example = 1)]
+    pub total: usize,
+    pub anomalies: Vec<Anomaly>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReconcileFixResponse {
+    /// Draft payrolls whose net_salary was recomputed
+    #[schema(example = 1)]
+    pub payroll_net_salary_fixed: u64,
+}
+
+fn reconcile_error(check: &'static str) -> impl FnOnce(sqlx::Error) -> actix_web::Error {
+    move |e| {
+        tracing::error!(error = %e, check, "Reconciliation query failed");
+        ErrorInternalServerError("Internal Server Error")
+    }
+}
+
+/// Run every consistency check for the org. Only net_salary on draft payrolls is
+/// deterministic enough to fix automatically; the rest need a human to decide.
+async fn find_anomalies(
+    conn: &mut MySqlConnection,
+    org_id: u64,
+    holidays: &HashSet<NaiveDate>,
+    entitlement: u32,
+) -> actix_web::Result<Vec<Anomaly>> {
+    let mut anomalies = Vec::new();
+
+    let payrolls = sqlx::query_as::<_, (u64, String)>(NET_SALARY_MISMATCH_SQL)
+        .bind(org_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(reconcile_error("payroll_net_salary_mismatch"))?;
+
+    anomalies.extend(payrolls.into_iter().map(|(id, status)| Anomaly {
+        check: "payroll_net_salary_mismatch",
+        entity: "payroll",
+        id,
+        fixable: status == "draft",
+        detail: Some(format!("status {}", status)),
+    }));
+
+    let balances = sqlx::query_as::<_, (u64, i32, String, i32)>(
+        r#"
+        SELECT lb.employee_id, lb.year, lb.leave_type, lb.remaining_days
+        FROM leave_balances lb
+        JOIN employees e ON e.id = lb.employee_id
+        WHERE e.org_id = ?
+        AND lb.remaining_days < 0
+        ORDER BY lb.employee_id, lb.year
+        "#,
+    )
+    .bind(org_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(reconcile_error("negative_leave_balance"))?;
+
+    anomalies.extend(balances.into_iter().map(
+        |(employee_id, year, leave_type, remaining)| Anomaly {
+            check: "negative_leave_balance",
+            entity: "employee",
+            id: employee_id,
+            fixable: false,
+            detail: Some(format!("{} {}: {} day(s)", leave_type, year, remaining)),
+        },
+    ));
+
+    // manual adjustments carry the admin who made them; approvals and cancellations
+    // are recorded without an actor and are what the approved leave accounts for
+    let annual = sqlx::query_as::<_, AnnualBalance>(
+        r#"
+        SELECT lb.employee_id, lb.year, lb.remaining_days,
+            CAST(COALESCE((
+                SELECT SUM(l.delta_days)
+                FROM leave_balance_ledger l
+                WHERE l.employee_id = lb.employee_id
+                AND l.year = lb.year
+                AND l.leave_type = lb.leave_type
+                AND l.actor_user_id IS NOT NULL
+            ), 0) AS SIGNED) AS adjusted_days
+        FROM leave_balances lb
+        JOIN employees e ON e.id = lb.employee_id
+        WHERE e.org_id = ?
+        AND lb.leave_type = 'annual'
+        ORDER BY lb.employee_id, lb.year
+        "#,
+    )
+    .bind(org_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(reconcile_error("leave_balance_mismatch"))?;
+
+    let approved = sqlx::query_as::<_, (u64, NaiveDate, NaiveDate)>(
+        r#"
+        SELECT employee_id, start_date, end_date
+        FROM leave_requests
+        WHERE org_id = ?
+        AND status = 'approved'
+        AND leave_type = 'annual'
+        "#,
+    )
+    .bind(org_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(reconcile_error("leave_balance_mismatch"))?;
+
+    anomalies.extend(balance_mismatches(
+        &annual,
+        &approved,
+        holidays,
+        entitlement,
+    ));
+
+    let leaves = sqlx::query_scalar::<_, u64>(
+        r#"
+        SELECT lr.id
+        FROM leave_requests lr
+        WHERE lr.org_id = ?
+        AND lr.status = 'approved'
+        AND lr.leave_type = 'annual'
+        AND NOT EXISTS (
+            SELECT 1 FROM leave_balances lb
+            WHERE lb.employee_id = lr.employee_id
+            AND lb.year = YEAR(lr.start_date)
+            AND lb.leave_type = 'annual'
+        )
+        ORDER BY lr.id
+        "#,
+    )
+    .bind(org_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(reconcile_error("approved_leave_without_balance"))?;
+
+    anomalies.extend(leaves.into_iter().map(|id| Anomaly {
+        check: "approved_leave_without_balance",
+        entity: "leave_request",
+        id,
+        fixable: false,
+        detail: None,
+    }));
+
+    let attendance = sqlx::query_scalar::<_, u64>(
+        r#"
+        SELECT a.id
+        FROM attendance a
+        JOIN employees e ON e.id = a.employee_id
+        WHERE e.org_id = ?
+        AND a.check_out IS NOT NULL
+        AND a.check_out < a.check_in
+        ORDER BY a.id
+        "#,
+    )
+    .bind(org_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(reconcile_error("check_out_before_check_in"))?;
+
+    anomalies.extend(attendance.into_iter().map(|id| Anomaly {
+        check: "check_out_before_check_in",
+        entity: "attendance",
+        id,
+        fixable: false,
+        detail: None,
+    }));
+
+    Ok(anomalies)
+}
+
+/// An annual leave balance with the net of its manual adjustments
+#[derive(sqlx::FromRow)]
+struct AnnualBalance {
+    employee_id: u64,
+    year: i32,
+    remaining_days: i32,
+    adjusted_days: i64,
+}
+
+/// Annual balances whose used days (entitlement plus manual adjustments minus what is
+/// left) differ from the working days of the employee's approved annual leave that year
+fn balance_mismatches(
+    balances: &[AnnualBalance],
+    approved: &[(u64, NaiveDate, NaiveDate)],
+    holidays: &HashSet<NaiveDate>,
+    entitlement: u32,
+) -> Vec<Anomaly> {
+    let mut approved_days: HashMap<(u64, i32), i64> = HashMap::new();
+    for &(employee_id, start, end) in approved {
+        for (year, days) in days_per_year(start, end, holidays) {
+            *approved_days.entry((employee_id, year)).or_default() += i64::from(days);
+        }
+    }
+
+    balances
+        .iter()
+        .filter_map(|b| {
+            let used = i64::from(entitlement) + b.adjusted_days - i64::from(b.remaining_days);
+            let approved = approved_days
+                .get(&(b.employee_id, b.year))
+                .copied()
+                .unwrap_or(0);
+
+            (used != approved).then(|| Anomaly {
+                check: "leave_balance_mismatch",
+                entity: "employee",
+                id: b.employee_id,
+                fixable: false,
+                detail: Some(format!(
+                    "annual {}: {} day(s) used, {} day(s) of approved leave",
+                    b.year, used, approved
+                )),
+            })
+        })
+        .collect()
+}
+
+/// Report data inconsistencies left by bugs or manual edits (Admin)
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/reconcile",
+    responses(
+        (status = 200, description = "Anomalies found by each check; empty when consistent", body = ReconcileReport),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin"
+)]
+pub async fn reconcile(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("system.reconcile")?;
+
+    let holidays = calendar::holidays(pool.get_ref(), auth.org_id).await?;
+    let mut conn = pool.acquire().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to acquire connection for reconciliation");
+        ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let anomalies =
+        find_anomalies(&mut conn, auth.org_id, &holidays, config.annual_leave_days).await?;
+
+    Ok(HttpResponse::Ok().json(ReconcileReport {
+        total: anomalies.len(),
+        anomalies,
+    }))
+}
+
+/// Correct the deterministic anomalies in one transaction (Admin)
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/reconcile/fix",
+    responses(
+        (status = 200, description = "Draft payrolls have net_salary recomputed; paid/approved ones and other checks are left for manual review", body = ReconcileFixResponse),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin"
+)]
+pub async fn reconcile_fix(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("system.reconcile")?;
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to start reconciliation transaction");
+        ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let fixed = sqlx::query(
+        r#"
+        UPDATE payroll
+        SET net_salary = base_salary + bonus - tax - deductions
+        WHERE org_id = ?
+        AND status = 'draft'
+        AND ROUND(net_salary, 2) <> ROUND(base_salary + bonus - tax - deductions, 2)
+        "#,
+    )
+    .bind(auth.org_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(reconcile_error("payroll_net_salary_mismatch"))?
+    .rows_affected();
+
+    if fixed > 0 {
+        audit::record(
+            &mut *tx,
+            AuditEntry {
+                org_id: auth.org_id,
+                actor_user_id: Some(auth.user_id),
+                entity: "system",
+                entity_id: None,
+                action: "reconcile_fix",
+                details: Some(serde_json::json!({ "payroll_net_salary_fixed": fixed })),
+            },
+        )
+        .await?;
+    }
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to commit reconciliation");
+        ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    tracing::warn!(fixed, admin_id = auth.user_id, "Reconciliation fixes applied");
+
+    Ok(HttpResponse::Ok().json(ReconcileFixResponse {
+        payroll_net_salary_fixed: fixed,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, day};
+
+    fn balance(
+        employee_id: u64,
+        year: i32,
+        remaining_days: i32,
+        adjusted_days: i64,
+    ) -> AnnualBalance {
+        AnnualBalance {
+            employee_id,
+            year,
+            remaining_days,
+            adjusted_days,
+        }
+    }
+
+    #[test]
+    fn balance_matching_the_approved_leave_is_consistent() {
+        // Mon-Fri, and a week spanning New Year: 3 days in 2025, 2 in 2026
+        let approved = [
+            (1, day("2026-03-02"), day("2026-03-06")),
+            (1, day("2025-12-29"), day("2026-01-02")),
+        ];
+        let balances = [balance(1, 2026, 13, 0), balance(1, 2025, 17, 0)];
+
+        assert!(balance_mismatches(&balances, &approved, &HashSet::new(), 20).is_empty());
+    }
+
+    #[test]
+    fn manual_adjustments_are_not_leave() {
+        let approved = [(1, day("2026-03-02"), day("2026-03-06"))];
+        // 20 + 3 granted by an admin - 5 taken
+        let balances = [balance(1, 2026, 18, 3)];
+
+        assert!(balance_mismatches(&balances, &approved, &HashSet::new(), 20).is_empty());
+    }
+
+    #[test]
+    fn balance_out_of_sync_with_the_approved_leave_is_reported() {
+        let approved = [(1, day("2026-03-02"), day("2026-03-06"))];
+        // a holiday on the Wednesday leaves 4 days of leave, but 5 were taken off
+        let holidays = HashSet::from([day("2026-03-04")]);
+        let balances = [balance(1, 2026, 15, 0), balance(2, 2026, 19, 0)];
+
+        let anomalies = balance_mismatches(&balances, &approved, &holidays, 20);
+
+        let found: Vec<(u64, Option<&str>)> = anomalies
+            .iter()
+            .map(|a| (a.id, a.detail.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    1,
+                    Some("annual 2026: 5 day(s) used, 4 day(s) of approved leave")
+                ),
+                (
+                    2,
+                    Some("annual 2026: 1 day(s) used, 0 day(s) of approved leave")
+                ),
+            ]
+        );
+        assert!(
+            anomalies
+                .iter()
+                .all(|a| a.check == "leave_balance_mismatch")
+        );
+    }
+
+    async fn set_balance(pool: &MySqlPool, employee_id: u64, remaining_days: i32) {
+        sqlx::query(
+            "INSERT INTO leave_balances (employee_id, year, leave_type, remaining_days) VALUES (?, 2026, 'annual', ?)",
+        )
+        .bind(employee_id)
+        .bind(remaining_days)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn every_seeded_anomaly_is_found() {
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+
+        let paid_off = test_support::employee(&pool, org).await;
+        let payroll = test_support::payroll(&pool, org, paid_off, "2026-01-01", "draft").await;
+        sqlx::query("UPDATE payroll SET net_salary = 900 WHERE id = ?")
+            .bind(payroll)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let overdrawn = test_support::employee(&pool, org).await;
+        set_balance(&pool, overdrawn, -2).await;
+
+        // 5 days approved, nothing taken off the balance
+        let untouched = test_support::employee(&pool, org).await;
+        set_balance(&pool, untouched, 20).await;
+        test_support::leave(
+            &pool,
+            org,
+            untouched,
+            "2026-03-02",
+            "2026-03-06",
+            "approved",
+        )
+        .await;
+
+        let unbalanced = test_support::employee(&pool, org).await;
+        let leave = test_support::leave(
+            &pool,
+            org,
+            unbalanced,
+            "2026-03-02",
+            "2026-03-06",
+            "approved",
+        )
+        .await;
+
+        let attendance = sqlx::query(
+            "INSERT INTO attendance (employee_id, date, check_in, check_out) VALUES (?, '2026-03-02', '17:00:00', '09:00:00')",
+        )
+        .bind(unbalanced)
+        .execute(&pool)
+        .await
+        .unwrap()
+        .last_insert_id();
+
+        let mut conn = pool.acquire().await.unwrap();
+        let anomalies = find_anomalies(&mut conn, org, &HashSet::new(), 20)
+            .await
+            .unwrap();
+        let found: Vec<(&str, u64)> = anomalies.iter().map(|a| (a.check, a.id)).collect();
+
+        for expected in [
+            ("payroll_net_salary_mismatch", payroll),
+            ("negative_leave_balance", overdrawn),
+            ("leave_balance_mismatch", untouched),
+            ("approved_leave_without_balance", leave),
+            ("check_out_before_check_in", attendance),
+        ] {
+            assert!(
+                found.contains(&expected),
+                "{:?} not in {:?}",
+                expected,
+                found
+            );
+        }
+    }
+}
@@ -1,20 +1,294 @@
 use crate::auth::auth::AuthUser;
-use actix_web::{HttpResponse, Responder, web};
-use sqlx::MySqlPool;
+use crate::config::Config;
+use crate::model::attendance::Attendance;
+use crate::utils::audit::{self, AuditEntry};
+use crate::utils::date_param;
+use crate::utils::geo::{self, Geofence};
+use crate::utils::pagination::link_header;
+use crate::utils::pii;
+use actix_web::{HttpRequest, HttpResponse, Responder, http::header::LINK, web};
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use utoipa::{IntoParams, ToSchema};
 
-/// Check-in endpoint
+/// An employee with an open check-in (checked in, not yet checked out)
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct PresentEmployee {
+    #[schema(example = 1000)]
+    pub employee_id: u64,
+    #[schema(example = "John")]
+    #[serde(serialize_with = "pii::name")]
+    pub first_name: String,
+    #[schema(example = "Doe", nullable = true)]
+    #[serde(serialize_with = "pii::opt_name")]
+    pub last_name: Option<String>,
+    #[schema(example = "09:02:00", value_type = String)]
+    pub check_in: NaiveTime,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PresentResponse {
+    #[schema(example = "2026-01-01", format = "date", value_type = String)]
+    pub date: NaiveDate,
+    #[schema(example = 1)]
+    pub count: usize,
+    pub data: Vec<PresentEmployee>,
+}
+
+#[derive(Deserialize, IntoParams, ToSchema)]
+pub struct AttendanceQuery {
+    #[schema(example = 1)]
+    pub page: Option<u32>,
+    #[schema(example = 31)]
+    pub per_page: Option<u32>,
+    /// Another employee's history; needs `attendance.read`. Defaults to the caller.
+    #[schema(example = 1000)]
+    pub employee_id: Option<u64>,
+    /// `YYYY-MM-DD`, inclusive
+    #[schema(example = "2026-01-01")]
+    pub from_date: Option<String>,
+    /// `YYYY-MM-DD`, inclusive
+    #[schema(example = "2026-01-31")]
+    pub to_date: Option<String>,
+}
+
+/// One day of attendance
+#[derive(Serialize, ToSchema)]
+pub struct AttendanceRecord {
+    #[schema(example = 1)]
+    pub id: u64,
+    #[schema(example = 1000)]
+    pub employee_id: u64,
+    #[schema(example = "2026-01-05", format = "date", value_type = String)]
+    pub date: NaiveDate,
+    /// HH:MM
+    #[schema(example = "09:02", nullable = true)]
+    pub check_in: Option<String>,
+    /// HH:MM
+    #[schema(example = "17:45", nullable = true)]
+    pub check_out: Option<String>,
+    /// Hours between check-in and check-out, two decimals; null while still checked in
+    #[schema(example = 8.72, nullable = true)]
+    pub hours_worked: Option<f64>,
+}
+
+impl AttendanceRecord {
+    fn new(a: Attendance, overnight_shifts: bool) -> Self {
+        AttendanceRecord {
+            id: a.id,
+            employee_id: a.employee_id,
+            date: a.date,
+            check_in: a.check_in.map(|t| t.format("%H:%M").to_string()),
+            check_out: a.check_out.map(|t| t.format("%H:%M").to_string()),
+            hours_worked: hours_worked(a.check_in, a.check_out, overnight_shifts),
+        }
+    }
+}
+
+/// Hours between check-in and check-out, rounded to two decimals. A check-out before
+/// the check-in only counts (as crossing midnight) when overnight shifts are enabled.
+fn hours_worked(
+    check_in: Option<NaiveTime>,
+    check_out: Option<NaiveTime>,
+    overnight_shifts: bool,
+) -> Option<f64> {
+    let (check_in, check_out) = (check_in?, check_out?);
+
+    let mut seconds = (check_out - check_in).num_seconds();
+    if seconds < 0 {
+        if !overnight_shifts {
+            return None;
+        }
+        seconds += 24 * 60 * 60;
+    }
+
+    Some((seconds as f64 / 36.0).round() / 100.0)
+}
+
+#[derive(Deserialize, IntoParams, ToSchema)]
+pub struct AttendanceSummaryQuery {
+    /// Another employee's summary; needs `attendance.read`. Defaults to the caller.
+    #[schema(example = 1000)]
+    pub employee_id: Option<u64>,
+    /// `YYYY-MM`
+    #[schema(example = "2026-01")]
+    pub month: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AttendanceSummary {
+    #[schema(example = 1000)]
+    pub employee_id: u64,
+    #[schema(example = "2026-01")]
+    pub month: String,
+    /// Days with a check-in
+    #[schema(example = 21)]
+    pub days_present: u32,
+    #[schema(example = 168.5)]
+    pub total_hours: f64,
+    /// Past days checked in but never checked out; today is not counted yet
+    #[schema(example = 2)]
+    pub missing_checkouts: u32,
+    #[schema(example = json!(["2026-01-07", "2026-01-19"]))]
+    pub missing_checkout_dates: Vec<String>,
+}
+
+/// HR correction of a day's record; omitted fields keep their value
+#[derive(Deserialize, ToSchema)]
+pub struct CorrectAttendance {
+    #[schema(example = "2026-01-05", format = "date", value_type = Option<String>)]
+    pub date: Option<NaiveDate>,
+    #[schema(example = "09:00:00", value_type = Option<String>)]
+    pub check_in: Option<NaiveTime>,
+    #[schema(example = "17:30:00", value_type = Option<String>)]
+    pub check_out: Option<NaiveTime>,
+}
+
+/// Whose attendance the caller asked for: their own, or anyone's with `attendance.read`
+fn target_employee(auth: &AuthUser, requested: Option<u64>) -> actix_web::Result<u64> {
+    match requested {
+        Some(id) if auth.employee_id == Some(id) => Ok(id),
+        Some(id) => {
+            auth.require_permission("attendance.read")?;
+            Ok(id)
+        }
+        None => auth
+            .employee_id
+            .ok_or_else(|| actix_web::error::ErrorForbidden("No employee profile")),
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AttendanceListResponse {
+    pub data: Vec<AttendanceRecord>,
+    #[schema(example = 1)]
+    pub page: u32,
+    #[schema(example = 31)]
+    pub per_page: u32,
+    #[schema(example = 20)]
+    pub total: i64,
+}
+
+/// Optional body of the check-in endpoint
+#[derive(Deserialize, ToSchema)]
+pub struct CheckInRequest {
+    #[schema(example = 23.8103)]
+    pub lat: Option<f64>,
+    #[schema(example = 90.4125)]
+    pub lng: Option<f64>,
+    /// Day to record; other than today needs HR/Admin
+    #[schema(example = "2026-01-05", format = "date", value_type = Option<String>)]
+    pub date: Option<NaiveDate>,
+    /// Time to record instead of now; needs HR/Admin
+    #[schema(example = "09:00:00", value_type = Option<String>)]
+    pub time: Option<NaiveTime>,
+    /// Employee to check in; anyone but the caller needs HR/Admin
+    #[schema(example = 1000)]
+    pub employee_id: Option<u64>,
+}
+
+impl CheckInRequest {
+    fn location(&self) -> actix_web::Result<Option<CheckInLocation>> {
+        match (self.lat, self.lng) {
+            (Some(lat), Some(lng)) => Ok(Some(CheckInLocation { lat, lng })),
+            (None, None) => Ok(None),
+            _ => Err(actix_web::error::ErrorBadRequest(
+                "lat and lng must be sent together",
+            )),
+        }
+    }
+}
+
+/// Optional body of the check-out endpoint
+#[derive(Deserialize, ToSchema)]
+pub struct CheckOutRequest {
+    /// Day whose check-in to close; other than today needs HR/Admin
+    #[schema(example = "2026-01-05", format = "date", value_type = Option<String>)]
+    pub date: Option<NaiveDate>,
+    /// Time to record instead of now; needs HR/Admin
+    #[schema(example = "17:30:00", value_type = Option<String>)]
+    pub time: Option<NaiveTime>,
+    /// Employee to check out; anyone but the caller needs HR/Admin
+    #[schema(example = 1000)]
+    pub employee_id: Option<u64>,
+}
+
+struct CheckInLocation {
+    lat: f64,
+    lng: f64,
+}
+
+/// Whose record a check-in/out writes, and whether it is a backfill. Recording for
+/// someone else, another day or an explicit time is an HR correction, not a self
+/// check-in, and needs `attendance.correct`.
+fn backfill_target(
+    auth: &AuthUser,
+    employee_id: Option<u64>,
+    date: Option<NaiveDate>,
+    time: Option<NaiveTime>,
+    today: NaiveDate,
+) -> actix_web::Result<(u64, bool)> {
+    let backfill = employee_id.is_some_and(|id| auth.employee_id != Some(id))
+        || date.is_some_and(|d| d != today)
+        || time.is_some();
+    if backfill {
+        auth.require_permission("attendance.correct")?;
+    }
+
+    let employee_id = employee_id
+        .or(auth.employee_id)
+        .ok_or_else(|| actix_web::error::ErrorForbidden("No employee profile"))?;
+    Ok((employee_id, backfill))
+}
+
+fn employee_not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "message": "Employee not found"
+    }))
+}
+
+#[derive(FromRow)]
+struct GeofenceSettings {
+    geofence_required: bool,
+    geofence_lat: Option<f64>,
+    geofence_lng: Option<f64>,
+    geofence_radius_m: Option<f64>,
+}
+
+impl GeofenceSettings {
+    /// The department's fence if it has one, else the company-wide one
+    fn fence(&self, company: Option<Geofence>) -> Option<Geofence> {
+        match (self.geofence_lat, self.geofence_lng, self.geofence_radius_m) {
+            (Some(lat), Some(lng), Some(radius_m)) => Some(Geofence { lat, lng, radius_m }),
+            _ => company,
+        }
+    }
+}
+
+/// Check-in endpoint.
+/// Employees flagged `geofence_required` must send a location inside their department's
+/// (or the company-wide) geofence; everyone else may omit the body.
+/// HR/Admin may pass `employee_id`/`date`/`time` to backfill a forgotten day; the
+/// geofence is not applied to backfilled records.
 #[utoipa::path(
     post,
     path = "/api/v1/attendance/check-in",
+    request_body(
+        content = Option<CheckInRequest>,
+        description = "Current location, stored with the check-in when given; optional date/time to backfill",
+        content_type = "application/json"
+    ),
     responses(
         (status = 200, description = "Checked in successfully", body = Object, example = json!({
             "message": "Checked in successfully"
         })),
-        (status = 400, description = "Already checked in today", body = Object, example = json!({
-            "message": "Already checked in today"
+        (status = 400, description = "Already checked in on that day, invalid or missing location, or outside the geofence", body = Object, example = json!({
+            "message": "Outside allowed location"
         })),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden"),
+        (status = 404, description = "Backfill for an unknown employee"),
         (status = 500, description = "Internal server error")
     ),
     security(
@@ -25,18 +299,85 @@ use sqlx::MySqlPool;
 pub async fn check_in(
     auth: AuthUser,
     pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    payload: Option<web::Json<CheckInRequest>>,
 ) -> actix_web::Result<impl Responder> {
-    let employee_id: u64 = auth
-        .employee_id
-        .ok_or_else(|| actix_web::error::ErrorForbidden("No employee profile"))?;
+    let payload = payload.map(|p| p.into_inner());
+    let date = payload.as_ref().and_then(|p| p.date);
+    let time = payload.as_ref().and_then(|p| p.time);
+    let (employee_id, backfill) = backfill_target(
+        &auth,
+        payload.as_ref().and_then(|p| p.employee_id),
+        date,
+        time,
+        chrono::Local::now().date_naive(),
+    )?;
+
+    let location = match &payload {
+        Some(p) => p.location()?,
+        None => None,
+    };
+    if let Some(loc) = &location
+        && !geo::is_valid_point(loc.lat, loc.lng)
+    {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "Invalid coordinates"
+        })));
+    }
+
+    // 1️⃣ geofence, only for employees who opted in
+    let settings = sqlx::query_as::<_, GeofenceSettings>(
+        r#"
+        SELECT e.geofence_required, d.geofence_lat, d.geofence_lng, d.geofence_radius_m
+        FROM employees e
+        LEFT JOIN departments d ON d.id = e.department_id
+        WHERE e.id = ? AND e.org_id = ?
+        "#,
+    )
+    .bind(employee_id)
+    .bind(auth.org_id)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to load geofence settings");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let Some(settings) = settings else {
+        return Ok(employee_not_found());
+    };
+
+    let fence = if settings.geofence_required && !backfill {
+        settings.fence(config.geofence)
+    } else {
+        None
+    };
+
+    if let Some(fence) = fence {
+        let Some(loc) = &location else {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "message": "Location required for check-in"
+            })));
+        };
+        if !fence.contains(loc.lat, loc.lng) {
+            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "message": "Outside allowed location"
+            })));
+        }
+    }
 
-    let result = sqlx::query!(
+    // 2️⃣ record the check-in with its location
+    let result = sqlx::query(
         r#"
-        INSERT INTO attendance (employee_id, date, check_in)
-        VALUES (?, CURDATE(), CURTIME())
+        INSERT INTO attendance (employee_id, date, check_in, check_in_lat, check_in_lng)
+        VALUES (?, COALESCE(?, CURDATE()), COALESCE(?, CURTIME()), ?, ?)
         "#,
-        employee_id
     )
+    .bind(employee_id)
+    .bind(date)
+    .bind(time)
+    .bind(location.as_ref().map(|l| l.lat))
+    .bind(location.as_ref().map(|l| l.lng))
     .execute(pool.get_ref())
     .await;
 
@@ -47,12 +388,17 @@ pub async fn check_in(
 
         Err(e) => {
             // Duplicate check-in for same day
-            if let sqlx::Error::Database(db_err) = &e {
-                if db_err.code().as_deref() == Some("23000") {
-                    return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                        "message": "Already checked in today"
-                    })));
-                }
+            if let sqlx::Error::Database(db_err) = &e
+                && db_err.code().as_deref() == Some("23000")
+            {
+                let message = if date.is_some() {
+                    "Already checked in on that day"
+                } else {
+                    "Already checked in today"
+                };
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "message": message
+                })));
             }
 
             tracing::error!(error = %e, employee_id, "Check-in failed");
@@ -63,10 +409,16 @@ pub async fn check_in(
     }
 }
 
-/// Check-out endpoint
+/// Check-out endpoint.
+/// HR/Admin may pass `employee_id`/`date`/`time` to close a forgotten day's check-in.
 #[utoipa::path(
     post,
     path = "/api/v1/attendance/check-out",
+    request_body(
+        content = Option<CheckOutRequest>,
+        description = "Optional date/time to backfill",
+        content_type = "application/json"
+    ),
     responses(
         (status = 200, description = "Checked out successfully", body = Object, example = json!({
             "message": "Checked out successfully"
@@ -76,6 +428,7 @@ pub async fn check_in(
         })),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden"),
+        (status = 404, description = "Backfill for an unknown employee"),
         (status = 500, description = "Internal server error")
     ),
     security(
@@ -86,21 +439,47 @@ pub async fn check_in(
 pub async fn check_out(
     auth: AuthUser,
     pool: web::Data<MySqlPool>,
+    payload: Option<web::Json<CheckOutRequest>>,
 ) -> actix_web::Result<impl Responder> {
-    let employee_id: u64 = auth
-        .employee_id
-        .ok_or_else(|| actix_web::error::ErrorForbidden("No employee profile"))?;
+    let date = payload.as_ref().and_then(|p| p.date);
+    let time = payload.as_ref().and_then(|p| p.time);
+    let (employee_id, backfill) = backfill_target(
+        &auth,
+        payload.as_ref().and_then(|p| p.employee_id),
+        date,
+        time,
+        chrono::Local::now().date_naive(),
+    )?;
+
+    if backfill && auth.employee_id != Some(employee_id) {
+        let exists = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM employees WHERE id = ? AND org_id = ?",
+        )
+        .bind(employee_id)
+        .bind(auth.org_id)
+        .fetch_one(pool.get_ref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, employee_id, "Failed to look up employee for check-out");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+        if exists == 0 {
+            return Ok(employee_not_found());
+        }
+    }
 
-    let result = sqlx::query!(
+    let result = sqlx::query(
         r#"
         UPDATE attendance
-        SET check_out = CURTIME()
+        SET check_out = COALESCE(?, CURTIME())
         WHERE employee_id = ?
-        AND date = CURDATE()
+        AND date = COALESCE(?, CURDATE())
         AND check_out IS NULL
         "#,
-        employee_id
     )
+    .bind(time)
+    .bind(employee_id)
+    .bind(date)
     .execute(pool.get_ref())
     .await
     .map_err(|e| {
@@ -109,8 +488,13 @@ pub async fn check_out(
     })?;
 
     if result.rows_affected() == 0 {
+        let message = if date.is_some() {
+            "No active check-in found for that day"
+        } else {
+            "No active check-in found for today"
+        };
         return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "message": "No active check-in found for today"
+            "message": message
         })));
     }
 
@@ -118,3 +502,591 @@ pub async fn check_out(
         "message": "Checked out successfully"
     })))
 }
+
+/// Employees currently on site: checked in today and not yet checked out
+#[utoipa::path(
+    get,
+    path = "/api/v1/attendance/present",
+    responses(
+        (status = 200, description = "Employees with an open check-in today", body = PresentResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Attendance"
+)]
+pub async fn present(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("attendance.present")?;
+
+    let today = chrono::Local::now().date_naive();
+
+    let data = sqlx::query_as::<_, PresentEmployee>(
+        r#"
+        SELECT a.employee_id, e.first_name, e.last_name, a.check_in
+        FROM attendance a
+        JOIN employees e ON e.id = a.employee_id
+        WHERE e.org_id = ?
+        AND a.date = ?
+        AND a.check_in IS NOT NULL
+        AND a.check_out IS NULL
+        ORDER BY a.check_in ASC
+        "#,
+    )
+    .bind(auth.org_id)
+    .bind(today)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to fetch present employees");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(PresentResponse {
+        date: today,
+        count: data.len(),
+        data,
+    }))
+}
+
+/// Attendance history, newest day first. Employees see their own; `attendance.read`
+/// holders may pass any `employee_id` of the organization.
+#[utoipa::path(
+    get,
+    path = "/api/v1/attendance",
+    params(AttendanceQuery),
+    responses(
+        (status = 200, description = "Paginated attendance days", body = AttendanceListResponse,
+         headers(("Link" = String, description = "RFC 5988 pagination links (first, prev, next, last)"))),
+        (status = 400, description = "Invalid from_date or to_date"),
+        (status = 403, description = "No employee profile, or another employee's history without attendance.read")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Attendance"
+)]
+pub async fn list_attendance(
+    auth: AuthUser,
+    req: HttpRequest,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    query: web::Query<AttendanceQuery>,
+) -> actix_web::Result<impl Responder> {
+    let employee_id = target_employee(&auth, query.employee_id)?;
+
+    let from_date = date_param::parse("from_date", query.from_date.as_deref())?;
+    let to_date = date_param::parse("to_date", query.to_date.as_deref())?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(31).clamp(1, 100);
+    let offset = (page - 1) * per_page;
+
+    // NULL bounds are open ends
+    let where_sql = r#"
+        FROM attendance a
+        JOIN employees e ON e.id = a.employee_id
+        WHERE e.org_id = ?
+        AND a.employee_id = ?
+        AND (? IS NULL OR a.date >= ?)
+        AND (? IS NULL OR a.date <= ?)
+    "#;
+
+    let total = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) {}", where_sql))
+        .bind(auth.org_id)
+        .bind(employee_id)
+        .bind(from_date)
+        .bind(from_date)
+        .bind(to_date)
+        .bind(to_date)
+        .fetch_one(pool.get_ref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, employee_id, "Failed to count attendance");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+    let rows = sqlx::query_as::<_, Attendance>(&format!(
+        "SELECT a.id, a.employee_id, a.date, a.check_in, a.check_out {} \
+         ORDER BY a.date DESC, a.id DESC LIMIT ? OFFSET ?",
+        where_sql
+    ))
+    .bind(auth.org_id)
+    .bind(employee_id)
+    .bind(from_date)
+    .bind(from_date)
+    .bind(to_date)
+    .bind(to_date)
+    .bind(per_page as i64)
+    .bind(offset as i64)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to fetch attendance");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let link = link_header(
+        &config.public_base_url,
+        &req,
+        page as u64,
+        per_page as u64,
+        total,
+    );
+
+    Ok(HttpResponse::Ok()
+        .insert_header((LINK, link))
+        .json(AttendanceListResponse {
+            data: rows
+                .into_iter()
+                .map(|a| AttendanceRecord::new(a, config.attendance_overnight_shifts))
+                .collect(),
+            page,
+            per_page,
+            total,
+        }))
+}
+
+/// Monthly hours and forgotten check-outs for one employee
+#[utoipa::path(
+    get,
+    path = "/api/v1/attendance/summary",
+    params(AttendanceSummaryQuery),
+    responses(
+        (status = 200, description = "Totals for the month", body = AttendanceSummary),
+        (status = 400, description = "month is not YYYY-MM"),
+        (status = 403, description = "No employee profile, or another employee's summary without attendance.read")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Attendance"
+)]
+pub async fn attendance_summary(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    query: web::Query<AttendanceSummaryQuery>,
+) -> actix_web::Result<impl Responder> {
+    let employee_id = target_employee(&auth, query.employee_id)?;
+
+    let start = date_param::parse_month("month", &query.month)?;
+    let end = start
+        .checked_add_months(chrono::Months::new(1))
+        .ok_or_else(|| actix_web::error::ErrorBadRequest("month out of range"))?;
+
+    let rows = sqlx::query_as::<_, Attendance>(
+        r#"
+        SELECT a.id, a.employee_id, a.date, a.check_in, a.check_out
+        FROM attendance a
+        JOIN employees e ON e.id = a.employee_id
+        WHERE e.org_id = ?
+        AND a.employee_id = ?
+        AND a.date >= ?
+        AND a.date < ?
+        ORDER BY a.date
+        "#,
+    )
+    .bind(auth.org_id)
+    .bind(employee_id)
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to fetch attendance for summary");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let today = chrono::Local::now().date_naive();
+    let mut days_present = 0;
+    let mut total_hours = 0.0;
+    let mut missing_checkout_dates = Vec::new();
+
+    for a in rows.iter().filter(|a| a.check_in.is_some()) {
+        days_present += 1;
+        total_hours += hours_worked(a.check_in, a.check_out, config.attendance_overnight_shifts)
+            .unwrap_or_default();
+        if a.check_out.is_none() && a.date < today {
+            missing_checkout_dates.push(a.date.to_string());
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(AttendanceSummary {
+        employee_id,
+        month: start.format("%Y-%m").to_string(),
+        days_present,
+        total_hours: (total_hours * 100.0_f64).round() / 100.0,
+        missing_checkouts: missing_checkout_dates.len() as u32,
+        missing_checkout_dates,
+    }))
+}
+
+/// Correct a forgotten or wrong check-in/check-out (HR)
+#[utoipa::path(
+    put,
+    path = "/api/v1/attendance/{attendance_id}",
+    params(
+        ("attendance_id", Path, description = "Attendance record ID")
+    ),
+    request_body = CorrectAttendance,
+    responses(
+        (status = 200, description = "Record corrected; the corrector is stored and audited", body = AttendanceRecord),
+        (status = 400, description = "check_out before check_in", body = Object, example = json!({
+            "message": "check_out cannot be before check_in"
+        })),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Attendance record not found"),
+        (status = 409, description = "The employee already has a record on that date")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Attendance"
+)]
+pub async fn correct_attendance(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    path: web::Path<u64>,
+    body: web::Json<CorrectAttendance>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("attendance.correct")?;
+
+    let attendance_id = path.into_inner();
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, attendance_id, "Failed to start attendance correction");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let current = sqlx::query_as::<_, Attendance>(
+        r#"
+        SELECT a.id, a.employee_id, a.date, a.check_in, a.check_out
+        FROM attendance a
+        JOIN employees e ON e.id = a.employee_id
+        WHERE a.id = ? AND e.org_id = ?
+        FOR UPDATE
+        "#,
+    )
+    .bind(attendance_id)
+    .bind(auth.org_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, attendance_id, "Failed to fetch attendance");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let Some(current) = current else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "message": "Attendance record not found"
+        })));
+    };
+
+    let corrected = Attendance {
+        id: current.id,
+        employee_id: current.employee_id,
+        date: body.date.unwrap_or(current.date),
+        check_in: body.check_in.or(current.check_in),
+        check_out: body.check_out.or(current.check_out),
+    };
+
+    if let (Some(check_in), Some(check_out)) = (corrected.check_in, corrected.check_out)
+        && check_out < check_in && !config.attendance_overnight_shifts
+    {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "check_out cannot be before check_in"
+        })));
+    }
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE attendance
+        SET date = ?, check_in = ?, check_out = ?, corrected_by = ?, corrected_at = NOW()
+        WHERE id = ?
+        "#,
+    )
+    .bind(corrected.date)
+    .bind(corrected.check_in)
+    .bind(corrected.check_out)
+    .bind(auth.user_id)
+    .bind(attendance_id)
+    .execute(&mut *tx)
+    .await;
+
+    match updated {
+        Ok(_) => {}
+        // one record per employee and day
+        Err(sqlx::Error::Database(db_err)) if db_err.code().as_deref() == Some("23000") => {
+            return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                "message": "The employee already has an attendance record on that date"
+            })));
+        }
+        Err(e) => {
+            tracing::error!(error = %e, attendance_id, "Failed to correct attendance");
+            return Err(actix_web::error::ErrorInternalServerError("Internal Server Error"));
+        }
+    }
+
+    audit::record(
+        &mut *tx,
+        AuditEntry {
+            org_id: auth.org_id,
+            actor_user_id: Some(auth.user_id),
+            entity: "attendance",
+            entity_id: Some(attendance_id),
+            action: "correct",
+            details: Some(serde_json::json!({
+                "before": {
+                    "date": current.date,
+                    "check_in": current.check_in,
+                    "check_out": current.check_out
+                },
+                "after": {
+                    "date": corrected.date,
+                    "check_in": corrected.check_in,
+                    "check_out": corrected.check_out
+                }
+            })),
+        },
+    )
+    .await?;
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, attendance_id, "Failed to commit attendance correction");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(AttendanceRecord::new(
+        corrected,
+        config.attendance_overnight_shifts,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::role::Role;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn explicit_today_is_not_a_backfill() {
+        let employee = AuthUser::for_test(Role::Employee, Some(1001), 1);
+        let today = day("2026-10-16");
+
+        assert_eq!(
+            backfill_target(&employee, None, Some(today), None, today).unwrap(),
+            (1001, false)
+        );
+        assert_eq!(
+            backfill_target(&employee, Some(1001), None, None, today).unwrap(),
+            (1001, false)
+        );
+    }
+
+    #[test]
+    fn employees_cannot_backfill() {
+        let employee = AuthUser::for_test(Role::Employee, Some(1001), 1);
+        let today = day("2026-10-16");
+        let nine = NaiveTime::from_hms_opt(9, 0, 0);
+
+        assert!(backfill_target(&employee, None, Some(day("2026-10-15")), None, today).is_err());
+        assert!(backfill_target(&employee, None, None, nine, today).is_err());
+        assert!(backfill_target(&employee, Some(1002), None, None, today).is_err());
+    }
+
+    #[test]
+    fn hr_backfills_for_another_employee() {
+        let hr = AuthUser::for_test(Role::Hr, Some(5), 1);
+        let today = day("2026-10-16");
+
+        assert_eq!(
+            backfill_target(&hr, Some(1002), Some(today), None, today).unwrap(),
+            (1002, true)
+        );
+        assert_eq!(
+            backfill_target(&hr, None, Some(day("2026-10-15")), None, today).unwrap(),
+            (5, true)
+        );
+    }
+
+    #[test]
+    fn users_without_a_profile_must_name_the_employee() {
+        let admin = AuthUser::for_test(Role::Admin, None, 1);
+        let today = day("2026-10-16");
+
+        assert!(backfill_target(&admin, None, None, None, today).is_err());
+        assert_eq!(
+            backfill_target(&admin, Some(1002), None, None, today).unwrap(),
+            (1002, true)
+        );
+    }
+
+    async fn attendance_row(
+        pool: &MySqlPool,
+        employee_id: u64,
+        date: NaiveDate,
+        check_in: &str,
+        check_out: Option<&str>,
+    ) {
+        sqlx::query(
+            "INSERT INTO attendance (employee_id, date, check_in, check_out) VALUES (?, ?, ?, ?)",
+        )
+        .bind(employee_id)
+        .bind(date)
+        .bind(check_in)
+        .bind(check_out)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn only_open_check_ins_of_today_are_present() {
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let hr = test_support::user(&pool, org, None, Role::Hr).await;
+        let today = chrono::Local::now().date_naive();
+
+        let checked_in = test_support::employee(&pool, org).await;
+        attendance_row(&pool, checked_in, today, "09:00:00", None).await;
+        let checked_out = test_support::employee(&pool, org).await;
+        attendance_row(&pool, checked_out, today, "08:30:00", Some("12:00:00")).await;
+        // never checked out yesterday: not in the building today
+        let forgot = test_support::employee(&pool, org).await;
+        attendance_row(&pool, forgot, today.pred_opt().unwrap(), "09:00:00", None).await;
+        test_support::employee(&pool, org).await; // absent
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/attendance/present", web::get().to(present)),
+        )
+        .await;
+        let res = call_service(
+            &app,
+            TestRequest::get()
+                .uri("/attendance/present")
+                .insert_header(hr.bearer())
+                .to_request(),
+        )
+        .await;
+
+        let body: serde_json::Value = read_body_json(res).await;
+        assert_eq!(body["count"], 1);
+        assert_eq!(body["data"][0]["employee_id"], checked_in);
+        assert_eq!(body["data"][0]["check_in"], "09:00:00");
+    }
+
+    const OFFICE: Geofence = Geofence {
+        lat: 23.8103,
+        lng: 90.4125,
+        radius_m: 200.0,
+    };
+
+    #[test]
+    fn department_fence_takes_precedence() {
+        let settings = GeofenceSettings {
+            geofence_required: true,
+            geofence_lat: Some(51.5074),
+            geofence_lng: Some(-0.1278),
+            geofence_radius_m: Some(50.0),
+        };
+
+        let fence = settings.fence(Some(OFFICE)).unwrap();
+        assert_eq!(
+            (fence.lat, fence.lng, fence.radius_m),
+            (51.5074, -0.1278, 50.0)
+        );
+    }
+
+    #[test]
+    fn company_fence_is_the_fallback() {
+        let settings = GeofenceSettings {
+            geofence_required: true,
+            geofence_lat: Some(51.5074),
+            geofence_lng: None,
+            geofence_radius_m: None,
+        };
+
+        let fence = settings.fence(Some(OFFICE)).unwrap();
+        assert_eq!((fence.lat, fence.lng), (OFFICE.lat, OFFICE.lng));
+        assert!(settings.fence(None).is_none());
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn check_ins_must_be_inside_the_fence() {
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::http::StatusCode;
+        use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let on_site = test_support::employee(&pool, org).await;
+        sqlx::query("UPDATE employees SET geofence_required = TRUE WHERE id = ?")
+            .bind(on_site)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let remote = test_support::employee(&pool, org).await;
+        let on_site_user = test_support::user(&pool, org, Some(on_site), Role::Employee).await;
+        let remote_user = test_support::user(&pool, org, Some(remote), Role::Employee).await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::for_test(&[(
+                    "GEOFENCE",
+                    "23.8103,90.4125,200",
+                )])))
+                .route("/attendance/check-in", web::post().to(check_in)),
+        )
+        .await;
+        let check_in_at = |user: &test_support::TestUser, lat: f64, lng: f64| {
+            TestRequest::post()
+                .uri("/attendance/check-in")
+                .insert_header(user.bearer())
+                .set_json(serde_json::json!({"lat": lat, "lng": lng}))
+                .to_request()
+        };
+        // about 1 km north of the office
+        let (far_lat, far_lng) = (23.8193, 90.4125);
+
+        let res = call_service(&app, check_in_at(&on_site_user, far_lat, far_lng)).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = read_body_json(res).await;
+        assert_eq!(body["message"], "Outside allowed location");
+
+        let res = call_service(&app, check_in_at(&on_site_user, 23.8110, 90.4127)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let (lat, lng): (Option<f64>, Option<f64>) = sqlx::query_as(
+            "SELECT check_in_lat, check_in_lng FROM attendance WHERE employee_id = ?",
+        )
+        .bind(on_site)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((lat, lng), (Some(23.8110), Some(90.4127)));
+
+        // the fence is opt-in: remote employees check in from anywhere
+        let res = call_service(&app, check_in_at(&remote_user, far_lat, far_lng)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
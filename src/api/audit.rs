@@ -0,0 +1,328 @@
+use crate::auth::auth::AuthUser;
+use crate::config::Config;
+use crate::utils::csv_export::stream_csv;
+use crate::utils::date_param;
+use crate::utils::db_utils::{SqlValue, bind_values};
+use crate::utils::pagination::link_header;
+use crate::utils::report_limit;
+use actix_web::{HttpRequest, HttpResponse, Responder, http::header::LINK, web};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, IntoParams, ToSchema)]
+pub struct AuditQuery {
+    #[schema(example = 1)]
+    pub actor_user_id: Option<u64>,
+    /// e.g. `leave_request`, `payroll`, `user`
+    #[schema(example = "leave_request")]
+    pub entity: Option<String>,
+    #[schema(example = 55)]
+    pub entity_id: Option<u64>,
+    #[schema(example = "approve")]
+    pub action: Option<String>,
+    /// Events on or after this day (`YYYY-MM-DD`)
+    #[schema(example = "2026-01-01", format = "date")]
+    pub from: Option<String>,
+    /// Events on or before this day (`YYYY-MM-DD`)
+    #[schema(example = "2026-01-31", format = "date")]
+    pub to: Option<String>,
+    #[schema(example = 1)]
+    pub page: Option<u32>,
+    #[schema(example = 50)]
+    pub per_page: Option<u32>,
+}
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct AuditEvent {
+    #[schema(example = 9001)]
+    pub id: u64,
+    #[schema(example = 1, nullable = true)]
+    pub actor_user_id: Option<u64>,
+    #[schema(example = "leave_request")]
+    pub entity: String,
+    #[schema(example = 55, nullable = true)]
+    pub entity_id: Option<u64>,
+    #[schema(example = "approve")]
+    pub action: String,
+    /// Action specific context, JSON encoded
+    #[schema(example = "{\"days\":3}", nullable = true)]
+    pub details: Option<String>,
+    #[schema(value_type = String, format = "date-time")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AuditListResponse {
+    pub data: Vec<AuditEvent>,
+    pub page: u32,
+    pub per_page: u32,
+    pub total: i64,
+}
+
+/// `details` is a JSON column; read it back as text
+const AUDIT_COLUMNS: &str =
+    "id, actor_user_id, entity, entity_id, action, CAST(details AS CHAR) AS details, created_at";
+
+/// WHERE clause and bindings for the audit filters, shared by the search and export endpoints
+fn audit_filters(org_id: u64, query: &AuditQuery) -> actix_web::Result<(String, Vec<SqlValue>)> {
+    // always scoped to the caller's organization
+    let mut conditions = vec!["org_id = ?"];
+    let mut args = vec![SqlValue::I64(org_id as i64)];
+
+    if let Some(actor) = query.actor_user_id {
+        conditions.push("actor_user_id = ?");
+        args.push(SqlValue::I64(actor as i64));
+    }
+
+    if let Some(entity) = &query.entity {
+        conditions.push("entity = ?");
+        args.push(SqlValue::String(entity.clone()));
+    }
+
+    if let Some(entity_id) = query.entity_id {
+        conditions.push("entity_id = ?");
+        args.push(SqlValue::I64(entity_id as i64));
+    }
+
+    if let Some(action) = &query.action {
+        conditions.push("action = ?");
+        args.push(SqlValue::String(action.clone()));
+    }
+
+    if let Some(from) = date_param::parse("from", query.from.as_deref())? {
+        conditions.push("created_at >= ?");
+        args.push(SqlValue::DateTime(from.and_hms_opt(0, 0, 0).unwrap()));
+    }
+
+    // `to` is inclusive: everything before the start of the next day
+    if let Some(to) = date_param::parse("to", query.to.as_deref())? {
+        conditions.push("created_at < ?");
+        let next_day = (to + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap();
+        args.push(SqlValue::DateTime(next_day));
+    }
+
+    Ok((format!("WHERE {}", conditions.join(" AND ")), args))
+}
+
+/// Search the audit log, newest first (Admin)
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Paginated audit events, newest first", body = AuditListResponse,
+         headers(("Link" = String, description = "RFC 5988 pagination links (first, prev, next, last)"))),
+        (status = 400, description = "Malformed date"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin"
+)]
+pub async fn search_audit(
+    auth: AuthUser,
+    req: HttpRequest,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    query: web::Query<AuditQuery>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("audit.read")?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(50).clamp(1, 200);
+    let offset = (page - 1) * per_page;
+
+    let (where_sql, args) = audit_filters(auth.org_id, &query)?;
+
+    let count_sql = format!("SELECT COUNT(*) FROM audit_log {}", where_sql);
+    let (total,) = bind_values(sqlx::query_as::<_, (i64,)>(&count_sql), args.clone())
+        .fetch_one(pool.get_ref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to count audit events");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+    let data_sql = format!(
+        "SELECT {} FROM audit_log {} ORDER BY created_at DESC, id DESC LIMIT ? OFFSET ?",
+        AUDIT_COLUMNS, where_sql
+    );
+    let data = bind_values(sqlx::query_as::<_, AuditEvent>(&data_sql), args)
+        .bind(per_page as i64)
+        .bind(offset as i64)
+        .fetch_all(pool.get_ref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to fetch audit events");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+    let link = link_header(
+        &config.public_base_url,
+        &req,
+        page as u64,
+        per_page as u64,
+        total,
+    );
+
+    Ok(HttpResponse::Ok()
+        .insert_header((LINK, link))
+        .json(AuditListResponse {
+            data,
+            page,
+            per_page,
+            total,
+        }))
+}
+
+/// Export the filtered audit log as CSV (Admin)
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit/export",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "CSV file, streamed newest first; ends with a `# truncated` line if the row cap was hit", content_type = "text/csv", body = String),
+        (status = 400, description = "Malformed date"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 503, description = "Too many reports running (`REPORT_MAX_CONCURRENT`)")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin"
+)]
+pub async fn export_audit(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    query: web::Query<AuditQuery>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("audit.read")?;
+
+    let (where_sql, args) = audit_filters(auth.org_id, &query)?;
+    let permit = report_limit::try_acquire()?;
+
+    Ok(stream_csv::<AuditEvent, _>(
+        permit,
+        pool.get_ref().clone(),
+        "audit_log.csv",
+        format!(
+            "SELECT {} FROM audit_log {} ORDER BY created_at DESC, id DESC LIMIT ?",
+            AUDIT_COLUMNS, where_sql
+        ),
+        args,
+        &[
+            "id",
+            "actor_user_id",
+            "entity",
+            "entity_id",
+            "action",
+            "details",
+            "created_at",
+        ],
+        config.export_max_rows,
+        |a| {
+            vec![
+                a.id.to_string(),
+                a.actor_user_id.map(|v| v.to_string()).unwrap_or_default(),
+                a.entity.clone(),
+                a.entity_id.map(|v| v.to_string()).unwrap_or_default(),
+                a.action.clone(),
+                a.details.clone().unwrap_or_default(),
+                a.created_at.to_rfc3339(),
+            ]
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::audit::{self, AuditEntry};
+
+    fn query(params: serde_json::Value) -> AuditQuery {
+        serde_json::from_value(params).unwrap()
+    }
+
+    #[test]
+    fn entity_filter_stays_in_the_organization() {
+        let (where_sql, args) =
+            audit_filters(7, &query(serde_json::json!({"entity": "payroll"}))).unwrap();
+
+        assert_eq!(where_sql, "WHERE org_id = ? AND entity = ?");
+        assert_eq!(format!("{:?}", args), r#"[I64(7), String("payroll")]"#);
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn entity_filter_returns_only_matching_events() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::http::StatusCode;
+        use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+
+        let pool = test_support::pool().await;
+        let (org, other_org) = (test_support::new_org(), test_support::new_org());
+        let admin = test_support::user(&pool, org, None, Role::Admin).await;
+        for (org_id, entity, entity_id) in [
+            (org, "payroll", 1),
+            (org, "leave_request", 2),
+            (org, "payroll", 3),
+            (org, "user", 4),
+            (other_org, "payroll", 5),
+        ] {
+            audit::record(
+                &pool,
+                AuditEntry {
+                    org_id,
+                    actor_user_id: Some(admin.user_id),
+                    entity,
+                    entity_id: Some(entity_id),
+                    action: "update",
+                    details: None,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/audit", web::get().to(search_audit)),
+        )
+        .await;
+        let res = call_service(
+            &app,
+            TestRequest::get()
+                .uri("/audit?entity=payroll")
+                .insert_header(admin.bearer())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body: serde_json::Value = read_body_json(res).await;
+        assert_eq!(body["total"], 2);
+        let events: Vec<(&str, u64)> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| {
+                (
+                    e["entity"].as_str().unwrap(),
+                    e["entity_id"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        // newest first
+        assert_eq!(events, [("payroll", 3), ("payroll", 1)]);
+    }
+}
@@ -0,0 +1,495 @@
+use crate::auth::auth::AuthUser;
+use crate::model::department::Department;
+use actix_web::{HttpResponse, Responder, web};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+use std::collections::HashSet;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateDepartment {
+    #[schema(example = "Engineering")]
+    pub name: String,
+}
+
+/// Outcome of a batch insert: names inserted vs. names skipped as duplicates
+#[derive(Serialize, ToSchema)]
+pub struct BatchCreateResponse {
+    #[schema(example = json!(["Engineering", "Finance"]))]
+    pub created: Vec<String>,
+    #[schema(example = json!(["Sales"]))]
+    pub skipped: Vec<String>,
+}
+
+/// Bulk-create departments
+#[utoipa::path(
+    post,
+    path = "/api/v1/departments/batch",
+    request_body = Vec<CreateDepartment>,
+    responses(
+        (status = 200, description = "Departments created, duplicates by name skipped", body = BatchCreateResponse),
+        (status = 400, description = "Empty batch"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Department"
+)]
+pub async fn batch_create_departments(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    payload: web::Json<Vec<CreateDepartment>>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("department.create")?;
+
+    if payload.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "At least one department is required"
+        })));
+    }
+
+    let names = payload.iter().map(|item| item.name.as_str());
+    let response = batch_insert_names(pool.get_ref(), "departments", auth.org_id, names).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Inserts named rows (`departments`, `job_titles`) for the organization in one
+/// transaction. Blank names, repeats within the batch and names the organization
+/// already uses are skipped instead of failing the batch.
+pub(crate) async fn batch_insert_names<'a>(
+    pool: &MySqlPool,
+    table: &'static str,
+    org_id: u64,
+    names: impl Iterator<Item = &'a str>,
+) -> actix_web::Result<BatchCreateResponse> {
+    let (names, mut skipped) = dedupe_batch(names);
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, table, "Failed to start batch transaction");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let sql = format!("INSERT INTO {} (org_id, name) VALUES (?, ?)", table);
+    let mut created = Vec::new();
+    for name in names {
+        // the unique key decides, so concurrent batches can't both insert a name
+        match sqlx::query(&sql)
+            .bind(org_id)
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+        {
+            Ok(_) => created.push(name.to_string()),
+            Err(e) if is_duplicate_name(&e) => skipped.push(name.to_string()),
+            Err(e) => {
+                tracing::error!(error = %e, table, name, "Failed to insert batch row");
+                return Err(actix_web::error::ErrorInternalServerError(
+                    "Internal Server Error",
+                ));
+            }
+        }
+    }
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, table, "Failed to commit batch");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(BatchCreateResponse { created, skipped })
+}
+
+/// Trimmed names to insert, in order, and the ones skipped up front: blank, or a
+/// repeat within the batch ignoring case (like the column's collation)
+fn dedupe_batch<'a>(names: impl Iterator<Item = &'a str>) -> (Vec<&'a str>, Vec<String>) {
+    let mut seen = HashSet::new();
+    let mut unique = Vec::new();
+    let mut skipped = Vec::new();
+
+    for name in names.map(str::trim) {
+        if name.is_empty() || !seen.insert(name.to_lowercase()) {
+            skipped.push(name.to_string());
+        } else {
+            unique.push(name);
+        }
+    }
+
+    (unique, skipped)
+}
+
+/// true for a unique-key violation, i.e. the organization already has the name
+pub(crate) fn is_duplicate_name(e: &sqlx::Error) -> bool {
+    matches!(e, sqlx::Error::Database(db_err) if db_err.is_unique_violation())
+}
+
+/// Trimmed, non-empty name or a 400
+fn department_name(payload: &CreateDepartment) -> Result<&str, Box<HttpResponse>> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(Box::new(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "Department name is required"
+        }))));
+    }
+    Ok(name)
+}
+
+/// Another department in the org already uses this name
+async fn name_taken(
+    pool: &MySqlPool,
+    org_id: u64,
+    name: &str,
+    except_id: Option<u64>,
+) -> actix_web::Result<bool> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM departments WHERE org_id = ? AND name = ? AND id <> ? LIMIT 1)",
+    )
+    .bind(org_id)
+    .bind(name)
+    .bind(except_id.unwrap_or(0))
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, name, "Failed to check department");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })
+}
+
+fn name_conflict() -> HttpResponse {
+    HttpResponse::Conflict().json(serde_json::json!({
+        "message": "A department with this name already exists"
+    }))
+}
+
+fn not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "message": "Department not found"
+    }))
+}
+
+/// Create a department
+#[utoipa::path(
+    post,
+    path = "/api/v1/department",
+    request_body = CreateDepartment,
+    responses(
+        (status = 201, description = "Department created", body = Department),
+        (status = 400, description = "Blank name"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Name already in use", body = Object, example = json!({
+            "message": "A department with this name already exists"
+        }))
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Department"
+)]
+pub async fn create_department(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    payload: web::Json<CreateDepartment>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("department.create")?;
+
+    let name = match department_name(&payload) {
+        Ok(name) => name,
+        Err(response) => return Ok(*response),
+    };
+
+    if name_taken(pool.get_ref(), auth.org_id, name, None).await? {
+        return Ok(name_conflict());
+    }
+
+    let result = match sqlx::query("INSERT INTO departments (org_id, name) VALUES (?, ?)")
+        .bind(auth.org_id)
+        .bind(name)
+        .execute(pool.get_ref())
+        .await
+    {
+        Ok(result) => result,
+        // a concurrent request took the name after the check above
+        Err(e) if is_duplicate_name(&e) => return Ok(name_conflict()),
+        Err(e) => {
+            tracing::error!(error = %e, name, "Failed to insert department");
+            return Err(actix_web::error::ErrorInternalServerError(
+                "Internal Server Error",
+            ));
+        }
+    };
+
+    Ok(HttpResponse::Created().json(Department {
+        id: result.last_insert_id(),
+        name: name.to_string(),
+    }))
+}
+
+/// List the organization's departments
+#[utoipa::path(
+    get,
+    path = "/api/v1/department",
+    responses(
+        (status = 200, description = "All departments, by name", body = Vec<Department>)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Department"
+)]
+pub async fn list_departments(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+) -> actix_web::Result<impl Responder> {
+    let departments = sqlx::query_as::<_, Department>(
+        "SELECT id, name FROM departments WHERE org_id = ? ORDER BY name",
+    )
+    .bind(auth.org_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to list departments");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(departments))
+}
+
+/// Get a department
+#[utoipa::path(
+    get,
+    path = "/api/v1/department/{department_id}",
+    params(
+        ("department_id", Path, description = "Department ID")
+    ),
+    responses(
+        (status = 200, description = "Department found", body = Department),
+        (status = 404, description = "Department not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Department"
+)]
+pub async fn get_department(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+) -> actix_web::Result<impl Responder> {
+    let department_id = path.into_inner();
+
+    let department = sqlx::query_as::<_, Department>(
+        "SELECT id, name FROM departments WHERE id = ? AND org_id = ?",
+    )
+    .bind(department_id)
+    .bind(auth.org_id)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, department_id, "Failed to fetch department");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(match department {
+        Some(department) => HttpResponse::Ok().json(department),
+        None => not_found(),
+    })
+}
+
+/// Rename a department
+#[utoipa::path(
+    put,
+    path = "/api/v1/department/{department_id}",
+    params(
+        ("department_id", Path, description = "Department ID")
+    ),
+    request_body = CreateDepartment,
+    responses(
+        (status = 200, description = "Department updated", body = Department),
+        (status = 400, description = "Blank name"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Department not found"),
+        (status = 409, description = "Name already in use")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Department"
+)]
+pub async fn update_department(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+    payload: web::Json<CreateDepartment>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("department.update")?;
+
+    let department_id = path.into_inner();
+    let name = match department_name(&payload) {
+        Ok(name) => name,
+        Err(response) => return Ok(*response),
+    };
+
+    if name_taken(pool.get_ref(), auth.org_id, name, Some(department_id)).await? {
+        return Ok(name_conflict());
+    }
+
+    let exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM departments WHERE id = ? AND org_id = ?)",
+    )
+    .bind(department_id)
+    .bind(auth.org_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, department_id, "Failed to fetch department");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    if !exists {
+        return Ok(not_found());
+    }
+
+    match sqlx::query("UPDATE departments SET name = ? WHERE id = ? AND org_id = ?")
+        .bind(name)
+        .bind(department_id)
+        .bind(auth.org_id)
+        .execute(pool.get_ref())
+        .await
+    {
+        Ok(_) => {}
+        Err(e) if is_duplicate_name(&e) => return Ok(name_conflict()),
+        Err(e) => {
+            tracing::error!(error = %e, department_id, "Failed to update department");
+            return Err(actix_web::error::ErrorInternalServerError(
+                "Internal Server Error",
+            ));
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(Department {
+        id: department_id,
+        name: name.to_string(),
+    }))
+}
+
+/// Delete a department that no employee references
+#[utoipa::path(
+    delete,
+    path = "/api/v1/department/{department_id}",
+    params(
+        ("department_id", Path, description = "Department ID")
+    ),
+    responses(
+        (status = 200, description = "Department deleted"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Department not found"),
+        (status = 409, description = "Employees (including terminated ones) are still assigned", body = Object, example = json!({
+            "message": "Department still has 3 employee(s) assigned"
+        }))
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Department"
+)]
+pub async fn delete_department(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("department.delete")?;
+
+    let department_id = path.into_inner();
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, department_id, "Failed to start department delete transaction");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    // lock the referencing rows so no employee is moved in before the delete
+    let assigned = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM (SELECT id FROM employees WHERE org_id = ? AND department_id = ? FOR UPDATE) e",
+    )
+    .bind(auth.org_id)
+    .bind(department_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, department_id, "Failed to count department employees");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    if assigned > 0 {
+        return Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "message": format!("Department still has {} employee(s) assigned", assigned)
+        })));
+    }
+
+    let deleted = sqlx::query("DELETE FROM departments WHERE id = ? AND org_id = ?")
+        .bind(department_id)
+        .bind(auth.org_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, department_id, "Failed to delete department");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+    if deleted.rows_affected() == 0 {
+        return Ok(not_found());
+    }
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, department_id, "Failed to commit department delete");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Department deleted"
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeats_within_a_batch_are_skipped_ignoring_case() {
+        let (unique, skipped) =
+            dedupe_batch(["Sales", " sales ", "", "Finance", "SALES"].into_iter());
+
+        assert_eq!(unique, ["Sales", "Finance"]);
+        assert_eq!(skipped, ["sales", "", "SALES"]);
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn batch_skips_names_in_the_batch_and_in_the_organization() {
+        use crate::test_support;
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        sqlx::query("INSERT INTO departments (org_id, name) VALUES (?, 'Finance')")
+            .bind(org)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let names = ["Sales", "sales", "Finance", "Support"].into_iter();
+        let response = batch_insert_names(&pool, "departments", org, names)
+            .await
+            .unwrap();
+
+        assert_eq!(response.created, ["Sales", "Support"]);
+        assert_eq!(response.skipped, ["sales", "Finance"]);
+
+        // another organization may use the same names
+        let names = ["Sales", "Finance"].into_iter();
+        let other = batch_insert_names(&pool, "departments", test_support::new_org(), names)
+            .await
+            .unwrap();
+        assert_eq!(other.created, ["Sales", "Finance"]);
+    }
+}
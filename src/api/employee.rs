@@ -1,32 +1,67 @@
 use crate::{
-    model::employee::Employee,
-    utils::db_utils::{build_update_sql, execute_update},
+    api::user,
+    auth::auth::AuthUser,
+    config::Config,
+    utils::api_error::ApiError,
+    model::employee::{Employee, EmployeeNames, EmployeeStatus},
+    utils::audit::{self, AuditEntry},
+    utils::csv_export::stream_csv,
+    utils::db_utils::{SqlUpdate, SqlValue, build_update_sql, execute_update},
+    utils::email::is_valid_email,
+    utils::omit_null,
+    utils::pagination::{CountResponse, link_header},
+    utils::phone::is_valid_phone,
+    utils::photo,
+    utils::pii,
+    utils::precondition::{ensure_unmodified, if_unmodified_since, precondition_failed},
+    utils::report_limit,
+    utils::sort::SortOrder,
 };
-use actix_web::{HttpResponse, Responder, error::ErrorInternalServerError, web};
-use chrono::NaiveDate;
+use actix_web::{
+    HttpRequest, HttpResponse, Responder, http::header::LINK, web,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use moka::future::Cache;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_json::json;
 use sqlx::MySqlPool;
+use std::collections::HashMap;
+use std::time::Duration;
 use tracing::{debug, error};
 use utoipa::ToSchema;
 
+/// Columns `EMPLOYEE_LIST_SORT` and `?sort_by=` may name
+pub const SORT_COLUMNS: &[&str] =
+    &["id", "employee_code", "first_name", "last_name", "hire_date", "status"];
+
 #[derive(Deserialize, Serialize, ToSchema)]
 pub struct CreateEmployee {
-    #[schema(example = "3000", value_type = String)]
-    pub employee_code: String,
+    /// Optional when `EMPLOYEE_CODE_AUTOGEN` is on; the next sequential code is generated
+    #[schema(example = "EMP-0042", value_type = Option<String>)]
+    #[serde(default)]
+    pub employee_code: Option<String>,
     #[schema(example = "first name", value_type = String)]
     pub first_name: String,
     #[schema(example = "last name", value_type = String)]
     pub last_name: String,
     #[schema(example = "john@email.com", format = "email", value_type = String)]
     pub email: String,
+    /// Digits with an optional leading `+`
+    #[schema(example = "+8801712345678", value_type = Option<String>)]
+    #[serde(default)]
+    pub phone: Option<String>,
     #[schema(example = 1, value_type = u64 )]
     pub department_id: u64,
     #[schema(example = 2, value_type = u64 )]
     pub job_title_id: u64,
     #[schema(example = "2026-01-01", format = "date", value_type = String)]
     pub hire_date: chrono::NaiveDate,
+    /// Flat object of extra attributes, e.g. `{"blood_group": "O+"}`
+    #[schema(example = json!({"blood_group": "O+", "emergency_contact": "+8801700000000"}), value_type = Option<Object>)]
+    #[serde(default)]
+    pub custom_fields: Option<Value>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -37,6 +72,106 @@ pub struct EmployeeQuery {
     pub job_title_id: Option<u64>,
     pub status: Option<String>,
     pub search: Option<String>,
+    /// Also return soft-deleted (terminated) employees; needs `employee.read_deleted`
+    pub include_deleted: Option<bool>,
+    /// Comma-separated related names to add: `department`, `job_title`
+    pub expand: Option<String>,
+    /// Overrides `EMPLOYEE_LIST_SORT`; one of `SORT_COLUMNS`
+    pub sort_by: Option<String>,
+    /// `asc` (default when only `sort_by` is given) or `desc`
+    pub sort_order: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GetEmployeeQuery {
+    /// Also return a soft-deleted (terminated) employee; needs `employee.read_deleted`
+    pub include_deleted: Option<bool>,
+    /// Comma-separated related names to add: `department`, `job_title`
+    pub expand: Option<String>,
+}
+
+/// Department and job title names for `?expand=`, loaded once per request.
+/// `None` maps = not expanded.
+#[derive(Default)]
+struct NameLookup {
+    departments: Option<HashMap<u64, String>>,
+    job_titles: Option<HashMap<u64, String>>,
+}
+
+impl NameLookup {
+    async fn load(pool: &MySqlPool, org_id: u64, expand: Option<&str>) -> actix_web::Result<Self> {
+        let mut lookup = NameLookup::default();
+
+        for part in expand.unwrap_or_default().split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part {
+                "department" if lookup.departments.is_none() => {
+                    lookup.departments = Some(load_names(pool, "departments", org_id).await?);
+                }
+                "job_title" if lookup.job_titles.is_none() => {
+                    lookup.job_titles = Some(load_names(pool, "job_titles", org_id).await?);
+                }
+                "department" | "job_title" => {}
+                other => {
+                    return Err(ApiError::validation(
+                        "expand",
+                        format!("Unknown expand value '{}'. Allowed: department, job_title", other),
+                    )
+                    .into());
+                }
+            }
+        }
+
+        Ok(lookup)
+    }
+
+    fn names(&self, department_id: u64, job_title_id: u64) -> EmployeeNames {
+        EmployeeNames {
+            department_name: self
+                .departments
+                .as_ref()
+                .map(|m| m.get(&department_id).cloned()),
+            job_title_name: self.job_titles.as_ref().map(|m| m.get(&job_title_id).cloned()),
+        }
+    }
+}
+
+/// id -> name for one of the org's lookup tables (`table` is never user input)
+async fn load_names(
+    pool: &MySqlPool,
+    table: &'static str,
+    org_id: u64,
+) -> actix_web::Result<HashMap<u64, String>> {
+    let rows = sqlx::query_as::<_, (u64, String)>(&format!(
+        "SELECT id, name FROM {} WHERE org_id = ?",
+        table
+    ))
+    .bind(org_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!(error = %e, table, "Failed to load names for expand");
+        ApiError::internal()
+    })?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// `get_employee` body with the expanded names alongside
+#[derive(Serialize)]
+struct ExpandedEmployeeResponse {
+    #[serde(flatten)]
+    employee: EmployeeResponse,
+    #[serde(flatten)]
+    names: EmployeeNames,
+}
+
+/// Seeing soft-deleted employees is an HR matter; everyone else gets active rows only
+fn include_deleted(auth: &AuthUser, requested: Option<bool>) -> actix_web::Result<bool> {
+    if requested == Some(true) {
+        auth.require_permission("employee.read_deleted")?;
+        return Ok(true);
+    }
+    Ok(false)
 }
 
 #[derive(Serialize, ToSchema)]
@@ -67,28 +202,153 @@ pub struct EmployeeListResponse {
 #[derive(Serialize, ToSchema)]
 pub struct EmployeeResponse {
     pub id: u64,
+    #[serde(skip_serializing_if = "omit_null::skip")]
     pub employee_code: Option<String>,
+    #[serde(serialize_with = "pii::name")]
     pub first_name: String,
+    #[serde(serialize_with = "pii::opt_name", skip_serializing_if = "omit_null::skip")]
     pub last_name: Option<String>,
+    #[serde(serialize_with = "pii::email")]
     pub email: String,
+    #[serde(serialize_with = "pii::opt_phone", skip_serializing_if = "omit_null::skip")]
+    pub phone: Option<String>,
     pub department_id: u64,
     pub job_title_id: u64,
     #[schema(example = "2026-01-01", format = "date", value_type = String)]
     pub hire_date: chrono::NaiveDate,
+    #[serde(skip_serializing_if = "omit_null::skip")]
     pub status: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    #[serde(skip_serializing_if = "omit_null::skip")]
+    pub custom_fields: Option<Value>,
+}
+
+/// Distinct values currently present in the data, for UI filter dropdowns
+#[derive(Serialize, Clone, ToSchema)]
+pub struct EmployeeFilterOptions {
+    #[schema(example = json!(["active", "terminated"]))]
+    pub statuses: Vec<String>,
+    #[schema(example = json!([1, 2, 10]))]
+    pub department_ids: Vec<u64>,
+    #[schema(example = json!([3, 4]))]
+    pub job_title_ids: Vec<u64>,
 }
 
+/// Filter options change rarely; keep them per organization for a short while to spare the DISTINCT scans
+static FILTER_OPTIONS_CACHE: Lazy<Cache<u64, EmployeeFilterOptions>> = Lazy::new(|| {
+    Cache::builder()
+        .max_capacity(1_000)
+        .time_to_live(Duration::from_secs(60))
+        .build()
+});
+
+/// Request body schema only: `update_employee` reads the raw JSON
 #[derive(Deserialize, ToSchema)]
+#[allow(dead_code)]
 pub struct UpdateEmployee {
     pub employee_code: Option<String>,
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub email: Option<String>,
+    pub phone: Option<String>,
     pub department_id: Option<u64>,
     pub job_title_id: Option<u64>,
-    pub status: Option<String>,
     #[schema(example = "2026-01-01", format = "date", value_type = String)]
     pub hire_date: Option<NaiveDate>,
+    /// Monthly base salary used by `POST /payroll/generate`
+    #[schema(example = 50000.0)]
+    pub base_salary: Option<f64>,
+    /// Merged into the stored custom fields; a key set to `null` is removed
+    #[schema(example = json!({"blood_group": "A+", "locker": null}), value_type = Option<Object>)]
+    pub custom_fields: Option<Value>,
+}
+
+/// Columns `update_employee` may set directly; `custom_fields` is merged separately
+const UPDATABLE_COLUMNS: &[&str] = &[
+    "employee_code",
+    "first_name",
+    "last_name",
+    "email",
+    "phone",
+    "department_id",
+    "job_title_id",
+    "hire_date",
+    "base_salary",
+];
+
+/// Fields `update_employee` refuses outright, naming where they are changed instead
+fn reject_fixed_fields(body: &Value) -> Result<(), ApiError> {
+    // moving an employee to another organization is not an update
+    if body.get("org_id").is_some() {
+        return Err(ApiError::validation("org_id", "Field 'org_id' cannot be changed"));
+    }
+
+    // a status change is stamped, audited and disables a leaver's accounts
+    if body.get("status").is_some() {
+        return Err(ApiError::validation(
+            "status",
+            "Use PUT /employees/{id}/status to change the status",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Limits on `custom_fields`
+const MAX_CUSTOM_FIELD_KEYS: usize = 32;
+const MAX_CUSTOM_FIELD_KEY_LEN: usize = 64;
+const MAX_CUSTOM_FIELDS_BYTES: usize = 4096;
+
+/// `custom_fields` must be a flat JSON object of scalars within the size limits.
+/// `null` values are only meaningful in updates, where they remove the key.
+fn validate_custom_fields(value: &Value) -> Result<(), ApiError> {
+    let bad = |message: String| Err(ApiError::validation("custom_fields", message));
+
+    let Some(obj) = value.as_object() else {
+        return bad("custom_fields must be a JSON object".to_string());
+    };
+
+    if obj.len() > MAX_CUSTOM_FIELD_KEYS {
+        return bad(format!("custom_fields may have at most {} keys", MAX_CUSTOM_FIELD_KEYS));
+    }
+
+    if value.to_string().len() > MAX_CUSTOM_FIELDS_BYTES {
+        return bad(format!("custom_fields must be at most {} bytes", MAX_CUSTOM_FIELDS_BYTES));
+    }
+
+    for (key, v) in obj {
+        if key.is_empty() || key.len() > MAX_CUSTOM_FIELD_KEY_LEN {
+            return bad(format!(
+                "custom_fields keys must be 1 to {} characters",
+                MAX_CUSTOM_FIELD_KEY_LEN
+            ));
+        }
+        if v.is_array() || v.is_object() {
+            return bad(format!("custom_fields.{}: only scalar values are allowed", key));
+        }
+    }
+
+    Ok(())
+}
+
+/// Add a JSON merge patch of `custom_fields` to the update (or make it the whole update)
+fn merge_custom_fields(update: Option<SqlUpdate>, employee_id: i64, patch: &Value) -> SqlUpdate {
+    const MERGE: &str = "custom_fields = JSON_MERGE_PATCH(COALESCE(custom_fields, JSON_OBJECT()), ?)";
+
+    match update {
+        // SET a = ?, ... WHERE id = ?  →  SET a = ?, ..., custom_fields = ... WHERE id = ?
+        Some(mut update) => {
+            update.sql = update.sql.replacen(" WHERE ", &format!(", {} WHERE ", MERGE), 1);
+            let id = update.values.pop();
+            update.values.push(SqlValue::String(patch.to_string()));
+            update.values.extend(id);
+            update
+        }
+        None => SqlUpdate {
+            sql: format!("UPDATE employees SET {} WHERE id = ?", MERGE),
+            values: vec![SqlValue::String(patch.to_string()), SqlValue::I64(employee_id)],
+        },
+    }
 }
 
 /// Create Employee
@@ -98,10 +358,17 @@ pub struct UpdateEmployee {
     request_body = CreateEmployee,
     responses(
         (status = 200, description = "Employee created successfully", body = Object, example = json!({
-            "message": "User registered successfully"
+            "message": "User registered successfully",
+            "employee_code": "EMP-0042"
+        })),
+        (status = 400, description = "employee_code missing and generation disabled, invalid email or phone, unknown department_id or job_title_id, or invalid custom_fields", body = ApiError, example = json!({
+            "code": "validation_error", "message": "employee_code is required", "field": "employee_code"
         })),
-        (status = 500, description = "Internal server error", body = Object, example = json!({
-            "message": "Something went wrong, Contact with system admin"
+        (status = 409, description = "employee_code or email already in use", body = ApiError, example = json!({
+            "code": "duplicate", "message": "An employee with this email already exists", "field": "email"
+        })),
+        (status = 500, description = "Internal server error", body = ApiError, example = json!({
+            "code": "internal_error", "message": "Internal Server Error"
         }))
     ),
     tag = "Employee",
@@ -110,42 +377,269 @@ pub struct UpdateEmployee {
     )
 )]
 pub async fn create_employee(
-    // auth: AuthUser,
+    auth: AuthUser,
     pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
     payload: web::Json<CreateEmployee>,
-) -> impl Responder {
-    // auth.require_hr_or_admin()?;
+) -> Result<HttpResponse, ApiError> {
+    // auth.require_permission("employee.create")?;
 
-    let result = sqlx::query!(
-        r#"
-        INSERT INTO employees
-        (employee_code, first_name, last_name, email, department_id, job_title_id, hire_date)
-        VALUES (?, ?, ?, ?, ?, ?, ?)
-        "#,
-        payload.employee_code,
-        payload.first_name,
-        payload.last_name,
-        payload.email,
+    let explicit_code = payload
+        .employee_code
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+
+    if explicit_code.is_none() && !config.employee_code_autogen {
+        return Err(ApiError::validation("employee_code", "employee_code is required"));
+    }
+
+    if !is_valid_email(&payload.email) {
+        return Err(ApiError::validation("email", "Invalid email format"));
+    }
+
+    if payload.phone.as_deref().is_some_and(|p| !is_valid_phone(p)) {
+        return Err(ApiError::validation("phone", "Invalid phone format"));
+    }
+
+    let custom_fields = match &payload.custom_fields {
+        Some(fields) => {
+            validate_custom_fields(fields)?;
+            // nulls mean "no value" on create
+            let fields: serde_json::Map<String, Value> = fields
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            Some(Value::Object(fields).to_string())
+        }
+        None => None,
+    };
+
+    // a wrong id would otherwise surface as a foreign key error (or an orphaned reference)
+    ensure_reference_exists(
+        pool.get_ref(),
+        auth.org_id,
+        "departments",
+        "department_id",
         payload.department_id,
+    )
+    .await?;
+    ensure_reference_exists(
+        pool.get_ref(),
+        auth.org_id,
+        "job_titles",
+        "job_title_id",
         payload.job_title_id,
-        payload.hire_date,
     )
-    .execute(pool.get_ref())
-    .await;
+    .await?;
 
-    match result {
-        Ok(_) => HttpResponse::Ok().json(json!({
-            "message": "User registered successfully"
-        })),
-        Err(e) => {
-            error!(error = %e, "Failed to Create Employee");
-            HttpResponse::InternalServerError().json(json!({
-                "message":"Something went wrong, Contact with system admin"
-            }))
+    let pool = pool.get_ref();
+    let employee_code = insert_with_code(
+        explicit_code,
+        async || {
+            next_employee_code(
+                pool,
+                auth.org_id,
+                &config.employee_code_prefix,
+                config.employee_code_width,
+            )
+            .await
+        },
+        async |employee_code: &str| {
+            sqlx::query(
+                r#"
+                INSERT INTO employees
+                (org_id, employee_code, first_name, last_name, email, phone, department_id, job_title_id, hire_date, custom_fields)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(auth.org_id)
+            .bind(employee_code)
+            .bind(&payload.first_name)
+            .bind(&payload.last_name)
+            .bind(&payload.email)
+            .bind(&payload.phone)
+            .bind(payload.department_id)
+            .bind(payload.job_title_id)
+            .bind(payload.hire_date)
+            .bind(&custom_fields)
+            .execute(pool)
+            .await
+            .map(|_| ())
+        },
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "User registered successfully",
+        "employee_code": employee_code
+    })))
+}
+
+/// Inserts the employee under `explicit_code`, or under codes from `generate`;
+/// a generated code a concurrent create took is recomputed and retried.
+/// Returns the code the employee was stored with.
+async fn insert_with_code(
+    explicit_code: Option<&str>,
+    mut generate: impl AsyncFnMut() -> Result<String, sqlx::Error>,
+    mut insert: impl AsyncFnMut(&str) -> Result<(), sqlx::Error>,
+) -> Result<String, ApiError> {
+    let mut attempt = 0;
+
+    loop {
+        let employee_code = match explicit_code {
+            Some(code) => code.to_string(),
+            None => match generate().await {
+                Ok(code) => code,
+                Err(e) => {
+                    error!(error = %e, "Failed to generate employee code");
+                    return Err(ApiError::internal());
+                }
+            },
+        };
+
+        match insert(&employee_code).await {
+            Ok(()) => return Ok(employee_code),
+            Err(e)
+                if explicit_code.is_none()
+                    && is_duplicate_employee_code(&e)
+                    && attempt < MAX_CODE_ATTEMPTS =>
+            {
+                attempt += 1;
+                debug!(employee_code = %employee_code, attempt, "Generated employee code collided, retrying");
+            }
+            Err(e) => {
+                if let Some(field) = duplicate_employee_field(&e) {
+                    return Err(duplicate_conflict(field));
+                }
+                error!(error = %e, "Failed to Create Employee");
+                return Err(ApiError::internal());
+            }
         }
     }
 }
 
+/// How many times a generated employee code is recomputed after a unique-key collision
+const MAX_CODE_ATTEMPTS: u32 = 5;
+
+/// 400 unless `id` names a row of `table` in the organization; `field` is the
+/// request field it came from (`department_id`, `job_title_id`)
+async fn ensure_reference_exists(
+    pool: &MySqlPool,
+    org_id: u64,
+    table: &str,
+    field: &str,
+    id: u64,
+) -> Result<(), ApiError> {
+    let exists = sqlx::query_scalar::<_, bool>(&format!(
+        "SELECT EXISTS(SELECT 1 FROM {} WHERE id = ? AND org_id = ?)",
+        table
+    ))
+    .bind(id)
+    .bind(org_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        error!(error = %e, field, id, "Failed to check employee reference");
+        ApiError::internal()
+    })?;
+
+    if exists {
+        Ok(())
+    } else {
+        Err(ApiError::validation(field, format!("Unknown {}: {}", field, id)))
+    }
+}
+
+/// Same as `ensure_reference_exists` for a field of an update body; absent or null is fine
+async fn ensure_update_reference_exists(
+    pool: &MySqlPool,
+    org_id: u64,
+    body: &Value,
+    table: &str,
+    field: &str,
+) -> Result<(), ApiError> {
+    let Some(value) = body.get(field).filter(|v| !v.is_null()) else {
+        return Ok(());
+    };
+
+    match value.as_u64() {
+        Some(id) => ensure_reference_exists(pool, org_id, table, field, id).await,
+        None => Err(ApiError::validation(field, format!("Unknown {}: {}", field, value))),
+    }
+}
+
+/// Next sequential code within the organization:
+/// `prefix` + (current max numeric suffix + 1), zero padded to `width`
+async fn next_employee_code(
+    pool: &MySqlPool,
+    org_id: u64,
+    prefix: &str,
+    width: usize,
+) -> Result<String, sqlx::Error> {
+    // escape LIKE wildcards that may appear in the configured prefix
+    let pattern = format!(
+        "{}%",
+        prefix
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+
+    let current_max = sqlx::query_scalar::<_, u64>(
+        r#"
+        SELECT CAST(COALESCE(MAX(CAST(SUBSTRING(employee_code, ?) AS UNSIGNED)), 0) AS UNSIGNED)
+        FROM employees
+        WHERE org_id = ? AND employee_code LIKE ?
+        "#,
+    )
+    .bind((prefix.chars().count() + 1) as u64)
+    .bind(org_id)
+    .bind(pattern)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(format!("{}{:0width$}", prefix, current_max + 1, width = width))
+}
+
+/// true when the error is a unique-key violation on `employee_code`
+fn is_duplicate_employee_code(e: &sqlx::Error) -> bool {
+    duplicate_employee_field(e) == Some("employee_code")
+}
+
+/// Which unique employee field a duplicate-key error is about, if it is one.
+/// SQLSTATE 23000 also covers foreign-key failures, hence the message check;
+/// the key name follows "for key" in MySQL's message.
+fn duplicate_employee_field(e: &sqlx::Error) -> Option<&'static str> {
+    let sqlx::Error::Database(db_err) = e else {
+        return None;
+    };
+    let message = db_err.message();
+    if db_err.code().as_deref() != Some("23000") || !message.starts_with("Duplicate entry") {
+        return None;
+    }
+
+    let key = message.rsplit("for key").next().unwrap_or_default();
+    if key.contains("employee_code") {
+        Some("employee_code")
+    } else if key.contains("email") {
+        Some("email")
+    } else {
+        None
+    }
+}
+
+fn duplicate_conflict(field: &str) -> ApiError {
+    ApiError::conflict(
+        "duplicate",
+        format!("An employee with this {} already exists", field),
+    )
+    .with_field(field)
+}
+
 // -------------------- Handler --------------------
 
 #[utoipa::path(
@@ -157,10 +651,16 @@ pub async fn create_employee(
         ("department_id", Query, description = "Filter by department"),
         ("job_title_id", Query, description = "Filter by job title"),
         ("status", Query, description = "Filter by status"),
-        ("search", Query, description = "Search by name or email")
+        ("search", Query, description = "Search by name or email"),
+        ("include_deleted", Query, description = "Include soft-deleted employees (HR)"),
+        ("expand", Query, description = "Comma-separated: department, job_title — adds department_name / job_title_name"),
+        ("sort_by", Query, description = "id, employee_code, first_name, last_name, hire_date or status"),
+        ("sort_order", Query, description = "asc or desc")
     ),
     responses(
-        (status = 200, description = "Paginated employee list", body = EmployeeListResponse)
+        (status = 200, description = "Paginated employee list", body = EmployeeListResponse,
+         headers(("Link" = String, description = "RFC 5988 pagination links (first, prev, next, last)"))),
+        (status = 400, description = "Invalid sort_by, sort_order or expand value")
     ),
     tag = "Employee",
     security(
@@ -168,20 +668,105 @@ pub async fn create_employee(
     )
 )]
 pub async fn list_employees(
-    // auth: AuthUser, // optional auth
+    auth: AuthUser,
+    req: HttpRequest,
     pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
     query: web::Query<EmployeeQuery>,
 ) -> actix_web::Result<impl Responder> {
     // Example: only HR/Admin allowed
-    // auth.require_hr_or_admin()?;
+    // auth.require_permission("employee.read")?;
 
     let page = query.page.unwrap_or(1).max(1);
     let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
     let offset = (page - 1) * per_page;
 
-    // ---------- build WHERE clause dynamically ----------
-    let mut conditions = Vec::new();
-    let mut bindings: Vec<sqlx::types::JsonValue> = Vec::new();
+    let include_deleted = include_deleted(&auth, query.include_deleted)?;
+    let sort = requested_sort(&query, &config.employee_list_sort)?;
+    let lookup = NameLookup::load(pool.get_ref(), auth.org_id, query.expand.as_deref()).await?;
+    let (where_clause, bindings) = employee_filters(auth.org_id, &query, include_deleted);
+
+    // ---------- total count ----------
+    let total = count_matching_employees(pool.get_ref(), &where_clause, &bindings).await?;
+
+    // ---------- data query ----------
+    let data_sql = format!(
+        "SELECT * FROM employees {} {} LIMIT ? OFFSET ?",
+        where_clause,
+        sort.order_by()
+    );
+    debug!(sql = %data_sql, bindings = ?bindings, page, per_page, offset, "Fetching employees");
+
+    let mut data_query = sqlx::query_as::<_, Employee>(&data_sql);
+    for b in &bindings {
+        data_query = data_query.bind(b);
+    }
+    data_query = data_query.bind(per_page as i64).bind(offset as i64);
+
+    let mut employees = data_query.fetch_all(pool.get_ref()).await.map_err(|e| {
+        error!(error = %e, sql = %data_sql, "Failed to fetch employees");
+        ApiError::internal()
+    })?;
+
+    for e in &mut employees {
+        e.names = lookup.names(e.department_id, e.job_title_id);
+    }
+
+    let link = link_header(
+        &config.public_base_url,
+        &req,
+        page as u64,
+        per_page as u64,
+        total,
+    );
+
+    Ok(HttpResponse::Ok()
+        .insert_header((LINK, link))
+        .json(EmployeeListResponse {
+            data: employees,
+            page,
+            per_page,
+            total,
+        }))
+}
+
+/// ORDER BY from `sort_by`/`sort_order`, or the configured default when neither is given
+fn requested_sort(query: &EmployeeQuery, default: &SortOrder) -> actix_web::Result<SortOrder> {
+    if query.sort_by.is_none() && query.sort_order.is_none() {
+        return Ok(default.clone());
+    }
+
+    let column = query.sort_by.as_deref().unwrap_or("id");
+    if !SORT_COLUMNS.contains(&column) {
+        return Err(ApiError::validation(
+            "sort_by",
+            format!("Invalid sort_by. Allowed: {}", SORT_COLUMNS.join(", ")),
+        )
+        .into());
+    }
+
+    let order = query.sort_order.as_deref().unwrap_or("asc");
+    if order != "asc" && order != "desc" {
+        return Err(ApiError::validation("sort_order", "Invalid sort_order. Allowed: asc, desc").into());
+    }
+
+    Ok(SortOrder::parse(&format!("{}:{}", column, order), SORT_COLUMNS)
+        .expect("sort_by and sort_order validated above"))
+}
+
+/// WHERE clause and bindings for the employee list filters, shared by the list and count endpoints
+fn employee_filters(
+    org_id: u64,
+    query: &EmployeeQuery,
+    include_deleted: bool,
+) -> (String, Vec<sqlx::types::JsonValue>) {
+    // always scoped to the caller's organization
+    let mut conditions = vec!["org_id = ?"];
+    let mut bindings: Vec<sqlx::types::JsonValue> = vec![org_id.into()];
+
+    if !include_deleted {
+        conditions.push("deleted_at IS NULL");
+    }
 
     if let Some(department_id) = query.department_id {
         conditions.push("department_id = ?");
@@ -206,162 +791,648 @@ pub async fn list_employees(
         bindings.push(like.into());
     }
 
-    let where_clause = if conditions.is_empty() {
-        "".to_string()
-    } else {
-        format!("WHERE {}", conditions.join(" AND "))
-    };
+    (format!("WHERE {}", conditions.join(" AND ")), bindings)
+}
 
-    // ---------- total count ----------
+async fn count_matching_employees(
+    pool: &MySqlPool,
+    where_clause: &str,
+    bindings: &[sqlx::types::JsonValue],
+) -> Result<i64, ApiError> {
     let count_sql = format!("SELECT COUNT(*) as total FROM employees {}", where_clause);
     debug!(sql = %count_sql, bindings = ?bindings, "Counting employees");
 
     let mut count_query = sqlx::query_scalar::<_, i64>(&count_sql);
-    for b in &bindings {
+    for b in bindings {
         count_query = count_query.bind(b);
     }
 
-    let total = count_query.fetch_one(pool.get_ref()).await.map_err(|e| {
+    count_query.fetch_one(pool).await.map_err(|e| {
         error!(error = %e, sql = %count_sql, "Failed to count employees");
-        ErrorInternalServerError("Database error")
-    })?;
-
-    // ---------- data query ----------
-    let data_sql = format!(
-        "SELECT * FROM employees {} ORDER BY id DESC LIMIT ? OFFSET ?",
-        where_clause
-    );
-    debug!(sql = %data_sql, bindings = ?bindings, page, per_page, offset, "Fetching employees");
-
-    let mut data_query = sqlx::query_as::<_, Employee>(&data_sql);
-    for b in &bindings {
-        data_query = data_query.bind(b);
-    }
-    data_query = data_query.bind(per_page as i64).bind(offset as i64);
-
-    let employees = data_query.fetch_all(pool.get_ref()).await.map_err(|e| {
-        error!(error = %e, sql = %data_sql, "Failed to fetch employees");
-        ErrorInternalServerError("Database error")
-    })?;
-
-    Ok(HttpResponse::Ok().json(EmployeeListResponse {
-        data: employees,
-        page,
-        per_page,
-        total,
-    }))
+        ApiError::internal()
+    })
 }
 
-/// Update Employee
+/// Number of employees matching the list filters, without fetching them
 #[utoipa::path(
-    put,
-    path = "/api/v1/employees/{employee_id}",
+    get,
+    path = "/api/v1/employees/count",
     params(
-        ("employee_id", Path, description = "Employee ID")
+        ("department_id", Query, description = "Filter by department"),
+        ("job_title_id", Query, description = "Filter by job title"),
+        ("status", Query, description = "Filter by status"),
+        ("search", Query, description = "Search by name or email"),
+        ("include_deleted", Query, description = "Include soft-deleted employees (HR)")
     ),
-    request_body = UpdateEmployee,
     responses(
-        (status = 200, description = "Employee updated successfully", body = Object, example = json!({
-            "message": "Employee updated successfully"
-        })),
-        (status = 404, description = "Employee not found", body = Object, example = json!({
-            "message": "Employee not found"
-        })),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "Same number as `total` of the list endpoint with these filters", body = CountResponse)
     ),
     tag = "Employee",
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn update_employee(
+pub async fn count_employees(
+    auth: AuthUser,
     pool: web::Data<MySqlPool>,
-    path: web::Path<i64>,
-    body: web::Json<Value>,
+    query: web::Query<EmployeeQuery>,
 ) -> actix_web::Result<impl Responder> {
-    let employee_id = path.into_inner();
-
-    let update = build_update_sql("employees", &body, "id", employee_id)?;
-
-    let affected = execute_update(pool.get_ref(), update)
-        .await
-        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let include_deleted = include_deleted(&auth, query.include_deleted)?;
+    let (where_clause, bindings) = employee_filters(auth.org_id, &query, include_deleted);
+    let count = count_matching_employees(pool.get_ref(), &where_clause, &bindings).await?;
 
-    if affected == 0 {
-        return Ok(HttpResponse::NotFound().body("Employee not found"));
-    }
-
-    Ok(HttpResponse::Ok().body("Employee updated successfully"))
+    Ok(HttpResponse::Ok().json(CountResponse { count }))
 }
 
-/// Delete Employee
+/// Distinct filter values for employee list dropdowns
 #[utoipa::path(
-    delete,
-    path = "/api/v1/employees/{employee_id}",
-    params(
-        ("employee_id", Path, description = "Employee ID")
-    ),
+    get,
+    path = "/api/v1/employees/filter-options",
     responses(
-        (status = 200, description = "Successfully deleted", body = Object, example = json!({
-            "message": "Successfully deleted"
-        })),
-        (status = 404, description = "Employee not found", body = Object, example = json!({
-            "message": "Employee not found"
-        })),
-        (status = 500, description = "Internal server error", body = Object)
+        (status = 200, description = "Distinct statuses, departments and job titles in use", body = EmployeeFilterOptions),
+        (status = 500, description = "Internal server error")
     ),
     tag = "Employee",
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn delete_employee(
+pub async fn employee_filter_options(
+    auth: AuthUser,
     pool: web::Data<MySqlPool>,
-    path: web::Path<i64>,
 ) -> actix_web::Result<impl Responder> {
-    let employee_id = path.into_inner();
+    if let Some(options) = FILTER_OPTIONS_CACHE.get(&auth.org_id).await {
+        return Ok(HttpResponse::Ok().json(options));
+    }
 
-    let result = sqlx::query!(r#"DELETE FROM employees WHERE id = ?"#, employee_id)
-        .execute(pool.get_ref())
-        .await;
+    let statuses = sqlx::query_scalar::<_, String>(
+        "SELECT DISTINCT status FROM employees WHERE org_id = ? AND status IS NOT NULL ORDER BY status",
+    )
+    .bind(auth.org_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to fetch distinct employee statuses");
+        ApiError::internal()
+    })?;
 
-    match result {
-        Ok(res) => {
-            // Optional: check if a row was actually deleted
-            if res.rows_affected() == 0 {
-                return Ok(HttpResponse::NotFound().json(json!({
-                    "message": "Employee not found"
-                })));
-            }
+    let department_ids = sqlx::query_scalar::<_, u64>(
+        "SELECT DISTINCT department_id FROM employees WHERE org_id = ? AND department_id IS NOT NULL ORDER BY department_id",
+    )
+    .bind(auth.org_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to fetch distinct department ids");
+        ApiError::internal()
+    })?;
 
-            Ok(HttpResponse::Ok().json(json!({
-                "message": "Successfully deleted"
-            })))
-        }
+    let job_title_ids = sqlx::query_scalar::<_, u64>(
+        "SELECT DISTINCT job_title_id FROM employees WHERE org_id = ? AND job_title_id IS NOT NULL ORDER BY job_title_id",
+    )
+    .bind(auth.org_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to fetch distinct job title ids");
+        ApiError::internal()
+    })?;
 
-        Err(e) => {
-            error!(error = %e, employee_id, "Failed to delete employee");
+    let options = EmployeeFilterOptions {
+        statuses,
+        department_ids,
+        job_title_ids,
+    };
 
-            Ok(HttpResponse::InternalServerError().json(json!({
-                "message": "Internal Server Error"
-            })))
-        }
-    }
+    FILTER_OPTIONS_CACHE.insert(auth.org_id, options.clone()).await;
+
+    Ok(HttpResponse::Ok().json(options))
 }
 
-/// Get Employee by ID
+/// Export the employees matching the list filters as CSV (streamed, capped at `EXPORT_MAX_ROWS`)
 #[utoipa::path(
     get,
-    path = "/api/v1/employees/{employee_id}",
+    path = "/api/v1/employees/export",
     params(
-        ("employee_id", Path, description = "Employee ID")
+        ("department_id", Query, description = "Filter by department"),
+        ("job_title_id", Query, description = "Filter by job title"),
+        ("status", Query, description = "Filter by status"),
+        ("search", Query, description = "Search by name or email"),
+        ("include_deleted", Query, description = "Include soft-deleted employees (HR)")
     ),
     responses(
-        (status = 200, description = "Employee found", body = EmployeeResponse),
-        (status = 404, description = "Employee not found", body = Object, example = json!({
-            "message": "Employee not found"
-        })),
-        (status = 500, description = "Internal server error")
+        (status = 200, description = "CSV file; ends with a `# truncated` line if the row cap was hit", content_type = "text/csv", body = String),
+        (status = 403, description = "Forbidden"),
+        (status = 503, description = "Too many reports running (`REPORT_MAX_CONCURRENT`)", body = Object, example = json!({
+            "error": "too_busy"
+        }))
+    ),
+    tag = "Employee",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn export_employees(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    query: web::Query<EmployeeQuery>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("employee.export")?;
+    let include_deleted = include_deleted(&auth, query.include_deleted)?;
+    let permit = report_limit::try_acquire()?;
+
+    // same filters as the list; page/per_page do not apply
+    let (where_clause, bindings) = employee_filters(auth.org_id, &query, include_deleted);
+    let bindings = bindings
+        .into_iter()
+        .map(|b| match b {
+            Value::Number(n) => SqlValue::I64(n.as_i64().unwrap_or_default()),
+            Value::String(s) => SqlValue::String(s),
+            _ => SqlValue::Null,
+        })
+        .collect();
+
+    Ok(stream_csv::<Employee, _>(
+        permit,
+        pool.get_ref().clone(),
+        "employees.csv",
+        format!(
+            r#"
+            SELECT id, employee_code, first_name, last_name, email, phone,
+                   department_id, job_title_id, hire_date, status
+            FROM employees
+            {}
+            ORDER BY id
+            LIMIT ?
+            "#,
+            where_clause
+        ),
+        bindings,
+        &[
+            "id",
+            "employee_code",
+            "first_name",
+            "last_name",
+            "email",
+            "phone",
+            "department_id",
+            "job_title_id",
+            "hire_date",
+            "status",
+        ],
+        config.export_max_rows,
+        |e| {
+            vec![
+                e.id.to_string(),
+                e.employee_code.clone(),
+                pii::mask_name(&e.first_name),
+                pii::mask_name(&e.last_name),
+                pii::mask_email(&e.email),
+                e.phone.as_deref().map(pii::mask_phone).unwrap_or_default(),
+                e.department_id.to_string(),
+                e.job_title_id.to_string(),
+                e.hire_date.to_string(),
+                e.status.clone(),
+            ]
+        },
+    ))
+}
+
+/// Update Employee
+#[utoipa::path(
+    put,
+    path = "/api/v1/employees/{employee_id}",
+    params(
+        ("employee_id", Path, description = "Employee ID"),
+        ("If-Unmodified-Since" = Option<String>, Header, description = "Only update if the employee hasn't changed since this HTTP date")
+    ),
+    request_body = UpdateEmployee,
+    responses(
+        (status = 200, description = "Employee updated successfully", body = Object, example = json!({
+            "message": "Employee updated successfully"
+        })),
+        (status = 400, description = "Unknown field, status (see PUT /employees/{employee_id}/status), invalid value, email or phone format, unknown department_id or job_title_id, or custom_fields not a flat object within limits"),
+        (status = 404, description = "Employee not found", body = ApiError, example = json!({
+            "code": "not_found", "message": "Employee not found"
+        })),
+        (status = 409, description = "New employee_code or email already in use", body = ApiError, example = json!({
+            "code": "duplicate", "message": "An employee with this employee_code already exists", "field": "employee_code"
+        })),
+        (status = 412, description = "Employee was modified after If-Unmodified-Since", body = ApiError, example = json!({
+            "code": "precondition_failed", "message": "Resource was modified after If-Unmodified-Since"
+        })),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Employee",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_employee(
+    auth: AuthUser,
+    req: HttpRequest,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i64>,
+    body: web::Json<Value>,
+) -> actix_web::Result<impl Responder> {
+    let employee_id = path.into_inner();
+    let since = if_unmodified_since(&req);
+    let mut body = body.into_inner();
+
+    reject_fixed_fields(&body)?;
+
+    // build_update_sql takes any value, so check the address before it reaches the DB
+    if let Some(email) = body.get("email")
+        && !email.as_str().is_some_and(is_valid_email)
+    {
+        return Err(ApiError::validation("email", "Invalid email format").into());
+    }
+
+    // null clears the phone
+    if let Some(phone) = body.get("phone").filter(|p| !p.is_null())
+        && !phone.as_str().is_some_and(is_valid_phone)
+    {
+        return Err(ApiError::validation("phone", "Invalid phone format").into());
+    }
+
+    ensure_update_reference_exists(pool.get_ref(), auth.org_id, &body, "departments", "department_id")
+        .await?;
+    ensure_update_reference_exists(pool.get_ref(), auth.org_id, &body, "job_titles", "job_title_id")
+        .await?;
+
+    // custom fields are merged into the stored object rather than replacing it
+    let custom_fields = body.as_object_mut().and_then(|o| o.remove("custom_fields"));
+
+    let mut update = match custom_fields {
+        Some(patch) => {
+            validate_custom_fields(&patch)?;
+            let rest = match body.as_object() {
+                Some(o) if o.is_empty() => None,
+                _ => Some(build_update_sql("employees", &body, UPDATABLE_COLUMNS, "id", employee_id)?),
+            };
+            merge_custom_fields(rest, employee_id, &patch)
+        }
+        None => build_update_sql("employees", &body, UPDATABLE_COLUMNS, "id", employee_id)?,
+    };
+
+    update.sql.push_str(" AND org_id = ?");
+    update.values.push(SqlValue::I64(auth.org_id as i64));
+
+    if let Some(since) = since {
+        if !check_employee_unmodified(pool.get_ref(), auth.org_id, employee_id, since).await? {
+            return Err(ApiError::not_found("Employee not found").into());
+        }
+
+        // repeat the check in the UPDATE itself so a change in between is not overwritten
+        update.sql.push_str(" AND updated_at < ?");
+        update
+            .values
+            .push(SqlValue::DateTime(unmodified_bound(since)));
+    }
+
+    let affected = match execute_update(pool.get_ref(), update).await {
+        Ok(affected) => affected,
+        Err(e) => {
+            if let Some(field) = duplicate_employee_field(&e) {
+                return Err(duplicate_conflict(field).into());
+            }
+            error!(error = %e, employee_id, "Failed to update employee");
+            return Err(ApiError::internal().into());
+        }
+    };
+
+    if affected == 0 {
+        if since.is_some() {
+            return Err(precondition_failed());
+        }
+        return Err(ApiError::not_found("Employee not found").into());
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Employee updated successfully"
+    })))
+}
+
+/// Delete Employee
+/// Employees are never hard-deleted: payroll, leave and attendance keep referencing them.
+/// Linked user accounts are disabled with them; restoring doesn't re-enable them.
+const SOFT_DELETE_SQL: &str = "UPDATE employees SET deleted_at = NOW(), status = 'terminated' \
+     WHERE id = ? AND org_id = ? AND deleted_at IS NULL";
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/employees/{employee_id}",
+    params(
+        ("employee_id", Path, description = "Employee ID"),
+        ("If-Unmodified-Since" = Option<String>, Header, description = "Only delete if the employee hasn't changed since this HTTP date")
+    ),
+    responses(
+        (status = 200, description = "Employee marked terminated and hidden from lists, linked accounts disabled; restorable", body = Object, example = json!({
+            "message": "Successfully deleted"
+        })),
+        (status = 404, description = "Employee not found or already deleted", body = ApiError, example = json!({
+            "code": "not_found", "message": "Employee not found"
+        })),
+        (status = 412, description = "Employee was modified after If-Unmodified-Since", body = ApiError, example = json!({
+            "code": "precondition_failed", "message": "Resource was modified after If-Unmodified-Since"
+        })),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    tag = "Employee",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_employee(
+    auth: AuthUser,
+    req: HttpRequest,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i64>,
+) -> actix_web::Result<impl Responder> {
+    let employee_id = path.into_inner();
+    let since = if_unmodified_since(&req);
+
+    if let Some(since) = since
+        && !check_employee_unmodified(pool.get_ref(), auth.org_id, employee_id, since).await?
+    {
+        return Err(ApiError::not_found("Employee not found").into());
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        error!(error = %e, employee_id, "Failed to start employee deletion");
+        ApiError::internal()
+    })?;
+
+    let result = match since {
+        Some(since) => {
+            sqlx::query(&format!("{} AND updated_at < ?", SOFT_DELETE_SQL))
+                .bind(employee_id)
+                .bind(auth.org_id)
+                .bind(unmodified_bound(since))
+                .execute(&mut *tx)
+                .await
+        }
+        None => {
+            sqlx::query(SOFT_DELETE_SQL)
+                .bind(employee_id)
+                .bind(auth.org_id)
+                .execute(&mut *tx)
+                .await
+        }
+    };
+
+    match result {
+        Ok(res) => {
+            // Optional: check if a row was actually deleted
+            if res.rows_affected() == 0 {
+                // the row existed a moment ago, so with a precondition it was changed meanwhile
+                if since.is_some() {
+                    return Err(precondition_failed());
+                }
+                return Err(ApiError::not_found("Employee not found").into());
+            }
+
+            // a terminated employee must not keep a working login
+            user::disable_employee_users(&mut tx, auth.org_id, employee_id as u64)
+                .await
+                .map_err(|e| {
+                    error!(error = %e, employee_id, "Failed to disable linked users");
+                    ApiError::internal()
+                })?;
+
+            tx.commit().await.map_err(|e| {
+                error!(error = %e, employee_id, "Failed to commit employee deletion");
+                ApiError::internal()
+            })?;
+
+            Ok(HttpResponse::Ok().json(json!({
+                "message": "Successfully deleted"
+            })))
+        }
+
+        Err(e) => {
+            error!(error = %e, employee_id, "Failed to delete employee");
+
+            Err(ApiError::internal().into())
+        }
+    }
+}
+
+/// Bring a soft-deleted employee back as active (HR)
+#[utoipa::path(
+    post,
+    path = "/api/v1/employees/{employee_id}/restore",
+    params(
+        ("employee_id", Path, description = "Employee ID")
+    ),
+    responses(
+        (status = 200, description = "Employee restored", body = Object, example = json!({
+            "message": "Employee restored"
+        })),
+        (status = 404, description = "Employee not found", body = ApiError, example = json!({
+            "code": "not_found", "message": "Employee not found"
+        })),
+        (status = 409, description = "Employee is not deleted", body = ApiError, example = json!({
+            "code": "not_deleted", "message": "Employee is not deleted"
+        }))
+    ),
+    tag = "Employee",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn restore_employee(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("employee.restore")?;
+
+    let employee_id = path.into_inner();
+
+    let deleted_at = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+        "SELECT deleted_at FROM employees WHERE id = ? AND org_id = ?",
+    )
+    .bind(employee_id)
+    .bind(auth.org_id)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(|e| {
+        error!(error = %e, employee_id, "Failed to fetch employee for restore");
+        ApiError::internal()
+    })?;
+
+    match deleted_at {
+        None => return Err(ApiError::not_found("Employee not found").into()),
+        Some(None) => {
+            return Err(ApiError::conflict("not_deleted", "Employee is not deleted").into());
+        }
+        Some(Some(_)) => {}
+    }
+
+    sqlx::query(
+        "UPDATE employees SET deleted_at = NULL, status = 'active' \
+         WHERE id = ? AND org_id = ? AND deleted_at IS NOT NULL",
+    )
+    .bind(employee_id)
+    .bind(auth.org_id)
+    .execute(pool.get_ref())
+    .await
+    .map_err(|e| {
+        error!(error = %e, employee_id, "Failed to restore employee");
+        ApiError::internal()
+    })?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Employee restored"
+    })))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateEmployeeStatus {
+    /// active, on_leave, terminated or suspended
+    #[schema(example = "on_leave")]
+    pub status: String,
+}
+
+fn parse_status(status: &str) -> Result<EmployeeStatus, ApiError> {
+    EmployeeStatus::from_name(status).ok_or_else(|| {
+        ApiError::validation(
+            "status",
+            format!("status must be one of: {}", EmployeeStatus::NAMES.join(", ")),
+        )
+    })
+}
+
+/// Change an employee's status (HR); the time of the change is stored in `status_changed_at`
+#[utoipa::path(
+    put,
+    path = "/api/v1/employees/{employee_id}/status",
+    params(
+        ("employee_id", Path, description = "Employee ID")
+    ),
+    request_body = UpdateEmployeeStatus,
+    responses(
+        (status = 200, description = "Status changed (or already set); moving to terminated disables linked accounts", body = Object, example = json!({
+            "message": "Employee status updated", "status": "on_leave"
+        })),
+        (status = 400, description = "Unknown status", body = ApiError, example = json!({
+            "code": "validation_error", "message": "status must be one of: active, on_leave, terminated, suspended", "field": "status"
+        })),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Employee not found", body = ApiError, example = json!({
+            "code": "not_found", "message": "Employee not found"
+        })),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    tag = "Employee",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_employee_status(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+    body: web::Json<UpdateEmployeeStatus>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("employee.status")?;
+
+    let employee_id = path.into_inner();
+    let status = parse_status(&body.status)?;
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        error!(error = %e, employee_id, "Failed to start employee status change");
+        ApiError::internal()
+    })?;
+
+    let current = sqlx::query_scalar::<_, String>(
+        "SELECT status FROM employees WHERE id = ? AND org_id = ? AND deleted_at IS NULL FOR UPDATE",
+    )
+    .bind(employee_id)
+    .bind(auth.org_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| {
+        error!(error = %e, employee_id, "Failed to fetch employee status");
+        ApiError::internal()
+    })?;
+
+    let Some(current) = current else {
+        return Err(ApiError::not_found("Employee not found").into());
+    };
+
+    // repeating the current status keeps the original transition time
+    if current == status.as_str() {
+        return Ok(HttpResponse::Ok().json(json!({
+            "message": "Employee status updated",
+            "status": status.as_str()
+        })));
+    }
+
+    sqlx::query("UPDATE employees SET status = ?, status_changed_at = NOW() WHERE id = ?")
+        .bind(status.as_str())
+        .bind(employee_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!(error = %e, employee_id, "Failed to change employee status");
+            ApiError::internal()
+        })?;
+
+    // same as deleting: a terminated employee must not keep a working login
+    if status == EmployeeStatus::Terminated {
+        user::disable_employee_users(&mut tx, auth.org_id, employee_id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, employee_id, "Failed to disable linked users");
+                ApiError::internal()
+            })?;
+    }
+
+    audit::record(
+        &mut *tx,
+        AuditEntry {
+            org_id: auth.org_id,
+            actor_user_id: Some(auth.user_id),
+            entity: "employee",
+            entity_id: Some(employee_id),
+            action: "status_change",
+            details: Some(json!({
+                "from": current,
+                "to": status.as_str()
+            })),
+        },
+    )
+    .await?;
+
+    tx.commit().await.map_err(|e| {
+        error!(error = %e, employee_id, "Failed to commit employee status change");
+        ApiError::internal()
+    })?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Employee status updated",
+        "status": status.as_str()
+    })))
+}
+
+/// Get Employee by ID
+#[utoipa::path(
+    get,
+    path = "/api/v1/employees/{employee_id}",
+    params(
+        ("employee_id", Path, description = "Employee ID"),
+        ("include_deleted", Query, description = "Return the employee even if soft-deleted (HR)"),
+        ("expand", Query, description = "Comma-separated: department, job_title — adds department_name / job_title_name")
+    ),
+    responses(
+        (status = 200, description = "Employee found", body = EmployeeResponse),
+        (status = 404, description = "Employee not found", body = ApiError, example = json!({
+            "code": "not_found", "message": "Employee not found"
+        })),
+        (status = 500, description = "Internal server error")
     ),
     tag = "Employee",
     security(
@@ -369,10 +1440,14 @@ pub async fn delete_employee(
     )
 )]
 pub async fn get_employee(
+    auth: AuthUser,
     pool: web::Data<MySqlPool>,
     path: web::Path<u64>,
+    query: web::Query<GetEmployeeQuery>,
 ) -> actix_web::Result<impl Responder> {
     let employee_id: u64 = path.into_inner();
+    let include_deleted = include_deleted(&auth, query.include_deleted)?;
+    let lookup = NameLookup::load(pool.get_ref(), auth.org_id, query.expand.as_deref()).await?;
 
     let employee = sqlx::query_as!(
         EmployeeResponse,
@@ -383,26 +1458,777 @@ pub async fn get_employee(
             first_name,
             last_name,
             email,
+            phone,
             department_id,
             job_title_id,
             hire_date,
-            status
+            status,
+            custom_fields
         FROM employees
-        WHERE id = ?
+        WHERE id = ? AND org_id = ? AND (deleted_at IS NULL OR ?)
         "#,
-        employee_id
+        employee_id,
+        auth.org_id,
+        include_deleted
     )
     .fetch_optional(pool.get_ref())
     .await
     .map_err(|e| {
         tracing::error!(error = %e, employee_id, "Failed to fetch employee");
-        actix_web::error::ErrorInternalServerError("Internal Server Error")
+        ApiError::internal()
     })?;
 
     match employee {
-        Some(emp) => Ok(HttpResponse::Ok().json(emp)),
-        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
-            "message": "Employee not found"
-        }))),
+        Some(emp) => Ok(HttpResponse::Ok().json(ExpandedEmployeeResponse {
+            names: lookup.names(emp.department_id, emp.job_title_id),
+            employee: emp,
+        })),
+        None => Err(ApiError::not_found("Employee not found").into()),
+    }
+}
+
+/// Checks `If-Unmodified-Since` against the employee's `updated_at`.
+/// `Ok(false)` if the employee doesn't exist, 412 if it changed after `since`.
+async fn check_employee_unmodified(
+    pool: &MySqlPool,
+    org_id: u64,
+    employee_id: i64,
+    since: DateTime<Utc>,
+) -> actix_web::Result<bool> {
+    let updated_at = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+        "SELECT updated_at FROM employees WHERE id = ? AND org_id = ?",
+    )
+    .bind(employee_id)
+    .bind(org_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        error!(error = %e, employee_id, "Failed to fetch employee updated_at");
+        ApiError::internal()
+    })?;
+
+    match updated_at {
+        Some(updated_at) => {
+            ensure_unmodified(Some(since), updated_at)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Exclusive upper bound for `updated_at` in a guarded statement (HTTP dates are whole seconds)
+fn unmodified_bound(since: DateTime<Utc>) -> chrono::NaiveDateTime {
+    (since + chrono::Duration::seconds(1)).naive_utc()
+}
+
+/// Upload an employee photo. Any common image format is accepted; it is validated,
+/// resized to fit `PHOTO_MAX_DIMENSION`, stripped of metadata and stored as JPEG.
+#[utoipa::path(
+    put,
+    path = "/api/v1/employees/{employee_id}/photo",
+    params(
+        ("employee_id", Path, description = "Employee ID")
+    ),
+    request_body(content = Vec<u8>, description = "Raw image bytes", content_type = "image/*"),
+    responses(
+        (status = 200, description = "Photo stored", body = Object, example = json!({
+            "message": "Photo updated",
+            "content_type": "image/jpeg",
+            "size": 48213
+        })),
+        (status = 400, description = "Not a decodable image", body = ApiError, example = json!({
+            "code": "validation_error", "message": "Uploaded file is not a valid image", "field": "photo"
+        })),
+        (status = 403, description = "Not your profile"),
+        (status = 404, description = "Employee not found"),
+        (status = 413, description = "Upload larger than PHOTO_MAX_UPLOAD_BYTES")
+    ),
+    tag = "Employee",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn upload_employee_photo(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    path: web::Path<u64>,
+    body: web::Bytes,
+) -> actix_web::Result<impl Responder> {
+    let employee_id = path.into_inner();
+
+    // employees may replace their own photo, HR anyone's
+    if !auth.has_permission("employee.photo") && auth.employee_id != Some(employee_id) {
+        return Err(ApiError::forbidden("not_your_profile", "Not your profile").into());
+    }
+
+    let exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM employees WHERE id = ? AND org_id = ?)",
+    )
+    .bind(employee_id)
+    .bind(auth.org_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .map_err(|e| {
+        error!(error = %e, employee_id, "Failed to look up employee");
+        ApiError::internal()
+    })?;
+
+    if !exists {
+        return Err(ApiError::not_found("Employee not found").into());
+    }
+
+    let max_dimension = config.photo_max_dimension;
+    let normalized = web::block(move || photo::normalize(&body, max_dimension)).await?;
+
+    let jpeg = match normalized {
+        Ok(jpeg) => jpeg,
+        Err(e) => {
+            debug!(error = %e, employee_id, "Rejected photo upload");
+            return Err(ApiError::validation("photo", "Uploaded file is not a valid image").into());
+        }
+    };
+
+    let size = jpeg.len();
+
+    sqlx::query(
+        r#"
+        INSERT INTO employee_photos (employee_id, content_type, data)
+        VALUES (?, ?, ?)
+        ON DUPLICATE KEY UPDATE content_type = VALUES(content_type), data = VALUES(data)
+        "#,
+    )
+    .bind(employee_id)
+    .bind(photo::PHOTO_CONTENT_TYPE)
+    .bind(jpeg)
+    .execute(pool.get_ref())
+    .await
+    .map_err(|e| {
+        error!(error = %e, employee_id, "Failed to store employee photo");
+        ApiError::internal()
+    })?;
+
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Photo updated",
+        "content_type": photo::PHOTO_CONTENT_TYPE,
+        "size": size
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{ResponseError, http::StatusCode};
+    use serde_json::json;
+
+    fn query(params: Value) -> EmployeeQuery {
+        serde_json::from_value(params).unwrap()
+    }
+
+    fn default_sort() -> SortOrder {
+        SortOrder::parse("id:asc", SORT_COLUMNS).unwrap()
+    }
+
+    #[test]
+    fn crafted_sort_by_is_400() {
+        let err = requested_sort(
+            &query(json!({"sort_by": "id; DROP TABLE employees"})),
+            &default_sort(),
+        )
+        .unwrap_err();
+        let response = err.error_response();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn unknown_sort_order_is_400() {
+        let err = requested_sort(
+            &query(json!({"sort_by": "hire_date", "sort_order": "asc, (SELECT 1)"})),
+            &default_sort(),
+        )
+        .unwrap_err();
+
+        assert_eq!(err.error_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn known_sort_is_used() {
+        let sort = requested_sort(
+            &query(json!({"sort_by": "hire_date", "sort_order": "desc"})),
+            &default_sort(),
+        )
+        .unwrap();
+
+        assert_eq!(sort.order_by(), "ORDER BY hire_date DESC, id DESC");
+    }
+
+    #[test]
+    fn status_goes_through_the_status_endpoint() {
+        assert!(!UPDATABLE_COLUMNS.contains(&"status"));
+
+        let err =
+            reject_fixed_fields(&json!({"first_name": "Ann", "status": "terminated"})).unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(err.field.as_deref(), Some("status"));
+
+        assert!(reject_fixed_fields(&json!({"org_id": 2})).is_err());
+        assert!(reject_fixed_fields(&json!({"first_name": "Ann"})).is_ok());
+    }
+
+    #[test]
+    fn id_and_unknown_columns_cannot_be_updated() {
+        assert!(!UPDATABLE_COLUMNS.contains(&"id"));
+
+        for payload in [
+            json!({"id": 2}),
+            json!({"org_id": 2}),
+            json!({"status = 'x' --": 1}),
+        ] {
+            let err =
+                build_update_sql("employees", &payload, UPDATABLE_COLUMNS, "id", 1).unwrap_err();
+            assert_eq!(err.status_code(), StatusCode::BAD_REQUEST, "{}", payload);
+        }
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn another_organizations_employee_is_not_found() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::test::{TestRequest, call_service, init_service};
+
+        let pool = test_support::pool().await;
+        let (org_a, org_b) = (test_support::new_org(), test_support::new_org());
+        let admin_a = test_support::user(&pool, org_a, None, Role::Admin).await;
+        let admin_b = test_support::user(&pool, org_b, None, Role::Admin).await;
+        let employee_b = test_support::employee(&pool, org_b).await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/employees/{employee_id}", web::get().to(get_employee)),
+        )
+        .await;
+        let get = |user: &test_support::TestUser| {
+            TestRequest::get()
+                .uri(&format!("/employees/{}", employee_b))
+                .insert_header(user.bearer())
+                .to_request()
+        };
+
+        assert_eq!(
+            call_service(&app, get(&admin_a)).await.status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            call_service(&app, get(&admin_b)).await.status(),
+            StatusCode::OK
+        );
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn stale_delete_is_412() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::http::header::HttpDate;
+        use actix_web::test::{TestRequest, call_service, init_service};
+        use std::time::{Duration, SystemTime};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let admin = test_support::user(&pool, org, None, Role::Admin).await;
+        let employee_id = test_support::employee(&pool, org).await;
+        sqlx::query("UPDATE employees SET updated_at = NOW() WHERE id = ?")
+            .bind(employee_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route(
+                    "/employees/{employee_id}",
+                    web::delete().to(delete_employee),
+                ),
+        )
+        .await;
+        let delete = |since: SystemTime| {
+            TestRequest::delete()
+                .uri(&format!("/employees/{}", employee_id))
+                .insert_header(admin.bearer())
+                .insert_header(("If-Unmodified-Since", HttpDate::from(since).to_string()))
+                .to_request()
+        };
+
+        // the client last saw the employee an hour ago, before the update
+        let stale = SystemTime::now() - Duration::from_secs(3600);
+        assert_eq!(
+            call_service(&app, delete(stale)).await.status(),
+            StatusCode::PRECONDITION_FAILED
+        );
+        let deleted_at: Option<DateTime<Utc>> =
+            sqlx::query_scalar("SELECT deleted_at FROM employees WHERE id = ?")
+                .bind(employee_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(deleted_at.is_none());
+
+        let fresh = SystemTime::now() + Duration::from_secs(60);
+        assert_eq!(
+            call_service(&app, delete(fresh)).await.status(),
+            StatusCode::OK
+        );
+    }
+
+    #[test]
+    fn null_status_is_omitted_when_enabled() {
+        let employee = EmployeeResponse {
+            id: 1,
+            employee_code: Some("EMP-001".to_string()),
+            first_name: "Ann".to_string(),
+            last_name: None,
+            email: "ann@example.com".to_string(),
+            phone: None,
+            department_id: 2,
+            job_title_id: 3,
+            hire_date: chrono::NaiveDate::from_ymd_opt(2026, 1, 5).unwrap(),
+            status: None,
+            custom_fields: None,
+        };
+
+        // the switch is process-wide, so both cases run in this one test
+        omit_null::set_enabled(true);
+        let omitted = serde_json::to_value(&employee).unwrap();
+        omit_null::set_enabled(false);
+        let kept = serde_json::to_value(&employee).unwrap();
+
+        assert!(omitted.get("status").is_none());
+        assert!(omitted.get("last_name").is_none());
+        // required fields are never dropped
+        for field in ["id", "first_name", "email", "department_id", "hire_date"] {
+            assert!(omitted.get(field).is_some(), "{}", field);
+        }
+        assert_eq!(omitted["employee_code"], "EMP-001");
+
+        assert_eq!(kept["status"], Value::Null);
+        assert_eq!(kept["last_name"], Value::Null);
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn count_matches_the_list_total() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let hr = test_support::user(&pool, org, None, Role::Hr).await;
+        let (dept_a, dept_b) = (
+            test_support::department(&pool, org).await,
+            test_support::department(&pool, org).await,
+        );
+        let title = test_support::job_title(&pool, org).await;
+        for (department, status) in [
+            (dept_a, "active"),
+            (dept_a, "active"),
+            (dept_a, "on_leave"),
+            (dept_b, "active"),
+            (dept_b, "terminated"),
+        ] {
+            test_support::employee_in(&pool, org, department, title, status).await;
+        }
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/employees", web::get().to(list_employees))
+                .route("/employees/count", web::get().to(count_employees)),
+        )
+        .await;
+
+        for filters in [
+            String::new(),
+            format!("department_id={}", dept_a),
+            "status=active".to_string(),
+            format!("department_id={}&status=active", dept_b),
+            "search=nobody-matches-this".to_string(),
+        ] {
+            let get = |path: &str| {
+                TestRequest::get()
+                    .uri(&format!("{}?per_page=1&{}", path, filters))
+                    .insert_header(hr.bearer())
+                    .to_request()
+            };
+            let list: Value = read_body_json(call_service(&app, get("/employees")).await).await;
+            let count: Value =
+                read_body_json(call_service(&app, get("/employees/count")).await).await;
+
+            assert_eq!(count["count"], list["total"], "{}", filters);
+        }
+    }
+
+    #[test]
+    fn flat_custom_fields_are_accepted() {
+        let fields = json!({"blood_group": "A+", "locker": 12, "remote": true, "old": null});
+
+        assert!(validate_custom_fields(&fields).is_ok());
+    }
+
+    #[test]
+    fn malformed_custom_fields_are_refused() {
+        let too_many: serde_json::Map<String, Value> = (0..=MAX_CUSTOM_FIELD_KEYS)
+            .map(|i| (format!("key{}", i), json!(i)))
+            .collect();
+
+        for fields in [
+            json!(["blood_group"]),
+            json!("A+"),
+            json!({"languages": ["en", "bn"]}),
+            json!({"address": {"city": "Dhaka"}}),
+            json!({"": "empty key"}),
+            json!({"k".repeat(MAX_CUSTOM_FIELD_KEY_LEN + 1): 1}),
+            json!({"notes": "x".repeat(MAX_CUSTOM_FIELDS_BYTES)}),
+            Value::Object(too_many),
+        ] {
+            let err = validate_custom_fields(&fields).unwrap_err();
+            assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+            assert_eq!(err.field.as_deref(), Some("custom_fields"), "{}", fields);
+        }
+    }
+
+    #[test]
+    fn custom_fields_merge_alone() {
+        let update = merge_custom_fields(None, 42, &json!({"locker": null}));
+
+        assert_eq!(
+            update.sql,
+            "UPDATE employees SET custom_fields = JSON_MERGE_PATCH(COALESCE(custom_fields, JSON_OBJECT()), ?) WHERE id = ?"
+        );
+        assert_eq!(
+            format!("{:?}", update.values),
+            r#"[String("{\"locker\":null}"), I64(42)]"#
+        );
+    }
+
+    #[test]
+    fn custom_fields_merge_joins_the_column_update() {
+        let rest = build_update_sql(
+            "employees",
+            &json!({"first_name": "Ann"}),
+            UPDATABLE_COLUMNS,
+            "id",
+            42,
+        )
+        .unwrap();
+        let update = merge_custom_fields(Some(rest), 42, &json!({"locker": 7}));
+
+        assert!(
+            update.sql.ends_with(
+                ", custom_fields = JSON_MERGE_PATCH(COALESCE(custom_fields, JSON_OBJECT()), ?) WHERE id = ?"
+            ),
+            "{}",
+            update.sql
+        );
+        // the patch is bound before the id, matching the placeholders
+        assert_eq!(
+            format!("{:?}", update.values),
+            r#"[String("Ann"), String("{\"locker\":7}"), I64(42)]"#
+        );
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn custom_fields_are_merged_into_the_stored_ones() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::test::{TestRequest, call_service, init_service};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let hr = test_support::user(&pool, org, None, Role::Hr).await;
+        let employee_id = test_support::employee(&pool, org).await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/employees/{employee_id}", web::put().to(update_employee)),
+        )
+        .await;
+        let update = |body: Value| {
+            TestRequest::put()
+                .uri(&format!("/employees/{}", employee_id))
+                .insert_header(hr.bearer())
+                .set_json(body)
+                .to_request()
+        };
+        let stored = async || -> Value {
+            let raw: String = sqlx::query_scalar(
+                "SELECT CAST(custom_fields AS CHAR) FROM employees WHERE id = ?",
+            )
+            .bind(employee_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            serde_json::from_str(&raw).unwrap()
+        };
+
+        let res = call_service(
+            &app,
+            update(json!({"custom_fields": {"blood_group": "A+", "locker": 12}})),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // untouched keys stay, null removes, new keys are added
+        let res = call_service(
+            &app,
+            update(json!({"first_name": "Ann", "custom_fields": {"locker": null, "remote": true}})),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(stored().await, json!({"blood_group": "A+", "remote": true}));
+
+        let res = call_service(&app, update(json!({"custom_fields": {"a": {"b": 1}}}))).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(stored().await, json!({"blood_group": "A+", "remote": true}));
+    }
+
+    /// A database error as the MySQL driver reports it
+    #[derive(Debug)]
+    struct MySqlError {
+        code: &'static str,
+        message: &'static str,
+    }
+
+    impl std::fmt::Display for MySqlError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+
+    impl std::error::Error for MySqlError {}
+
+    impl sqlx::error::DatabaseError for MySqlError {
+        fn message(&self) -> &str {
+            self.message
+        }
+
+        fn code(&self) -> Option<std::borrow::Cow<'_, str>> {
+            Some(self.code.into())
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> sqlx::error::ErrorKind {
+            sqlx::error::ErrorKind::Other
+        }
+    }
+
+    fn mysql_error(code: &'static str, message: &'static str) -> sqlx::Error {
+        sqlx::Error::Database(Box::new(MySqlError { code, message }))
+    }
+
+    fn duplicate_code() -> sqlx::Error {
+        mysql_error(
+            "23000",
+            "Duplicate entry '7-EMP0001' for key 'employees.uq_employees_org_employee_code'",
+        )
+    }
+
+    fn duplicate_email() -> sqlx::Error {
+        mysql_error(
+            "23000",
+            "Duplicate entry '7-jane@example.com' for key 'employees.uq_employees_org_email'",
+        )
+    }
+
+    #[test]
+    fn duplicate_code_key_is_employee_code() {
+        assert_eq!(
+            duplicate_employee_field(&duplicate_code()),
+            Some("employee_code")
+        );
+    }
+
+    #[test]
+    fn duplicate_email_key_is_email() {
+        assert_eq!(duplicate_employee_field(&duplicate_email()), Some("email"));
+    }
+
+    #[test]
+    fn key_name_decides_not_the_duplicated_value() {
+        let e = mysql_error(
+            "23000",
+            "Duplicate entry '7-employee_code@example.com' for key 'employees.uq_employees_org_email'",
+        );
+
+        assert_eq!(duplicate_employee_field(&e), Some("email"));
+    }
+
+    #[test]
+    fn foreign_key_failure_is_not_a_duplicate() {
+        let e = mysql_error(
+            "23000",
+            "Cannot add or update a child row: a foreign key constraint fails \
+             (`hrm`.`employees`, CONSTRAINT `employees_ibfk_1` FOREIGN KEY (`department_id`) \
+             REFERENCES `departments` (`id`))",
+        );
+
+        assert_eq!(duplicate_employee_field(&e), None);
+        assert_eq!(duplicate_employee_field(&sqlx::Error::RowNotFound), None);
+    }
+
+    /// Runs `insert_with_code` with generated codes EMP0001, EMP0002, ... and
+    /// `outcomes` as the results of successive inserts (then success);
+    /// returns the result and the codes that were tried
+    async fn insert_codes(
+        explicit_code: Option<&str>,
+        outcomes: Vec<sqlx::Error>,
+    ) -> (Result<String, ApiError>, Vec<String>) {
+        let mut generated = 0;
+        let mut outcomes = outcomes.into_iter();
+        let mut tried = Vec::new();
+
+        let result = insert_with_code(
+            explicit_code,
+            async || {
+                generated += 1;
+                Ok(format!("EMP{:04}", generated))
+            },
+            async |code: &str| {
+                tried.push(code.to_string());
+                outcomes.next().map_or(Ok(()), Err)
+            },
+        )
+        .await;
+        (result, tried)
+    }
+
+    #[actix_web::test]
+    async fn colliding_generated_code_is_recomputed() {
+        let (result, tried) = insert_codes(None, vec![duplicate_code(), duplicate_code()]).await;
+
+        assert_eq!(result.unwrap(), "EMP0003");
+        assert_eq!(tried, ["EMP0001", "EMP0002", "EMP0003"]);
+    }
+
+    #[actix_web::test]
+    async fn retries_stop_after_max_attempts() {
+        let collisions = (0..=MAX_CODE_ATTEMPTS).map(|_| duplicate_code()).collect();
+        let (result, tried) = insert_codes(None, collisions).await;
+
+        let err = result.unwrap_err();
+        assert_eq!(err.error_response().status(), StatusCode::CONFLICT);
+        assert_eq!(err.field.as_deref(), Some("employee_code"));
+        assert_eq!(tried.len(), MAX_CODE_ATTEMPTS as usize + 1);
+    }
+
+    #[actix_web::test]
+    async fn explicit_code_collision_is_not_retried() {
+        let (result, tried) = insert_codes(Some("X-1"), vec![duplicate_code()]).await;
+
+        let err = result.unwrap_err();
+        assert_eq!(err.error_response().status(), StatusCode::CONFLICT);
+        assert_eq!(err.field.as_deref(), Some("employee_code"));
+        assert_eq!(tried, ["X-1"]);
+    }
+
+    #[actix_web::test]
+    async fn duplicate_email_is_not_retried() {
+        let (result, tried) = insert_codes(None, vec![duplicate_email()]).await;
+
+        let err = result.unwrap_err();
+        assert_eq!(err.error_response().status(), StatusCode::CONFLICT);
+        assert_eq!(err.field.as_deref(), Some("email"));
+        assert_eq!(tried, ["EMP0001"]);
+    }
+
+    #[actix_web::test]
+    async fn foreign_key_failure_is_a_500() {
+        let fk = mysql_error(
+            "23000",
+            "Cannot add or update a child row: a foreign key constraint fails",
+        );
+        let (result, tried) = insert_codes(None, vec![fk]).await;
+
+        assert_eq!(
+            result.unwrap_err().error_response().status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(tried, ["EMP0001"]);
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn filter_options_are_distinct() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let hr = test_support::user(&pool, org, None, Role::Hr).await;
+        let (dept_a, dept_b) = (
+            test_support::department(&pool, org).await,
+            test_support::department(&pool, org).await,
+        );
+        let title = test_support::job_title(&pool, org).await;
+        for (department, status) in [
+            (dept_a, "active"),
+            (dept_a, "active"),
+            (dept_b, "active"),
+            (dept_b, "inactive"),
+        ] {
+            test_support::employee_in(&pool, org, department, title, status).await;
+        }
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route(
+                    "/employees/filter-options",
+                    web::get().to(employee_filter_options),
+                ),
+        )
+        .await;
+        let res = call_service(
+            &app,
+            TestRequest::get()
+                .uri("/employees/filter-options")
+                .insert_header(hr.bearer())
+                .to_request(),
+        )
+        .await;
+
+        assert_eq!(res.status(), StatusCode::OK);
+        let options: Value = read_body_json(res).await;
+        assert_eq!(options["statuses"], json!(["active", "inactive"]));
+        assert_eq!(options["department_ids"], json!([dept_a, dept_b]));
+        assert_eq!(options["job_title_ids"], json!([title]));
     }
 }
@@ -0,0 +1,142 @@
+use crate::auth::auth::AuthUser;
+use crate::utils::calendar;
+use actix_web::{HttpResponse, Responder, web};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateHoliday {
+    #[schema(example = "2026-12-16", format = "date", value_type = String)]
+    pub date: NaiveDate,
+    #[schema(example = "Victory Day")]
+    pub name: String,
+}
+
+#[derive(Deserialize, IntoParams, ToSchema)]
+pub struct HolidayQuery {
+    /// Defaults to the current year
+    #[schema(example = 2026)]
+    pub year: Option<i32>,
+}
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct HolidayResponse {
+    #[schema(example = 1)]
+    pub id: u64,
+    #[schema(example = "2026-12-16", format = "date", value_type = String)]
+    pub date: NaiveDate,
+    #[schema(example = "Victory Day")]
+    pub name: String,
+}
+
+/// Add a company holiday; leave day counts skip it (HR/Admin)
+#[utoipa::path(
+    post,
+    path = "/api/v1/holidays",
+    request_body = CreateHoliday,
+    responses(
+        (status = 201, description = "Holiday added", body = HolidayResponse),
+        (status = 400, description = "Empty name"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "A holiday already exists on that date", body = Object, example = json!({
+            "message": "A holiday already exists on that date"
+        }))
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Holiday"
+)]
+pub async fn create_holiday(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    payload: web::Json<CreateHoliday>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("holiday.manage")?;
+
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "name is required"
+        })));
+    }
+
+    let inserted = sqlx::query("INSERT INTO holidays (org_id, date, name) VALUES (?, ?, ?)")
+        .bind(auth.org_id)
+        .bind(payload.date)
+        .bind(name)
+        .execute(pool.get_ref())
+        .await;
+
+    let inserted = match inserted {
+        Ok(result) => result,
+        // unique (org_id, date)
+        Err(sqlx::Error::Database(db_err)) if db_err.code().as_deref() == Some("23000") => {
+            return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                "message": "A holiday already exists on that date"
+            })));
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to create holiday");
+            return Err(actix_web::error::ErrorInternalServerError("Internal Server Error"));
+        }
+    };
+
+    calendar::invalidate(auth.org_id).await;
+
+    Ok(HttpResponse::Created().json(HolidayResponse {
+        id: inserted.last_insert_id(),
+        date: payload.date,
+        name: name.to_string(),
+    }))
+}
+
+/// Company holidays of one year, by date (HR/Admin)
+#[utoipa::path(
+    get,
+    path = "/api/v1/holidays",
+    params(HolidayQuery),
+    responses(
+        (status = 200, description = "Holidays sorted by date", body = [HolidayResponse]),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Holiday"
+)]
+pub async fn list_holidays(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    query: web::Query<HolidayQuery>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("holiday.manage")?;
+
+    let year = query
+        .year
+        .unwrap_or_else(|| chrono::Local::now().date_naive().year());
+
+    let holidays = sqlx::query_as::<_, HolidayResponse>(
+        r#"
+        SELECT id, date, name
+        FROM holidays
+        WHERE org_id = ?
+        AND YEAR(date) = ?
+        ORDER BY date
+        "#,
+    )
+    .bind(auth.org_id)
+    .bind(year)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, year, "Failed to list holidays");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(holidays))
+}
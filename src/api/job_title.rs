@@ -0,0 +1,376 @@
+use crate::api::department::{BatchCreateResponse, batch_insert_names, is_duplicate_name};
+use crate::auth::auth::AuthUser;
+use crate::model::job_title::JobTitle;
+use actix_web::{HttpResponse, Responder, web};
+use serde::Deserialize;
+use sqlx::MySqlPool;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateJobTitle {
+    #[schema(example = "Software Engineer")]
+    pub name: String,
+}
+
+/// Bulk-create job titles
+#[utoipa::path(
+    post,
+    path = "/api/v1/job-titles/batch",
+    request_body = Vec<CreateJobTitle>,
+    responses(
+        (status = 200, description = "Job titles created, duplicates by name skipped", body = BatchCreateResponse),
+        (status = 400, description = "Empty batch"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Job Title"
+)]
+pub async fn batch_create_job_titles(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    payload: web::Json<Vec<CreateJobTitle>>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("job_title.create")?;
+
+    if payload.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "At least one job title is required"
+        })));
+    }
+
+    let names = payload.iter().map(|item| item.name.as_str());
+    let response: BatchCreateResponse =
+        batch_insert_names(pool.get_ref(), "job_titles", auth.org_id, names).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Trimmed, non-empty name or a 400
+fn job_title_name(payload: &CreateJobTitle) -> Result<&str, Box<HttpResponse>> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(Box::new(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "Job title name is required"
+        }))));
+    }
+    Ok(name)
+}
+
+/// Another job title in the org already uses this name
+async fn name_taken(
+    pool: &MySqlPool,
+    org_id: u64,
+    name: &str,
+    except_id: Option<u64>,
+) -> actix_web::Result<bool> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM job_titles WHERE org_id = ? AND name = ? AND id <> ? LIMIT 1)",
+    )
+    .bind(org_id)
+    .bind(name)
+    .bind(except_id.unwrap_or(0))
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, name, "Failed to check job title");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })
+}
+
+fn name_conflict() -> HttpResponse {
+    HttpResponse::Conflict().json(serde_json::json!({
+        "message": "A job title with this name already exists"
+    }))
+}
+
+fn not_found() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({
+        "message": "Job title not found"
+    }))
+}
+
+/// Create a job title
+#[utoipa::path(
+    post,
+    path = "/api/v1/job-title",
+    request_body = CreateJobTitle,
+    responses(
+        (status = 201, description = "Job title created", body = JobTitle),
+        (status = 400, description = "Blank name"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Name already in use", body = Object, example = json!({
+            "message": "A job title with this name already exists"
+        }))
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Job Title"
+)]
+pub async fn create_job_title(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    payload: web::Json<CreateJobTitle>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("job_title.create")?;
+
+    let name = match job_title_name(&payload) {
+        Ok(name) => name,
+        Err(response) => return Ok(*response),
+    };
+
+    if name_taken(pool.get_ref(), auth.org_id, name, None).await? {
+        return Ok(name_conflict());
+    }
+
+    let result = match sqlx::query("INSERT INTO job_titles (org_id, name) VALUES (?, ?)")
+        .bind(auth.org_id)
+        .bind(name)
+        .execute(pool.get_ref())
+        .await
+    {
+        Ok(result) => result,
+        // a concurrent request took the name after the check above
+        Err(e) if is_duplicate_name(&e) => return Ok(name_conflict()),
+        Err(e) => {
+            tracing::error!(error = %e, name, "Failed to insert job title");
+            return Err(actix_web::error::ErrorInternalServerError(
+                "Internal Server Error",
+            ));
+        }
+    };
+
+    Ok(HttpResponse::Created().json(JobTitle {
+        id: result.last_insert_id(),
+        name: name.to_string(),
+    }))
+}
+
+/// List the organization's job titles
+#[utoipa::path(
+    get,
+    path = "/api/v1/job-title",
+    responses(
+        (status = 200, description = "All job titles, by name", body = Vec<JobTitle>)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Job Title"
+)]
+pub async fn list_job_titles(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+) -> actix_web::Result<impl Responder> {
+    let job_titles = sqlx::query_as::<_, JobTitle>(
+        "SELECT id, name FROM job_titles WHERE org_id = ? ORDER BY name",
+    )
+    .bind(auth.org_id)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to list job titles");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(job_titles))
+}
+
+/// Get a job title
+#[utoipa::path(
+    get,
+    path = "/api/v1/job-title/{job_title_id}",
+    params(
+        ("job_title_id", Path, description = "Job title ID")
+    ),
+    responses(
+        (status = 200, description = "Job title found", body = JobTitle),
+        (status = 404, description = "Job title not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Job Title"
+)]
+pub async fn get_job_title(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+) -> actix_web::Result<impl Responder> {
+    let job_title_id = path.into_inner();
+
+    let job_title = sqlx::query_as::<_, JobTitle>(
+        "SELECT id, name FROM job_titles WHERE id = ? AND org_id = ?",
+    )
+    .bind(job_title_id)
+    .bind(auth.org_id)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, job_title_id, "Failed to fetch job title");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(match job_title {
+        Some(job_title) => HttpResponse::Ok().json(job_title),
+        None => not_found(),
+    })
+}
+
+/// Rename a job title
+#[utoipa::path(
+    put,
+    path = "/api/v1/job-title/{job_title_id}",
+    params(
+        ("job_title_id", Path, description = "Job title ID")
+    ),
+    request_body = CreateJobTitle,
+    responses(
+        (status = 200, description = "Job title updated", body = JobTitle),
+        (status = 400, description = "Blank name"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Job title not found"),
+        (status = 409, description = "Name already in use")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Job Title"
+)]
+pub async fn update_job_title(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+    payload: web::Json<CreateJobTitle>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("job_title.update")?;
+
+    let job_title_id = path.into_inner();
+    let name = match job_title_name(&payload) {
+        Ok(name) => name,
+        Err(response) => return Ok(*response),
+    };
+
+    if name_taken(pool.get_ref(), auth.org_id, name, Some(job_title_id)).await? {
+        return Ok(name_conflict());
+    }
+
+    let exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM job_titles WHERE id = ? AND org_id = ?)",
+    )
+    .bind(job_title_id)
+    .bind(auth.org_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, job_title_id, "Failed to fetch job title");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    if !exists {
+        return Ok(not_found());
+    }
+
+    match sqlx::query("UPDATE job_titles SET name = ? WHERE id = ? AND org_id = ?")
+        .bind(name)
+        .bind(job_title_id)
+        .bind(auth.org_id)
+        .execute(pool.get_ref())
+        .await
+    {
+        Ok(_) => {}
+        Err(e) if is_duplicate_name(&e) => return Ok(name_conflict()),
+        Err(e) => {
+            tracing::error!(error = %e, job_title_id, "Failed to update job title");
+            return Err(actix_web::error::ErrorInternalServerError(
+                "Internal Server Error",
+            ));
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(JobTitle {
+        id: job_title_id,
+        name: name.to_string(),
+    }))
+}
+
+/// Delete a job title that no employee references
+#[utoipa::path(
+    delete,
+    path = "/api/v1/job-title/{job_title_id}",
+    params(
+        ("job_title_id", Path, description = "Job title ID")
+    ),
+    responses(
+        (status = 200, description = "Job title deleted"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Job title not found"),
+        (status = 409, description = "Employees (including terminated ones) are still assigned", body = Object, example = json!({
+            "message": "Job title is still assigned to 3 employee(s)"
+        }))
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Job Title"
+)]
+pub async fn delete_job_title(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("job_title.delete")?;
+
+    let job_title_id = path.into_inner();
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, job_title_id, "Failed to start job title delete transaction");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    // lock the referencing rows so no employee is moved in before the delete
+    let assigned = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM (SELECT id FROM employees WHERE org_id = ? AND job_title_id = ? FOR UPDATE) e",
+    )
+    .bind(auth.org_id)
+    .bind(job_title_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, job_title_id, "Failed to count job title employees");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    if assigned > 0 {
+        return Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "message": format!("Job title is still assigned to {} employee(s)", assigned)
+        })));
+    }
+
+    let deleted = sqlx::query("DELETE FROM job_titles WHERE id = ? AND org_id = ?")
+        .bind(job_title_id)
+        .bind(auth.org_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, job_title_id, "Failed to delete job title");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+    if deleted.rows_affected() == 0 {
+        return Ok(not_found());
+    }
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, job_title_id, "Failed to commit job title delete");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Job title deleted"
+    })))
+}
@@ -0,0 +1,710 @@
+use crate::auth::auth::AuthUser;
+use crate::config::Config;
+use crate::utils::api_error::ApiError;
+use crate::utils::calendar::{self, business_days_between};
+use actix_web::{HttpResponse, Responder, web};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySql, MySqlPool, Transaction};
+use std::ops::RangeInclusive;
+use utoipa::{IntoParams, ToSchema};
+
+/// Years `?year=` may name
+const YEAR_RANGE: RangeInclusive<i32> = 1900..=9999;
+
+#[derive(Deserialize, IntoParams, ToSchema)]
+pub struct LeaveBalanceQuery {
+    /// HR/Admin only; defaults to the caller's own employee profile
+    #[schema(example = 1001)]
+    pub employee_id: Option<u64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LeaveBalanceResponse {
+    #[schema(example = 1001)]
+    pub employee_id: u64,
+    #[schema(example = 2026)]
+    pub year: i32,
+
+    /// Remaining plus used days, so manual adjustments count towards it
+    #[schema(example = 20)]
+    pub annual_total: i64,
+    #[schema(example = 6)]
+    pub annual_used: i64,
+    /// The stored `leave_balances` row; entitlement minus used days until one exists
+    #[schema(example = 14)]
+    pub annual_remaining: i64,
+
+    #[schema(example = 10)]
+    pub sick_total: i64,
+    #[schema(example = 2)]
+    pub sick_used: i64,
+    #[schema(example = 8)]
+    pub sick_remaining: i64,
+
+    /// Unpaid leave has no entitlement; only usage is reported
+    #[schema(example = 0)]
+    pub unpaid_used: i64,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct BulkAdjustBalance {
+    /// Employees to adjust; give either this or `department_id`
+    #[schema(example = json!([1001, 1002]))]
+    pub employee_ids: Option<Vec<u64>>,
+
+    /// Adjust every employee of this department
+    #[schema(example = 10)]
+    pub department_id: Option<u64>,
+
+    /// Days to add (negative to take away)
+    #[schema(example = 2)]
+    pub delta_days: i32,
+
+    /// Defaults to `annual`
+    #[schema(example = "annual")]
+    pub leave_type: Option<String>,
+
+    /// Defaults to the current year
+    #[schema(example = 2026)]
+    pub year: Option<i32>,
+
+    /// Stored in the balance ledger
+    #[schema(example = "Year-end bonus days")]
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BulkAdjustResponse {
+    #[schema(example = 41)]
+    pub adjusted: u64,
+
+    /// Employees without a balance row for that year/type, or whose balance would go negative
+    #[schema(example = json!([1007]))]
+    pub skipped: Vec<u64>,
+}
+
+/// Append an entry to the leave balance ledger (`leave_balance_ledger`)
+pub(crate) async fn record_balance_change(
+    tx: &mut Transaction<'static, MySql>,
+    employee_id: u64,
+    year: i32,
+    leave_type: &str,
+    delta_days: i64,
+    reason: &str,
+    actor_user_id: Option<u64>,
+) -> actix_web::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO leave_balance_ledger
+            (employee_id, year, leave_type, delta_days, reason, actor_user_id)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(employee_id)
+    .bind(year)
+    .bind(leave_type)
+    .bind(delta_days)
+    .bind(reason)
+    .bind(actor_user_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to record leave balance change");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(())
+}
+
+/// Adjust many leave balances at once (Admin)
+#[utoipa::path(
+    post,
+    path = "/api/v1/leave/balance/bulk-adjust",
+    request_body = BulkAdjustBalance,
+    responses(
+        (status = 200, description = "Balances adjusted in one transaction; every change is written to the ledger", body = BulkAdjustResponse),
+        (status = 400, description = "Invalid request", body = Object, example = json!({
+            "message": "Give either employee_ids or department_id"
+        })),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Leave"
+)]
+pub async fn bulk_adjust_balances(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    payload: web::Json<BulkAdjustBalance>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("leave.balance_adjust")?;
+
+    if payload.delta_days == 0 {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "delta_days must not be 0"
+        })));
+    }
+
+    let leave_type = payload.leave_type.as_deref().unwrap_or("annual");
+    if !matches!(leave_type, "annual" | "sick" | "unpaid") {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "Invalid leave type. Allowed: annual, sick, unpaid"
+        })));
+    }
+
+    let year = payload
+        .year
+        .unwrap_or_else(|| chrono::Local::now().date_naive().year());
+    let reason = payload.reason.as_deref().unwrap_or("bulk adjustment");
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to start balance adjustment transaction");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    // resolve targets within the caller's organization
+    let (targets, mut skipped): (Vec<u64>, Vec<u64>) =
+        match (&payload.employee_ids, payload.department_id) {
+            (Some(ids), None) if !ids.is_empty() => {
+                let placeholders = vec!["?"; ids.len()].join(", ");
+                let sql = format!(
+                    "SELECT id FROM employees WHERE org_id = ? AND id IN ({})",
+                    placeholders
+                );
+                let mut q = sqlx::query_scalar::<_, u64>(&sql).bind(auth.org_id);
+                for id in ids {
+                    q = q.bind(*id);
+                }
+                let found = q.fetch_all(&mut *tx).await.map_err(|e| {
+                    tracing::error!(error = %e, "Failed to resolve employees for balance adjustment");
+                    actix_web::error::ErrorInternalServerError("Internal Server Error")
+                })?;
+
+                let unknown = ids.iter().copied().filter(|id| !found.contains(id)).collect();
+                (found, unknown)
+            }
+            (None, Some(department_id)) => {
+                let found = sqlx::query_scalar::<_, u64>(
+                    "SELECT id FROM employees WHERE org_id = ? AND department_id = ?",
+                )
+                .bind(auth.org_id)
+                .bind(department_id)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| {
+                    tracing::error!(error = %e, department_id, "Failed to resolve department employees");
+                    actix_web::error::ErrorInternalServerError("Internal Server Error")
+                })?;
+
+                (found, Vec::new())
+            }
+            _ => {
+                return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                    "message": "Give either employee_ids or department_id"
+                })));
+            }
+        };
+
+    let mut adjusted = 0;
+
+    for employee_id in targets {
+        let result = sqlx::query(
+            r#"
+            UPDATE leave_balances
+            SET remaining_days = remaining_days + ?
+            WHERE employee_id = ?
+            AND year = ?
+            AND leave_type = ?
+            AND remaining_days + ? >= 0
+            "#,
+        )
+        .bind(payload.delta_days)
+        .bind(employee_id)
+        .bind(year)
+        .bind(leave_type)
+        .bind(payload.delta_days)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, employee_id, "Failed to adjust leave balance");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+        if result.rows_affected() == 0 {
+            skipped.push(employee_id);
+            continue;
+        }
+
+        record_balance_change(
+            &mut tx,
+            employee_id,
+            year,
+            leave_type,
+            payload.delta_days as i64,
+            reason,
+            Some(auth.user_id),
+        )
+        .await?;
+
+        adjusted += 1;
+    }
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to commit balance adjustment");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    tracing::info!(adjusted, skipped = skipped.len(), admin_id = auth.user_id, "Leave balances adjusted");
+
+    Ok(HttpResponse::Ok().json(BulkAdjustResponse { adjusted, skipped }))
+}
+
+/// Leave entitlement and usage for the current calendar year
+#[utoipa::path(
+    get,
+    path = "/api/v1/leave/balance",
+    params(LeaveBalanceQuery),
+    responses(
+        (status = 200, description = "Used days count the working days (Mon-Fri) of approved leaves, clipped to the current year. The annual balance comes from the employee's stored balance once one exists", body = LeaveBalanceResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "No employee profile, or another employee's balance without `leave.read`"),
+        (status = 404, description = "Employee not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Leave"
+)]
+pub async fn leave_balance(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    query: web::Query<LeaveBalanceQuery>,
+) -> actix_web::Result<impl Responder> {
+    let employee_id = match query.employee_id {
+        Some(id) if auth.employee_id != Some(id) => {
+            auth.require_permission("leave.read")?;
+            id
+        }
+        _ => auth
+            .employee_id
+            .ok_or_else(|| actix_web::error::ErrorForbidden("No employee profile"))?,
+    };
+
+    let exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM employees WHERE id = ? AND org_id = ?)",
+    )
+    .bind(employee_id)
+    .bind(auth.org_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to look up employee");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    if !exists {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "message": "Employee not found"
+        })));
+    }
+
+    let year = chrono::Local::now().date_naive().year();
+    let (year_start, year_end) = leave_year(year, 1).ok_or_else(ApiError::internal)?;
+
+    let approved = sqlx::query_as::<_, (String, NaiveDate, NaiveDate)>(
+        r#"
+        SELECT leave_type, start_date, end_date
+        FROM leave_requests
+        WHERE employee_id = ?
+        AND org_id = ?
+        AND status = 'approved'
+        AND end_date >= ?
+        AND start_date <= ?
+        "#,
+    )
+    .bind(employee_id)
+    .bind(auth.org_id)
+    .bind(year_start)
+    .bind(year_end)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to sum used leave");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    // business days of each approved leave, clipped to this year
+    let holidays = calendar::holidays(pool.get_ref(), auth.org_id).await?;
+    let used_of = |leave_type: &str| {
+        approved
+            .iter()
+            .filter(|(t, _, _)| t == leave_type)
+            .map(|(_, start, end)| {
+                i64::from(business_days_between(
+                    *start.max(&year_start),
+                    *end.min(&year_end),
+                    &holidays,
+                ))
+            })
+            .sum::<i64>()
+    };
+
+    // approvals, cancellations and bulk adjustments keep this row up to date
+    let stored_annual = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT CAST(remaining_days AS SIGNED)
+        FROM leave_balances
+        WHERE employee_id = ?
+        AND year = ?
+        AND leave_type = 'annual'
+        "#,
+    )
+    .bind(employee_id)
+    .bind(year)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to fetch leave balance");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let annual_used = used_of("annual");
+    let (annual_total, annual_remaining) = match stored_annual {
+        Some(remaining) => (remaining + annual_used, remaining),
+        None => {
+            let total = i64::from(config.annual_leave_days);
+            (total, (total - annual_used).max(0))
+        }
+    };
+    // approvals don't draw sick leave from a stored balance
+    let sick_total = i64::from(config.sick_leave_days);
+    let sick_used = used_of("sick");
+
+    Ok(HttpResponse::Ok().json(LeaveBalanceResponse {
+        employee_id,
+        year,
+        annual_total,
+        annual_used,
+        annual_remaining,
+        sick_total,
+        sick_used,
+        sick_remaining: (sick_total - sick_used).max(0),
+        unpaid_used: used_of("unpaid"),
+    }))
+}
+
+#[derive(Deserialize, IntoParams, ToSchema)]
+pub struct EntitlementQuery {
+    /// Leave year, named after the calendar year it starts in; defaults to the current one
+    #[schema(example = 2026)]
+    pub year: Option<i32>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EntitlementResponse {
+    #[schema(example = 1001)]
+    pub employee_id: u64,
+    #[schema(example = 2026)]
+    pub year: i32,
+    /// First and last day of the leave year (`LEAVE_YEAR_START_MONTH`)
+    #[schema(example = "2026-01-01", value_type = String, format = "date")]
+    pub period_start: NaiveDate,
+    #[schema(example = "2026-12-31", value_type = String, format = "date")]
+    pub period_end: NaiveDate,
+    #[schema(example = "2026-07-01", value_type = String, format = "date")]
+    pub hire_date: NaiveDate,
+    /// Allowance for a full leave year (`ANNUAL_LEAVE_DAYS`)
+    #[schema(example = 20)]
+    pub full_entitlement: u32,
+    /// Working days (excluding weekends and holidays) in the leave year
+    #[schema(example = 253)]
+    pub working_days_in_period: u32,
+    /// Working days from the later of hire date and period start
+    #[schema(example = 127)]
+    pub working_days_employed: u32,
+    /// `full_entitlement` scaled by the employed share, rounded to the nearest half day
+    #[schema(example = 10.0)]
+    pub prorated_days: f64,
+}
+
+/// First and last day of the leave year starting in `year`; `None` outside chrono's range
+fn leave_year(year: i32, start_month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let start = NaiveDate::from_ymd_opt(year, start_month, 1)?;
+    let next = NaiveDate::from_ymd_opt(year.checked_add(1)?, start_month, 1)?;
+    Some((start, next.pred_opt()?))
+}
+
+/// Annual entitlement prorated by the working days left in the leave year at hire
+fn prorate(full: u32, employed_days: u32, period_days: u32) -> f64 {
+    if period_days == 0 {
+        return 0.0;
+    }
+    let days = f64::from(full) * f64::from(employed_days) / f64::from(period_days);
+    (days * 2.0).round() / 2.0
+}
+
+/// Annual leave entitlement of an employee for one leave year, prorated for mid-year hires
+#[utoipa::path(
+    get,
+    path = "/api/v1/employees/{employee_id}/entitlement",
+    params(
+        ("employee_id", Path, description = "Employee ID"),
+        EntitlementQuery
+    ),
+    responses(
+        (status = 200, description = "Full allowance for employees hired before the leave year, prorated by working days otherwise", body = EntitlementResponse),
+        (status = 400, description = "year outside 1900-9999", body = ApiError),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Another employee's entitlement without `leave.read`"),
+        (status = 404, description = "Employee not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Employee"
+)]
+pub async fn employee_entitlement(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    path: web::Path<u64>,
+    query: web::Query<EntitlementQuery>,
+) -> actix_web::Result<impl Responder> {
+    let employee_id = path.into_inner();
+
+    let hire_date = sqlx::query_scalar::<_, NaiveDate>(
+        "SELECT hire_date FROM employees WHERE id = ? AND org_id = ?",
+    )
+    .bind(employee_id)
+    .bind(auth.org_id)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to fetch hire date");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let Some(hire_date) = hire_date else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "message": "Employee not found"
+        })));
+    };
+
+    if auth.employee_id != Some(employee_id) {
+        auth.require_permission("leave.read")?;
+    }
+
+    let start_month = config.leave_year_start_month;
+    let year = query.year.unwrap_or_else(|| {
+        // the leave year in progress today
+        let today = chrono::Local::now().date_naive();
+        if today.month() >= start_month { today.year() } else { today.year() - 1 }
+    });
+    let (period_start, period_end) = Some(year)
+        .filter(|year| YEAR_RANGE.contains(year))
+        .and_then(|year| leave_year(year, start_month))
+        .ok_or_else(|| {
+            ApiError::validation(
+                "year",
+                format!(
+                    "year must be between {} and {}",
+                    YEAR_RANGE.start(),
+                    YEAR_RANGE.end()
+                ),
+            )
+        })?;
+
+    let holidays = calendar::holidays(pool.get_ref(), auth.org_id).await?;
+    let working_days_in_period = business_days_between(period_start, period_end, &holidays);
+    // 0 when hired after the period
+    let working_days_employed =
+        business_days_between(hire_date.max(period_start), period_end, &holidays);
+
+    Ok(HttpResponse::Ok().json(EntitlementResponse {
+        employee_id,
+        year,
+        period_start,
+        period_end,
+        hire_date,
+        full_entitlement: config.annual_leave_days,
+        working_days_in_period,
+        working_days_employed,
+        prorated_days: prorate(
+            config.annual_leave_days,
+            working_days_employed,
+            working_days_in_period,
+        ),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn calendar_leave_year() {
+        assert_eq!(
+            leave_year(2026, 1),
+            Some((date(2026, 1, 1), date(2026, 12, 31)))
+        );
+    }
+
+    #[test]
+    fn leave_year_starting_mid_year() {
+        assert_eq!(
+            leave_year(2027, 4),
+            Some((date(2027, 4, 1), date(2028, 3, 31)))
+        );
+    }
+
+    #[test]
+    fn unrepresentable_year_is_none() {
+        assert_eq!(leave_year(i32::MAX, 1), None);
+        assert_eq!(leave_year(300_000, 1), None);
+    }
+
+    /// Prorated allowance of a `full`-day entitlement for someone hired on `hire_date`,
+    /// computed as `employee_entitlement` does
+    fn prorated(full: u32, hire_date: NaiveDate, year: i32, start_month: u32) -> f64 {
+        let (start, end) = leave_year(year, start_month).unwrap();
+        let holidays = HashSet::new();
+        prorate(
+            full,
+            business_days_between(hire_date.max(start), end, &holidays),
+            business_days_between(start, end, &holidays),
+        )
+    }
+
+    #[test]
+    fn start_of_year_hire_gets_the_full_entitlement() {
+        assert_eq!(prorated(20, date(2026, 1, 1), 2026, 1), 20.0);
+        // hired in an earlier year
+        assert_eq!(prorated(20, date(2019, 6, 15), 2026, 1), 20.0);
+    }
+
+    #[test]
+    fn mid_year_hire_is_prorated() {
+        // 132 of 2026's 261 working days are left on Wed 1 July: 10.11 days
+        assert_eq!(prorated(20, date(2026, 7, 1), 2026, 1), 10.0);
+        // 66 of 261 left on Thu 1 October: 5.06 days
+        assert_eq!(prorated(20, date(2026, 10, 1), 2026, 1), 5.0);
+    }
+
+    #[test]
+    fn proration_follows_the_leave_year_start() {
+        // April-March leave year: 1 October 2026 is half-way through
+        let days = prorated(20, date(2026, 10, 1), 2026, 4);
+        assert!((9.5..=10.5).contains(&days), "{}", days);
+    }
+
+    #[test]
+    fn hire_after_the_year_gets_nothing() {
+        assert_eq!(prorated(20, date(2027, 2, 1), 2026, 1), 0.0);
+        assert_eq!(prorate(20, 0, 0), 0.0);
+    }
+
+    /// Remaining annual days of `employee_id` in 2026
+    async fn remaining(pool: &MySqlPool, employee_id: u64) -> i64 {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT remaining_days FROM leave_balances WHERE employee_id = ? AND year = 2026 AND leave_type = 'annual'",
+        )
+        .bind(employee_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn department_adjustment_touches_only_that_department() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::http::StatusCode;
+        use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let admin = test_support::user(&pool, org, None, Role::Admin).await;
+        let title = test_support::job_title(&pool, org).await;
+        let (sales, support) = (
+            test_support::department(&pool, org).await,
+            test_support::department(&pool, org).await,
+        );
+        let mut in_sales = Vec::new();
+        for _ in 0..2 {
+            in_sales.push(test_support::employee_in(&pool, org, sales, title, "active").await);
+        }
+        let in_support = test_support::employee_in(&pool, org, support, title, "active").await;
+        for employee_id in in_sales.iter().chain([&in_support]) {
+            sqlx::query(
+                "INSERT INTO leave_balances (employee_id, year, leave_type, remaining_days) VALUES (?, 2026, 'annual', 10)",
+            )
+            .bind(employee_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route(
+                    "/leave/balance/bulk-adjust",
+                    web::post().to(bulk_adjust_balances),
+                ),
+        )
+        .await;
+        let adjust = |delta_days: i32| {
+            TestRequest::post()
+                .uri("/leave/balance/bulk-adjust")
+                .insert_header(admin.bearer())
+                .set_json(serde_json::json!({
+                    "department_id": sales,
+                    "delta_days": delta_days,
+                    "year": 2026
+                }))
+                .to_request()
+        };
+
+        let res = call_service(&app, adjust(2)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let body: serde_json::Value = read_body_json(res).await;
+        assert_eq!(body, serde_json::json!({"adjusted": 2, "skipped": []}));
+
+        for employee_id in &in_sales {
+            assert_eq!(remaining(&pool, *employee_id).await, 12);
+        }
+        assert_eq!(remaining(&pool, in_support).await, 10);
+
+        let ledger: Vec<u64> = sqlx::query_scalar(
+            "SELECT employee_id FROM leave_balance_ledger WHERE employee_id IN (?, ?, ?) ORDER BY employee_id",
+        )
+        .bind(in_sales[0])
+        .bind(in_sales[1])
+        .bind(in_support)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(ledger, in_sales);
+
+        // would go negative: skipped, nothing changes
+        let res = call_service(&app, adjust(-20)).await;
+        let body: serde_json::Value = read_body_json(res).await;
+        assert_eq!(body["adjusted"], 0);
+        assert_eq!(body["skipped"], serde_json::json!(in_sales));
+        for employee_id in &in_sales {
+            assert_eq!(remaining(&pool, *employee_id).await, 12);
+        }
+    }
+}
@@ -1,17 +1,56 @@
+use crate::api::leave_balance::record_balance_change;
 use crate::auth::auth::AuthUser;
-use actix_web::{HttpResponse, Responder, web};
+use crate::utils::audit::{self, AuditEntry};
+use crate::utils::calendar::{self, business_days_between};
+use crate::config::Config;
+use crate::utils::csv_export::stream_csv;
+use crate::utils::date_param;
+use crate::utils::db_utils::SqlValue;
+use crate::utils::notifier::{Notification, Notifier};
+use crate::utils::pagination::{CountResponse, link_header};
+use crate::utils::precondition::{ensure_unmodified, if_unmodified_since};
+use crate::utils::report_limit;
+use crate::model::leave_request::{LeaveStatus, LeaveType};
+use actix_web::{
+    HttpRequest, HttpResponse, Responder,
+    error::InternalError,
+    http::{StatusCode, header::LINK},
+    web,
+};
 // use chrono::NaiveDateTime;
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{MySqlPool, prelude::FromRow};
+use sqlx::{MySql, MySqlPool, Transaction, prelude::FromRow};
+use std::collections::HashSet;
 // use utoipa::path as utoipa_path;
 use utoipa::{IntoParams, ToSchema};
 
+/// Columns `LEAVE_LIST_SORT` may name
+pub const SORT_COLUMNS: &[&str] = &["id", "created_at", "start_date"];
+
+/// Longest accepted `reason` / `decision_note`, in characters
+const MAX_NOTE_CHARS: usize = 500;
+
+/// Most leave requests one bulk approve/reject may touch
+const MAX_BULK_IDS: usize = 500;
+
+/// `approved_by_name` column: the approver's employee name, or their username if they
+/// have no employee record. A subquery keeps the leave filters' column names unambiguous.
+const APPROVER_NAME_COLUMN: &str = r#"(
+            SELECT COALESCE(NULLIF(TRIM(CONCAT(e.first_name, ' ', COALESCE(e.last_name, ''))), ''), u.username)
+            FROM users u
+            LEFT JOIN employees e ON e.id = u.employee_id
+            WHERE u.id = leave_requests.approved_by
+        ) AS approved_by_name"#;
+
 #[derive(Deserialize)]
 pub struct CreateLeave {
     pub start_date: chrono::NaiveDate,
     pub end_date: chrono::NaiveDate,
     pub leave_type: String, // validated manually
+    /// Free text for the approver, at most 500 characters
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -58,6 +97,15 @@ pub struct LeaveFilter {
     #[schema(example = "Pending")]
     /// Filter by leave status
     pub status: Option<String>,
+    #[schema(example = "sick")]
+    /// Filter by leave type (annual, sick, unpaid)
+    pub leave_type: Option<String>,
+    #[schema(example = "2026-01-01", format = "date")]
+    /// Only leaves ending on or after this date (`YYYY-MM-DD`)
+    pub from_date: Option<String>,
+    #[schema(example = "2026-01-31", format = "date")]
+    /// Only leaves starting on or before this date (`YYYY-MM-DD`)
+    pub to_date: Option<String>,
     #[schema(example = 1)]
     /// Pagination page number (start with 1)
     pub page: Option<u64>, // 1-based
@@ -66,10 +114,43 @@ pub struct LeaveFilter {
     pub per_page: Option<u64>, // items per page
 }
 
+#[derive(Deserialize, IntoParams, ToSchema)]
+pub struct LeaveStatsQuery {
+    /// Only count leaves ending on or after this date (`YYYY-MM-DD`)
+    #[schema(example = "2026-01-01", format = "date")]
+    pub from: Option<String>,
+    /// Only count leaves starting on or before this date (`YYYY-MM-DD`)
+    #[schema(example = "2026-12-31", format = "date")]
+    pub to: Option<String>,
+}
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct LeaveStatsGroup {
+    #[schema(example = "sick")]
+    pub leave_type: String,
+    #[schema(example = "pending")]
+    pub status: String,
+    #[schema(example = 12)]
+    pub count: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LeaveStats {
+    #[schema(value_type = Option<String>, format = "date")]
+    pub from: Option<NaiveDate>,
+    #[schema(value_type = Option<String>, format = "date")]
+    pub to: Option<NaiveDate>,
+    /// Sum of all group counts
+    #[schema(example = 17)]
+    pub total: i64,
+    pub groups: Vec<LeaveStatsGroup>,
+}
+
 // Helper enum for typed SQLx binding
 enum FilterValue<'a> {
     U64(u64),
     Str(&'a str),
+    Date(NaiveDate),
 }
 
 #[derive(Serialize, Deserialize, FromRow, ToSchema)]
@@ -95,10 +176,75 @@ pub struct LeaveResponse {
     // leave creation date time
     #[schema(example = "2026-01-01T00:00:00Z", format = "date-time", value_type = String)]
     pub created_at: Option<DateTime<Utc>>,
+    /// employee's reason for the request
+    #[schema(example = "Family wedding", nullable = true)]
+    pub reason: Option<String>,
+    /// HR's note on approval or rejection
+    #[schema(example = "Team is short-staffed that week", nullable = true)]
+    pub decision_note: Option<String>,
+    /// user id of whoever approved or rejected the request
+    #[schema(example = 1, nullable = true)]
+    pub approved_by: Option<u64>,
+    /// when it was approved or rejected
+    #[schema(example = "2026-01-02T09:30:00Z", format = "date-time", value_type = Option<String>)]
+    pub approved_at: Option<DateTime<Utc>>,
+    /// name of whoever approved or rejected the request; `null` while pending
+    #[schema(example = "Jane Smith", nullable = true)]
+    pub approved_by_name: Option<String>,
+    /// working days (Mon-Fri) the leave covers, computed from the dates
+    #[schema(example = 1)]
+    #[sqlx(default)]
+    pub business_days: u32,
+}
+
+impl LeaveResponse {
+    fn with_business_days(mut self, holidays: &HashSet<NaiveDate>) -> Self {
+        self.business_days = business_days_between(self.start_date, self.end_date, holidays);
+        self
+    }
+}
+
+/// Optional body of the approve/reject endpoints
+#[derive(Deserialize, ToSchema)]
+pub struct LeaveDecision {
+    /// Explanation for the employee, at most 500 characters
+    #[schema(example = "Team is short-staffed that week")]
+    pub decision_note: Option<String>,
+}
+
+/// Body of the bulk approve/reject endpoints
+#[derive(Deserialize, ToSchema)]
+pub struct BulkLeaveDecision {
+    /// At most 500 ids
+    #[schema(example = json!([55, 56, 57]))]
+    pub leave_ids: Vec<u64>,
+    /// Stored on every processed request, at most 500 characters
+    #[schema(example = "Approved in year-end review")]
+    pub decision_note: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BulkDecisionResponse {
+    #[schema(example = 2)]
+    pub processed: u64,
+    /// Ids that were not pending (or don't exist); left unchanged
+    #[schema(example = json!([57]))]
+    pub skipped: Vec<u64>,
+}
+
+/// 400 if a free-text field is longer than `MAX_NOTE_CHARS`
+fn ensure_note_length(field: &str, value: Option<&str>) -> actix_web::Result<()> {
+    match value {
+        Some(v) if v.chars().count() > MAX_NOTE_CHARS => Err(json_error(
+            StatusCode::BAD_REQUEST,
+            format!("{} must be at most {} characters", field, MAX_NOTE_CHARS),
+        )),
+        _ => Ok(()),
+    }
 }
 
 fn validate_leave_type(value: &str) -> bool {
-    matches!(value, "annual" | "sick" | "unpaid")
+    LeaveType::from_name(value).is_some()
 }
 
 /* =========================
@@ -123,7 +269,11 @@ Create leave request
         ),
         (status = 400, description = "Bad request"),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden")
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Overlaps a pending/approved leave, or auto-approval (LEAVE_DEFAULT_STATUS=approved) with insufficient annual balance", body = Object, example = json!({
+            "message": "Overlaps an existing leave request",
+            "conflicting_leave_id": 55
+        }))
     ),
     security(
         ("bearer_auth" = [])
@@ -133,6 +283,8 @@ Create leave request
 pub async fn create_leave(
     auth: AuthUser,
     pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    notifier: web::Data<dyn Notifier>,
     payload: web::Json<CreateLeave>,
 ) -> actix_web::Result<impl Responder> {
     let employee_id: u64 = auth
@@ -153,31 +305,206 @@ pub async fn create_leave(
         })));
     }
 
-    // 3️⃣ insert request
-    sqlx::query!(
+    ensure_note_length("reason", payload.reason.as_deref())?;
+
+    let holidays = calendar::holidays(pool.get_ref(), auth.org_id).await?;
+    if business_days_between(payload.start_date, payload.end_date, &holidays) == 0 {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "No working days in selected range"
+        })));
+    }
+
+    // 3️⃣ insert request in the configured initial status
+    let initial_status = config.leave_default_status;
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to start leave transaction");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    // reject double booking; ranges are inclusive, so ending the day another starts overlaps
+    if let Some(conflicting_id) =
+        find_overlapping_leave(&mut tx, employee_id, payload.start_date, payload.end_date).await?
+    {
+        return Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "message": "Overlaps an existing leave request",
+            "conflicting_leave_id": conflicting_id
+        })));
+    }
+
+    let inserted = sqlx::query(
         r#"
         INSERT INTO leave_requests
-            (employee_id, start_date, end_date, leave_type)
-        VALUES (?, ?, ?, ?)
+            (org_id, employee_id, start_date, end_date, leave_type, status, reason)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
         "#,
-        employee_id,
-        payload.start_date,
-        payload.end_date,
-        payload.leave_type,
     )
-    .execute(pool.get_ref())
+    .bind(auth.org_id)
+    .bind(employee_id)
+    .bind(payload.start_date)
+    .bind(payload.end_date)
+    .bind(&payload.leave_type)
+    .bind(initial_status.as_str())
+    .bind(&payload.reason)
+    .execute(&mut *tx)
     .await
     .map_err(|e| {
         tracing::error!(error = %e, employee_id, "Failed to create leave request");
         actix_web::error::ErrorInternalServerError("Internal Server Error")
     })?;
 
+    // auto-approved requests are accounted for right away
+    if initial_status == LeaveStatus::Approved && payload.leave_type == "annual" {
+        deduct_annual_balance(
+            &mut tx,
+            employee_id,
+            payload.start_date,
+            payload.end_date,
+            &holidays,
+            config.annual_leave_days,
+        )
+        .await?;
+    }
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to commit leave request");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    // 4️⃣ let the manager know (never fails the request)
+    if config.leave_notifications_enabled {
+        notify_manager(
+            pool.get_ref(),
+            notifier.get_ref(),
+            employee_id,
+            inserted.last_insert_id(),
+            &payload,
+        )
+        .await;
+    }
+
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "message": "Leave request submitted",
-        "status": "pending"
+        "status": initial_status.as_str()
     })))
 }
 
+/// First pending or approved leave of the employee intersecting `start..=end`.
+/// Locks the employee row so concurrent submissions can't both pass the check.
+async fn find_overlapping_leave(
+    tx: &mut Transaction<'static, MySql>,
+    employee_id: u64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> actix_web::Result<Option<u64>> {
+    sqlx::query("SELECT id FROM employees WHERE id = ? FOR UPDATE")
+        .bind(employee_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, employee_id, "Failed to lock employee");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+    sqlx::query_scalar::<_, u64>(
+        r#"
+        SELECT id
+        FROM leave_requests
+        WHERE employee_id = ?
+        AND status IN ('pending', 'approved')
+        AND start_date <= ?
+        AND end_date >= ?
+        ORDER BY start_date
+        LIMIT 1
+        "#,
+    )
+    .bind(employee_id)
+    .bind(end)
+    .bind(start)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to check overlapping leave");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })
+}
+
+/// The submitting employee and their manager, as `notify_manager` loads them
+#[derive(FromRow)]
+struct ManagerContact {
+    first_name: String,
+    last_name: Option<String>,
+    manager_id: u64,
+    manager_email: Option<String>,
+}
+
+/// `leave.submitted` for the manager; None when the manager has no email
+fn leave_submitted(
+    contact: ManagerContact,
+    employee_id: u64,
+    leave_id: u64,
+    leave: &CreateLeave,
+) -> Option<Notification> {
+    let recipient = contact.manager_email.filter(|e| !e.trim().is_empty())?;
+
+    Some(Notification {
+        event: "leave.submitted",
+        recipient,
+        recipient_id: contact.manager_id,
+        payload: serde_json::json!({
+            "leave_id": leave_id,
+            "employee_id": employee_id,
+            "employee_name": format!("{} {}", contact.first_name, contact.last_name.unwrap_or_default()).trim(),
+            "start_date": leave.start_date,
+            "end_date": leave.end_date,
+            "leave_type": leave.leave_type,
+        }),
+    })
+}
+
+/// Hand a `leave.submitted` notification for the employee's manager to the notifier.
+/// Skipped when the employee has no manager or the manager has no email.
+async fn notify_manager(
+    pool: &MySqlPool,
+    notifier: &dyn Notifier,
+    employee_id: u64,
+    leave_id: u64,
+    leave: &CreateLeave,
+) {
+    let contact = sqlx::query_as::<_, ManagerContact>(
+        r#"
+        SELECT e.first_name, e.last_name, m.id AS manager_id, m.email AS manager_email
+        FROM employees e
+        JOIN employees m ON m.id = e.manager_id
+        WHERE e.id = ?
+        "#,
+    )
+    .bind(employee_id)
+    .fetch_optional(pool)
+    .await;
+
+    let contact = match contact {
+        Ok(Some(contact)) => contact,
+        Ok(None) => {
+            tracing::debug!(employee_id, "No manager assigned, skipping leave notification");
+            return;
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, employee_id, "Manager lookup failed, skipping leave notification");
+            return;
+        }
+    };
+
+    match leave_submitted(contact, employee_id, leave_id, leave) {
+        Some(notification) => {
+            notifier.notify(notification);
+        }
+        None => {
+            tracing::debug!(employee_id, "Manager has no email, skipping leave notification");
+        }
+    }
+}
+
 /* =========================
 Approve leave (HR/Admin)
 ========================= */
@@ -186,17 +513,26 @@ Approve leave (HR/Admin)
     put,
     path = "/api/v1/leave/{leave_id}/approve",
     params(
-        ("leave_id" = u64, Path, description = "ID of the leave request to approve")
+        ("leave_id" = u64, Path, description = "ID of the leave request to approve"),
+        ("If-Unmodified-Since" = Option<String>, Header, description = "Only proceed if the leave request hasn't changed since this HTTP date")
     ),
+    request_body(content = Option<LeaveDecision>, description = "Optional note explaining the decision"),
     responses(
+        (status = 400, description = "decision_note longer than 500 characters"),
         (status = 200, description = "Leave approved successfully", body = Object, example = json!({
             "message": "Leave approved"
         })),
-        (status = 400, description = "Leave request not found or already processed", body = Object, example = json!({
-            "message": "Leave request not found or already processed"
-        })),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden")
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Leave request not found", body = Object, example = json!({
+            "message": "Leave request not found"
+        })),
+        (status = 409, description = "Illegal status transition, or annual leave balance too low; nothing was changed", body = Object, example = json!({
+            "message": "Cannot change leave status from approved to approved"
+        })),
+        (status = 412, description = "Leave request was modified after If-Unmodified-Since", body = Object, example = json!({
+            "message": "Resource was modified after If-Unmodified-Since"
+        }))
     ),
     security(
         ("bearer_auth" = [])
@@ -205,35 +541,45 @@ Approve leave (HR/Admin)
 )]
 pub async fn approve_leave(
     auth: AuthUser,
+    req: HttpRequest,
     pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
     path: web::Path<u64>,
+    body: Option<web::Json<LeaveDecision>>,
 ) -> actix_web::Result<impl Responder> {
-    auth.require_hr_or_admin()?;
+    auth.require_permission("leave.approve")?;
 
     let leave_id = path.into_inner();
+    let note = body.and_then(|b| b.into_inner().decision_note);
+    ensure_note_length("decision_note", note.as_deref())?;
 
-    let result = sqlx::query!(
-        r#"
-        UPDATE leave_requests
-        SET status = 'approved'
-        WHERE id = ?
-        AND status = 'pending'
-        "#,
-        leave_id
-    )
-    .execute(pool.get_ref())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, leave_id, "Approve leave failed");
-        actix_web::error::ErrorInternalServerError("Internal Server Error")
-    })?;
+    // loaded before the row lock so no second connection is needed while it is held
+    let holidays = calendar::holidays(pool.get_ref(), auth.org_id).await?;
 
-    if result.rows_affected() == 0 {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "message": "Leave request not found or already processed"
-        })));
+    // approval and balance deduction succeed or fail together
+    let mut tx = begin_tx(pool.get_ref(), leave_id).await?;
+
+    let leave = lock_leave(&mut tx, auth.org_id, leave_id).await?;
+    ensure_unmodified(if_unmodified_since(&req), leave.updated_at)?;
+    ensure_transition(leave.status, LeaveStatus::Approved)?;
+    set_leave_status(&mut tx, leave_id, LeaveStatus::Approved, note.as_deref(), Some(auth.user_id))
+        .await?;
+    audit_status_change(&mut tx, &auth, leave_id, leave.status, "approve").await?;
+
+    if leave.leave_type == "annual" {
+        deduct_annual_balance(
+            &mut tx,
+            leave.employee_id,
+            leave.start_date,
+            leave.end_date,
+            &holidays,
+            config.annual_leave_days,
+        )
+        .await?;
     }
 
+    commit_tx(tx, leave_id).await?;
+
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "message": "Leave approved"
     })))
@@ -247,73 +593,86 @@ Reject leave (HR/Admin)
     put,
     path = "/api/v1/leave/{leave_id}/reject",
     params(
-        ("leave_id" = u64, Path, description = "ID of the leave request to reject")
+        ("leave_id" = u64, Path, description = "ID of the leave request to reject"),
+        ("If-Unmodified-Since" = Option<String>, Header, description = "Only proceed if the leave request hasn't changed since this HTTP date")
     ),
+    request_body(content = Option<LeaveDecision>, description = "Optional note explaining the decision"),
     responses(
+        (status = 400, description = "decision_note longer than 500 characters"),
         (status = 200, description = "Leave rejected successfully", body = Object, example = json!({
             "message": "Leave rejected"
         })),
-        (status = 400, description = "Leave request not found or already processed", body = Object, example = json!({
-            "message": "Leave request not found or already processed"
-        })),
         (status = 401, description = "Unauthorized"),
-        (status = 403, description = "Forbidden")
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Leave request not found", body = Object, example = json!({
+            "message": "Leave request not found"
+        })),
+        (status = 409, description = "Illegal status transition", body = Object, example = json!({
+            "message": "Cannot change leave status from approved to rejected"
+        })),
+        (status = 412, description = "Leave request was modified after If-Unmodified-Since", body = Object, example = json!({
+            "message": "Resource was modified after If-Unmodified-Since"
+        }))
     ),
     security(
         ("bearer_auth" = [])
     ),
     tag = "Leave"
 )]
-
 pub async fn reject_leave(
     auth: AuthUser,
+    req: HttpRequest,
     pool: web::Data<MySqlPool>,
     path: web::Path<u64>,
+    body: Option<web::Json<LeaveDecision>>,
 ) -> actix_web::Result<impl Responder> {
-    auth.require_hr_or_admin()?;
+    auth.require_permission("leave.reject")?;
 
     let leave_id = path.into_inner();
+    let note = body.and_then(|b| b.into_inner().decision_note);
+    ensure_note_length("decision_note", note.as_deref())?;
 
-    let result = sqlx::query!(
-        r#"
-        UPDATE leave_requests
-        SET status = 'rejected'
-        WHERE id = ?
-        AND status = 'pending'
-        "#,
-        leave_id
-    )
-    .execute(pool.get_ref())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, leave_id, "Reject leave failed");
-        actix_web::error::ErrorInternalServerError("Internal Server Error")
-    })?;
+    let mut tx = begin_tx(pool.get_ref(), leave_id).await?;
 
-    if result.rows_affected() == 0 {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "message": "Leave request not found or already processed"
-        })));
-    }
+    let leave = lock_leave(&mut tx, auth.org_id, leave_id).await?;
+    ensure_unmodified(if_unmodified_since(&req), leave.updated_at)?;
+    ensure_transition(leave.status, LeaveStatus::Rejected)?;
+    set_leave_status(&mut tx, leave_id, LeaveStatus::Rejected, note.as_deref(), Some(auth.user_id))
+        .await?;
+    audit_status_change(&mut tx, &auth, leave_id, leave.status, "reject").await?;
+
+    commit_tx(tx, leave_id).await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "message": "Leave rejected"
     })))
 }
 
-/// for getting a leave application details endpoint
+/* =========================
+Cancel leave (owner or HR/Admin)
+========================= */
+/// Swagger doc for cancel_leave endpoint
 #[utoipa::path(
-    get,
-    path = "/api/v1/leave/{leave_id}",
+    put,
+    path = "/api/v1/leave/{leave_id}/cancel",
     params(
-        ("leave_id" = u64, Path, description = "ID of the leave request to fetch")
+        ("leave_id" = u64, Path, description = "ID of the leave request to cancel"),
+        ("If-Unmodified-Since" = Option<String>, Header, description = "Only proceed if the leave request hasn't changed since this HTTP date")
     ),
     responses(
-        (status = 200, description = "Leave request found", body = LeaveResponse),
+        (status = 200, description = "Leave cancelled; annual days of an approved leave are returned to the balance", body = Object, example = json!({
+            "message": "Leave cancelled"
+        })),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden"),
         (status = 404, description = "Leave request not found", body = Object, example = json!({
             "message": "Leave request not found"
+        })),
+        (status = 409, description = "Illegal status transition", body = Object, example = json!({
+            "message": "Cannot change leave status from rejected to cancelled"
+        })),
+        (status = 412, description = "Leave request was modified after If-Unmodified-Since", body = Object, example = json!({
+            "message": "Resource was modified after If-Unmodified-Since"
         }))
     ),
     security(
@@ -321,53 +680,87 @@ pub async fn reject_leave(
     ),
     tag = "Leave"
 )]
-pub async fn get_leave(
+pub async fn cancel_leave(
     auth: AuthUser,
+    req: HttpRequest,
     pool: web::Data<MySqlPool>,
     path: web::Path<u64>,
 ) -> actix_web::Result<impl Responder> {
-    auth.require_hr_or_admin()?;
-
     let leave_id = path.into_inner();
 
-    let leave = sqlx::query_as!(
-        LeaveResponse,
-        r#"
-        SELECT
-            id,
-            employee_id,
-            start_date,
-            end_date,
-            leave_type AS `leave_type: String`,
-            status,
-            created_at
-        FROM leave_requests
-        WHERE id = ?
-        "#,
-        leave_id
-    )
-    .fetch_optional(pool.get_ref())
-    .await
-    .map_err(|e| {
-        tracing::error!(error = %e, leave_id, "Failed to fetch leave request");
-        actix_web::error::ErrorInternalServerError("Internal Server Error")
-    })?;
+    let mut tx = begin_tx(pool.get_ref(), leave_id).await?;
 
-    match leave {
-        Some(data) => Ok(HttpResponse::Ok().json(data)),
-        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
-            "message": "Leave request not found"
-        }))),
+    let leave = lock_leave(&mut tx, auth.org_id, leave_id).await?;
+    ensure_unmodified(if_unmodified_since(&req), leave.updated_at)?;
+
+    // employees may only cancel their own requests
+    if !auth.has_permission("leave.cancel_any") && auth.employee_id != Some(leave.employee_id) {
+        return Err(actix_web::error::ErrorForbidden("Not your leave request"));
+    }
+
+    ensure_transition(leave.status, LeaveStatus::Cancelled)?;
+    set_leave_status(&mut tx, leave_id, LeaveStatus::Cancelled, None, None).await?;
+    audit_status_change(&mut tx, &auth, leave_id, leave.status, "cancel").await?;
+
+    if leave.status == LeaveStatus::Approved && leave.leave_type == "annual" {
+        let holidays = calendar::holidays(pool.get_ref(), auth.org_id).await?;
+        restore_annual_balance(
+            &mut tx,
+            leave.employee_id,
+            leave.start_date,
+            leave.end_date,
+            &holidays,
+        )
+        .await?;
     }
+
+    commit_tx(tx, leave_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Leave cancelled"
+    })))
 }
 
-/// for getting leave applications endpoint
+/* =========================
+Bulk approve / reject (HR/Admin)
+========================= */
+/// Approve many pending leave requests at once
 #[utoipa::path(
-    get,
-    path = "/api/v1/leave",
-    params(LeaveFilter),
+    put,
+    path = "/api/v1/leave/bulk-approve",
+    request_body = BulkLeaveDecision,
+    responses(
+        (status = 200, description = "Pending requests approved; the rest are listed in `skipped`", body = BulkDecisionResponse),
+        (status = 400, description = "No ids, more than 500 ids, or decision_note too long"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "An employee's annual leave balance is too low; nothing was changed", body = Object, example = json!({
+            "message": "Insufficient annual leave balance for 3 day(s) in 2026"
+        }))
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Leave"
+)]
+pub async fn bulk_approve_leaves(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    payload: web::Json<BulkLeaveDecision>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("leave.approve")?;
+    bulk_decide(&auth, pool.get_ref(), &config, payload.into_inner(), LeaveStatus::Approved).await
+}
+
+/// Reject many pending leave requests at once
+#[utoipa::path(
+    put,
+    path = "/api/v1/leave/bulk-reject",
+    request_body = BulkLeaveDecision,
     responses(
-        (status = 200, description = "Paginated leave list", body = LeaveListResponse),
+        (status = 200, description = "Pending requests rejected; the rest are listed in `skipped`", body = BulkDecisionResponse),
+        (status = 400, description = "No ids, more than 500 ids, or decision_note too long"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden")
     ),
@@ -376,73 +769,662 @@ pub async fn get_leave(
     ),
     tag = "Leave"
 )]
-pub async fn leave_list(
+pub async fn bulk_reject_leaves(
     auth: AuthUser,
     pool: web::Data<MySqlPool>,
-    query: web::Query<LeaveFilter>,
+    config: web::Data<Config>,
+    payload: web::Json<BulkLeaveDecision>,
 ) -> actix_web::Result<impl Responder> {
-    auth.require_hr_or_admin()?;
+    auth.require_permission("leave.reject")?;
+    bulk_decide(&auth, pool.get_ref(), &config, payload.into_inner(), LeaveStatus::Rejected).await
+}
 
-    // -------------------------
-    // Pagination
-    // -------------------------
-    let per_page = query.per_page.unwrap_or(10).min(100);
-    let page = query.page.unwrap_or(1).max(1);
-    let offset = (page - 1) * per_page;
+/// Moves every pending request in `leave_ids` to `to` in one transaction; any error rolls
+/// back the whole batch. Non-pending and unknown ids are skipped.
+async fn bulk_decide(
+    auth: &AuthUser,
+    pool: &MySqlPool,
+    config: &Config,
+    payload: BulkLeaveDecision,
+    to: LeaveStatus,
+) -> actix_web::Result<HttpResponse> {
+    if payload.leave_ids.is_empty() || payload.leave_ids.len() > MAX_BULK_IDS {
+        return Err(json_error(
+            StatusCode::BAD_REQUEST,
+            format!("leave_ids must contain 1 to {} ids", MAX_BULK_IDS),
+        ));
+    }
+    ensure_note_length("decision_note", payload.decision_note.as_deref())?;
 
-    // -------------------------
-    // WHERE clause
-    // -------------------------
-    let mut where_sql = String::from(" WHERE 1=1");
-    let mut args: Vec<FilterValue> = Vec::new();
+    let holidays = calendar::holidays(pool, auth.org_id).await?;
+    let action = if to == LeaveStatus::Approved { "approve" } else { "reject" };
 
-    if let Some(emp_id) = query.employee_id {
-        where_sql.push_str(" AND employee_id = ?");
-        args.push(FilterValue::U64(emp_id));
-    }
+    // locking in id order keeps concurrent batches from deadlocking
+    let mut leave_ids = payload.leave_ids;
+    leave_ids.sort_unstable();
+    leave_ids.dedup();
 
-    if let Some(status) = query.status.as_deref() {
-        where_sql.push_str(" AND status = ?");
-        args.push(FilterValue::Str(status));
-    }
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to start bulk leave transaction");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
 
-    // -------------------------
-    // COUNT query
-    // -------------------------
-    let count_sql = format!("SELECT COUNT(*) FROM leave_requests{}", where_sql);
+    let mut processed = 0;
+    let mut skipped = Vec::new();
 
-    let mut count_q = sqlx::query_scalar::<_, i64>(&count_sql);
-    for arg in &args {
-        count_q = match arg {
-            FilterValue::U64(v) => count_q.bind(*v),
-            FilterValue::Str(s) => count_q.bind(*s),
+    for leave_id in leave_ids {
+        let leave = match lock_leave(&mut tx, auth.org_id, leave_id).await {
+            Ok(leave) if leave.status == LeaveStatus::Pending => leave,
+            Ok(_) => {
+                skipped.push(leave_id);
+                continue;
+            }
+            // not found in this organization
+            Err(e) if e.as_response_error().status_code() == StatusCode::NOT_FOUND => {
+                skipped.push(leave_id);
+                continue;
+            }
+            Err(e) => return Err(e),
         };
+
+        set_leave_status(
+            &mut tx,
+            leave_id,
+            to,
+            payload.decision_note.as_deref(),
+            Some(auth.user_id),
+        )
+        .await?;
+        audit_status_change(&mut tx, auth, leave_id, leave.status, action).await?;
+
+        if to == LeaveStatus::Approved && leave.leave_type == "annual" {
+            deduct_annual_balance(
+                &mut tx,
+                leave.employee_id,
+                leave.start_date,
+                leave.end_date,
+                &holidays,
+                config.annual_leave_days,
+            )
+            .await?;
+        }
+
+        processed += 1;
     }
 
-    let total = count_q.fetch_one(pool.get_ref()).await.map_err(|e| {
-        tracing::error!(error=%e, "Failed to count leave requests");
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to commit bulk leave decision");
         actix_web::error::ErrorInternalServerError("Internal Server Error")
     })?;
 
-    // -------------------------
-    // DATA query
-    // -------------------------
-    let data_sql = format!(
+    Ok(HttpResponse::Ok().json(BulkDecisionResponse { processed, skipped }))
+}
+
+/* =========================
+Status transition helpers
+========================= */
+
+/// Leave row locked for a status change
+struct LockedLeave {
+    employee_id: u64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    leave_type: String,
+    status: LeaveStatus,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+/// JSON `{"message": ..}` error with the given status, usable with `?`
+fn json_error(status: StatusCode, message: String) -> actix_web::Error {
+    let response = HttpResponse::build(status).json(serde_json::json!({ "message": message }));
+    InternalError::from_response(message, response).into()
+}
+
+async fn begin_tx(
+    pool: &MySqlPool,
+    leave_id: u64,
+) -> actix_web::Result<Transaction<'static, MySql>> {
+    pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, leave_id, "Failed to start leave transaction");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })
+}
+
+async fn commit_tx(tx: Transaction<'static, MySql>, leave_id: u64) -> actix_web::Result<()> {
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, leave_id, "Failed to commit leave status change");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })
+}
+
+/// Lock the leave row (`FOR UPDATE`) so concurrent status changes are serialized.
+/// 404 if missing or owned by another organization.
+async fn lock_leave(
+    tx: &mut Transaction<'static, MySql>,
+    org_id: u64,
+    leave_id: u64,
+) -> actix_web::Result<LockedLeave> {
+    let row = sqlx::query_as::<_, (u64, NaiveDate, NaiveDate, String, String, Option<DateTime<Utc>>)>(
         r#"
-        SELECT id, employee_id, start_date, end_date, leave_type, status, created_at
+        SELECT employee_id, start_date, end_date, leave_type, status, updated_at
         FROM leave_requests
-        {}
-        ORDER BY created_at DESC
-        LIMIT ? OFFSET ?
+        WHERE id = ? AND org_id = ?
+        FOR UPDATE
         "#,
-        where_sql
-    );
+    )
+    .bind(leave_id)
+    .bind(org_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, leave_id, "Failed to fetch leave request");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
 
-    let mut data_q = sqlx::query_as::<_, LeaveResponse>(&data_sql);
-    for arg in args {
-        data_q = match arg {
+    let (employee_id, start_date, end_date, leave_type, status, updated_at) = row.ok_or_else(|| {
+        json_error(StatusCode::NOT_FOUND, "Leave request not found".to_string())
+    })?;
+
+    let status = LeaveStatus::from_name(&status).ok_or_else(|| {
+        json_error(
+            StatusCode::CONFLICT,
+            format!("Leave request has unknown status '{}'", status),
+        )
+    })?;
+
+    Ok(LockedLeave {
+        employee_id,
+        start_date,
+        end_date,
+        leave_type,
+        status,
+        updated_at,
+    })
+}
+
+/// 409 unless the state machine allows `from -> to`
+fn ensure_transition(from: LeaveStatus, to: LeaveStatus) -> actix_web::Result<()> {
+    if from.can_transition_to(to) {
+        Ok(())
+    } else {
+        Err(json_error(
+            StatusCode::CONFLICT,
+            format!(
+                "Cannot change leave status from {} to {}",
+                from.as_str(),
+                to.as_str()
+            ),
+        ))
+    }
+}
+
+/// A `decision_note` replaces the stored one; `None` keeps it.
+/// With `decided_by` (approve/reject) the acting user and time are recorded too.
+async fn set_leave_status(
+    tx: &mut Transaction<'static, MySql>,
+    leave_id: u64,
+    status: LeaveStatus,
+    decision_note: Option<&str>,
+    decided_by: Option<u64>,
+) -> actix_web::Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE leave_requests
+        SET status = ?,
+            decision_note = COALESCE(?, decision_note),
+            approved_by = COALESCE(?, approved_by),
+            approved_at = IF(? IS NULL, approved_at, NOW())
+        WHERE id = ?
+        "#,
+    )
+    .bind(status.as_str())
+    .bind(decision_note)
+    .bind(decided_by)
+    .bind(decided_by)
+    .bind(leave_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, leave_id, status = status.as_str(), "Failed to update leave status");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(())
+}
+
+/// Audit entry for a leave status change, committed with it
+async fn audit_status_change(
+    tx: &mut Transaction<'static, MySql>,
+    auth: &AuthUser,
+    leave_id: u64,
+    from: LeaveStatus,
+    action: &str,
+) -> actix_web::Result<()> {
+    audit::record(
+        &mut **tx,
+        AuditEntry {
+            org_id: auth.org_id,
+            actor_user_id: Some(auth.user_id),
+            entity: "leave_request",
+            entity_id: Some(leave_id),
+            action,
+            details: Some(serde_json::json!({ "previous_status": from.as_str() })),
+        },
+    )
+    .await
+}
+
+/// Business days of the leave in each calendar year it touches; balances are kept per year
+pub(crate) fn days_per_year(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    holidays: &HashSet<NaiveDate>,
+) -> Vec<(i32, u32)> {
+    (start_date.year()..=end_date.year())
+        .filter_map(|year| {
+            let from = start_date.max(NaiveDate::from_ymd_opt(year, 1, 1)?);
+            let to = end_date.min(NaiveDate::from_ymd_opt(year, 12, 31)?);
+            let days = business_days_between(from, to, holidays);
+            (days > 0).then_some((year, days))
+        })
+        .collect()
+}
+
+/// Balance left after taking `days` off `remaining`; `None` if it would go negative
+fn remaining_after(remaining: i64, days: u32) -> Option<i64> {
+    let left = remaining - i64::from(days);
+    (left >= 0).then_some(left)
+}
+
+/// Take the leave's business days off the annual balance of each year it falls in; 409 if
+/// any of them would go negative. A missing balance row starts at the full `entitlement`.
+async fn deduct_annual_balance(
+    tx: &mut Transaction<'static, MySql>,
+    employee_id: u64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    holidays: &HashSet<NaiveDate>,
+    entitlement: u32,
+) -> actix_web::Result<()> {
+    for (year, days) in days_per_year(start_date, end_date, holidays) {
+        // no-op when the row exists; locks it either way
+        sqlx::query(
+            r#"
+            INSERT INTO leave_balances (employee_id, year, leave_type, remaining_days)
+            VALUES (?, ?, 'annual', ?)
+            ON DUPLICATE KEY UPDATE remaining_days = remaining_days
+            "#,
+        )
+        .bind(employee_id)
+        .bind(year)
+        .bind(entitlement)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, employee_id, year, "Failed to seed leave balance");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+        let remaining = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT CAST(remaining_days AS SIGNED)
+            FROM leave_balances
+            WHERE employee_id = ?
+            AND year = ?
+            AND leave_type = 'annual'
+            FOR UPDATE
+            "#,
+        )
+        .bind(employee_id)
+        .bind(year)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, employee_id, year, "Failed to fetch leave balance");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+        let left = remaining_after(remaining, days).ok_or_else(|| {
+            json_error(
+                StatusCode::CONFLICT,
+                format!(
+                    "Insufficient annual leave balance for {} day(s) in {}",
+                    days, year
+                ),
+            )
+        })?;
+
+        sqlx::query(
+            r#"
+            UPDATE leave_balances
+            SET remaining_days = ?
+            WHERE employee_id = ?
+            AND year = ?
+            AND leave_type = 'annual'
+            "#,
+        )
+        .bind(left)
+        .bind(employee_id)
+        .bind(year)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, employee_id, year, "Failed to deduct leave balance");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+        record_balance_change(
+            tx,
+            employee_id,
+            year,
+            "annual",
+            -i64::from(days),
+            "leave approved",
+            None,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Give the leave's business days back to the annual balance of each year it falls in.
+/// Years without a balance row were never deducted, so there is nothing to give back.
+async fn restore_annual_balance(
+    tx: &mut Transaction<'static, MySql>,
+    employee_id: u64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    holidays: &HashSet<NaiveDate>,
+) -> actix_web::Result<()> {
+    for (year, days) in days_per_year(start_date, end_date, holidays) {
+        let restored = sqlx::query(
+            r#"
+            UPDATE leave_balances
+            SET remaining_days = remaining_days + ?
+            WHERE employee_id = ?
+            AND year = ?
+            AND leave_type = 'annual'
+            "#,
+        )
+        .bind(days)
+        .bind(employee_id)
+        .bind(year)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, employee_id, year, "Failed to restore leave balance");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+        if restored.rows_affected() == 0 {
+            continue;
+        }
+
+        record_balance_change(
+            tx,
+            employee_id,
+            year,
+            "annual",
+            i64::from(days),
+            "leave cancelled",
+            None,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// for getting a leave application details endpoint.
+/// Employees may fetch their own requests; HR/Admin (`leave.read`) may fetch any.
+#[utoipa::path(
+    get,
+    path = "/api/v1/leave/{leave_id}",
+    params(
+        ("leave_id" = u64, Path, description = "ID of the leave request to fetch")
+    ),
+    responses(
+        (status = 200, description = "Leave request found", body = LeaveResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Leave request belongs to another employee"),
+        (status = 404, description = "Leave request not found", body = Object, example = json!({
+            "message": "Leave request not found"
+        }))
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Leave"
+)]
+pub async fn get_leave(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+) -> actix_web::Result<impl Responder> {
+    let leave_id = path.into_inner();
+
+    let sql = format!(
+        r#"
+        SELECT
+            id,
+            employee_id,
+            start_date,
+            end_date,
+            leave_type,
+            status,
+            created_at,
+            reason,
+            decision_note,
+            approved_by,
+            approved_at,
+            {}
+        FROM leave_requests
+        WHERE id = ? AND org_id = ?
+        "#,
+        APPROVER_NAME_COLUMN
+    );
+
+    let leave = sqlx::query_as::<_, LeaveResponse>(&sql)
+        .bind(leave_id)
+        .bind(auth.org_id)
+        .fetch_optional(pool.get_ref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, leave_id, "Failed to fetch leave request");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+    // existence first, so a missing id is 404 for everyone
+    let leave = match leave {
+        Some(data) => {
+            let holidays = calendar::holidays(pool.get_ref(), auth.org_id).await?;
+            data.with_business_days(&holidays)
+        }
+        None => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "message": "Leave request not found"
+            })));
+        }
+    };
+
+    if !auth.has_permission("leave.read") && auth.employee_id != Some(leave.employee_id) {
+        return Err(actix_web::error::ErrorForbidden("Not your leave request"));
+    }
+
+    Ok(HttpResponse::Ok().json(leave))
+}
+
+/// WHERE clause and bindings for the leave list filters, shared by the list and count endpoints.
+/// HR/Admin may filter by any employee; everyone else only sees their own leaves.
+fn leave_filters<'a>(
+    auth: &AuthUser,
+    query: &'a LeaveFilter,
+) -> actix_web::Result<(String, Vec<FilterValue<'a>>)> {
+    let employee_filter = if auth.has_permission("leave.read") {
+        query.employee_id
+    } else {
+        let own_id = auth
+            .employee_id
+            .ok_or_else(|| actix_web::error::ErrorForbidden("No employee profile"))?;
+
+        if query.employee_id.is_some_and(|id| id != own_id) {
+            return Err(actix_web::error::ErrorForbidden(
+                "Only HR/Admin may filter by another employee",
+            ));
+        }
+        Some(own_id)
+    };
+
+    // always scoped to the caller's organization
+    let mut where_sql = String::from(" WHERE org_id = ?");
+    let mut args: Vec<FilterValue> = vec![FilterValue::U64(auth.org_id)];
+
+    if let Some(emp_id) = employee_filter {
+        where_sql.push_str(" AND employee_id = ?");
+        args.push(FilterValue::U64(emp_id));
+    }
+
+    if let Some(status) = query.status.as_deref() {
+        where_sql.push_str(" AND status = ?");
+        args.push(FilterValue::Str(status));
+    }
+
+    if let Some(leave_type) = query.leave_type.as_deref() {
+        let leave_type = LeaveType::from_name(leave_type).ok_or_else(|| {
+            actix_web::error::ErrorBadRequest("Invalid leave type. Allowed: annual, sick, unpaid")
+        })?;
+        where_sql.push_str(" AND leave_type = ?");
+        args.push(FilterValue::Str(leave_type.as_str()));
+    }
+
+    // leaves whose range intersects the window
+    if let Some(from) = date_param::parse("from_date", query.from_date.as_deref())? {
+        where_sql.push_str(" AND end_date >= ?");
+        args.push(FilterValue::Date(from));
+    }
+
+    if let Some(to) = date_param::parse("to_date", query.to_date.as_deref())? {
+        where_sql.push_str(" AND start_date <= ?");
+        args.push(FilterValue::Date(to));
+    }
+
+    Ok((where_sql, args))
+}
+
+async fn count_matching_leaves(
+    pool: &MySqlPool,
+    where_sql: &str,
+    args: &[FilterValue<'_>],
+) -> actix_web::Result<i64> {
+    let count_sql = format!("SELECT COUNT(*) FROM leave_requests{}", where_sql);
+
+    let mut count_q = sqlx::query_scalar::<_, i64>(&count_sql);
+    for arg in args {
+        count_q = match arg {
+            FilterValue::U64(v) => count_q.bind(*v),
+            FilterValue::Str(s) => count_q.bind(*s),
+            FilterValue::Date(d) => count_q.bind(*d),
+        };
+    }
+
+    count_q.fetch_one(pool).await.map_err(|e| {
+        tracing::error!(error=%e, "Failed to count leave requests");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })
+}
+
+/// Number of leave requests matching the list filters, without fetching them
+#[utoipa::path(
+    get,
+    path = "/api/v1/leave/count",
+    params(LeaveFilter),
+    responses(
+        (status = 200, description = "Same number as `total` of the list endpoint with these filters", body = CountResponse),
+        (status = 400, description = "Invalid `leave_type` or malformed `from_date` / `to_date`"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "No employee profile, or filtering by another employee without `leave.read`")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Leave"
+)]
+pub async fn count_leaves(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    query: web::Query<LeaveFilter>,
+) -> actix_web::Result<impl Responder> {
+    let (where_sql, args) = leave_filters(&auth, &query)?;
+    let count = count_matching_leaves(pool.get_ref(), &where_sql, &args).await?;
+
+    Ok(HttpResponse::Ok().json(CountResponse { count }))
+}
+
+/// for getting leave applications endpoint
+#[utoipa::path(
+    get,
+    path = "/api/v1/leave",
+    params(LeaveFilter),
+    responses(
+        (status = 200, description = "Paginated leave list; employees without `leave.read` only get their own leaves", body = LeaveListResponse,
+         headers(("Link" = String, description = "RFC 5988 pagination links (first, prev, next, last)"))),
+        (status = 400, description = "Invalid `leave_type` or malformed `from_date` / `to_date`"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "No employee profile, or filtering by another employee without `leave.read`")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Leave"
+)]
+pub async fn leave_list(
+    auth: AuthUser,
+    req: HttpRequest,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    query: web::Query<LeaveFilter>,
+) -> actix_web::Result<impl Responder> {
+    // -------------------------
+    // Pagination
+    // -------------------------
+    let per_page = query.per_page.unwrap_or(10).min(100);
+    let page = query.page.unwrap_or(1).max(1);
+    let offset = (page - 1) * per_page;
+
+    // -------------------------
+    // WHERE clause
+    // -------------------------
+    let (where_sql, args) = leave_filters(&auth, &query)?;
+
+    // -------------------------
+    // COUNT query
+    // -------------------------
+    let total = count_matching_leaves(pool.get_ref(), &where_sql, &args).await?;
+
+    // -------------------------
+    // DATA query
+    // -------------------------
+    let data_sql = format!(
+        r#"
+        SELECT id, employee_id, start_date, end_date, leave_type, status, created_at,
+               reason, decision_note, approved_by, approved_at, {}
+        FROM leave_requests
+        {}
+        {}
+        LIMIT ? OFFSET ?
+        "#,
+        APPROVER_NAME_COLUMN,
+        where_sql,
+        config.leave_list_sort.order_by()
+    );
+
+    let mut data_q = sqlx::query_as::<_, LeaveResponse>(&data_sql);
+    for arg in args {
+        data_q = match arg {
             FilterValue::U64(v) => data_q.bind(v),
             FilterValue::Str(s) => data_q.bind(s),
+            FilterValue::Date(d) => data_q.bind(d),
         };
     }
 
@@ -459,12 +1441,764 @@ pub async fn leave_list(
     // -------------------------
     // Response
     // -------------------------
+    let holidays = calendar::holidays(pool.get_ref(), auth.org_id).await?;
     let response = LeaveListResponse {
-        data: leaves,
+        data: leaves.into_iter().map(|l| l.with_business_days(&holidays)).collect(),
         page: page as u32,
         per_page: per_page as u32,
         total,
     };
 
-    Ok(HttpResponse::Ok().json(response))
+    let link = link_header(&config.public_base_url, &req, page, per_page, total);
+
+    Ok(HttpResponse::Ok().insert_header((LINK, link)).json(response))
+}
+
+/* =========================
+Leave statistics (HR/Admin)
+========================= */
+#[utoipa::path(
+    get,
+    path = "/api/v1/leave/stats",
+    params(LeaveStatsQuery),
+    responses(
+        (status = 200, description = "Leave counts grouped by type and status; leaves overlapping the range are counted", body = LeaveStats),
+        (status = 400, description = "Malformed date or `from` is after `to`", body = Object, example = json!({
+            "error": "invalid_date",
+            "field": "from",
+            "value": "2026-13-01"
+        })),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 503, description = "Too many reports running (`REPORT_MAX_CONCURRENT`)")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Leave"
+)]
+pub async fn leave_stats(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    query: web::Query<LeaveStatsQuery>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("leave.read")?;
+
+    let from = date_param::parse("from", query.from.as_deref())?;
+    let to = date_param::parse("to", query.to.as_deref())?;
+
+    if let (Some(from), Some(to)) = (from, to)
+        && from > to
+    {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "from must not be after to"
+        })));
+    }
+
+    let _permit = report_limit::try_acquire()?;
+
+    let groups = sqlx::query_as::<_, LeaveStatsGroup>(
+        r#"
+        SELECT leave_type, status, COUNT(*) AS count
+        FROM leave_requests
+        WHERE org_id = ?
+          AND (? IS NULL OR end_date >= ?)
+          AND (? IS NULL OR start_date <= ?)
+        GROUP BY leave_type, status
+        ORDER BY leave_type, status
+        "#,
+    )
+    .bind(auth.org_id)
+    .bind(from)
+    .bind(from)
+    .bind(to)
+    .bind(to)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to compute leave stats");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(LeaveStats {
+        from,
+        to,
+        total: groups.iter().map(|g| g.count).sum(),
+        groups,
+    }))
+}
+
+/* =========================
+Export leave requests (CSV)
+========================= */
+#[utoipa::path(
+    get,
+    path = "/api/v1/leave/export",
+    responses(
+        (status = 200, description = "CSV file, streamed; ends with a `# truncated` line if the row cap was hit", content_type = "text/csv", body = String),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 503, description = "Too many reports running (`REPORT_MAX_CONCURRENT`)")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Leave"
+)]
+pub async fn export_leaves(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("leave.export")?;
+    let holidays = calendar::holidays(pool.get_ref(), auth.org_id).await?;
+    let permit = report_limit::try_acquire()?;
+
+    Ok(stream_csv::<LeaveResponse, _>(
+        permit,
+        pool.get_ref().clone(),
+        "leave_requests.csv",
+        format!(
+            r#"
+            SELECT id, employee_id, start_date, end_date, leave_type, status, created_at,
+                   reason, decision_note, approved_by, approved_at, {}
+            FROM leave_requests
+            WHERE org_id = ?
+            ORDER BY id
+            LIMIT ?
+            "#,
+            APPROVER_NAME_COLUMN
+        ),
+        vec![SqlValue::I64(auth.org_id as i64)],
+        &[
+            "id",
+            "employee_id",
+            "start_date",
+            "end_date",
+            "leave_type",
+            "status",
+            "created_at",
+            "reason",
+            "decision_note",
+            "approved_by",
+            "approved_at",
+            "approved_by_name",
+            "business_days",
+        ],
+        config.export_max_rows,
+        move |l| {
+            vec![
+                l.id.to_string(),
+                l.employee_id.to_string(),
+                l.start_date.to_string(),
+                l.end_date.to_string(),
+                l.leave_type.clone(),
+                l.status.clone().unwrap_or_default(),
+                l.created_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                l.reason.clone().unwrap_or_default(),
+                l.decision_note.clone().unwrap_or_default(),
+                l.approved_by.map(|v| v.to_string()).unwrap_or_default(),
+                l.approved_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                l.approved_by_name.clone().unwrap_or_default(),
+                business_days_between(l.start_date, l.end_date, &holidays).to_string(),
+            ]
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use LeaveStatus::{Approved, Cancelled, Pending, Rejected};
+
+    const ALL: [LeaveStatus; 4] = [Pending, Approved, Rejected, Cancelled];
+
+    const ALLOWED: [(LeaveStatus, LeaveStatus); 4] = [
+        (Pending, Approved),
+        (Pending, Rejected),
+        (Pending, Cancelled),
+        (Approved, Cancelled),
+    ];
+
+    #[test]
+    fn allowed_transitions_pass() {
+        for (from, to) in ALLOWED {
+            assert!(
+                ensure_transition(from, to).is_ok(),
+                "{:?} -> {:?}",
+                from,
+                to
+            );
+        }
+    }
+
+    #[test]
+    fn every_other_transition_is_409() {
+        for from in ALL {
+            for to in ALL {
+                if ALLOWED.contains(&(from, to)) {
+                    continue;
+                }
+
+                let err = ensure_transition(from, to).expect_err("transition should be refused");
+                let response = err.error_response();
+                assert_eq!(
+                    response.status(),
+                    StatusCode::CONFLICT,
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn refusal_names_both_statuses() {
+        let err = ensure_transition(Rejected, Cancelled).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Cannot change leave status from rejected to cancelled"
+        );
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn leave_within_a_year_is_one_entry() {
+        // Mon 2026-03-02 .. Fri 2026-03-06
+        let days = days_per_year(date(2026, 3, 2), date(2026, 3, 6), &HashSet::new());
+
+        assert_eq!(days, vec![(2026, 5)]);
+    }
+
+    #[test]
+    fn leave_across_new_year_is_split() {
+        // Mon 2026-12-28 .. Fri 2027-01-08, with New Year's Day off
+        let holidays = HashSet::from([date(2027, 1, 1)]);
+        let days = days_per_year(date(2026, 12, 28), date(2027, 1, 8), &holidays);
+
+        assert_eq!(days, vec![(2026, 4), (2027, 5)]);
+    }
+
+    #[test]
+    fn years_without_working_days_are_left_out() {
+        // Thu 2026-12-31 .. Sun 2027-01-03, with New Year's Day off
+        let holidays = HashSet::from([date(2027, 1, 1)]);
+        let days = days_per_year(date(2026, 12, 31), date(2027, 1, 3), &holidays);
+
+        assert_eq!(days, vec![(2026, 1)]);
+    }
+
+    #[test]
+    fn deduction_leaves_the_rest() {
+        assert_eq!(remaining_after(20, 5), Some(15));
+        assert_eq!(remaining_after(5, 5), Some(0));
+        assert_eq!(remaining_after(5, 0), Some(5));
+    }
+
+    #[test]
+    fn insufficient_balance_is_refused() {
+        assert_eq!(remaining_after(4, 5), None);
+        assert_eq!(remaining_after(0, 1), None);
+        // already negative (e.g. after a manual adjustment)
+        assert_eq!(remaining_after(-1, 1), None);
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn another_organizations_leave_is_not_found() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::http::StatusCode;
+        use actix_web::test::{TestRequest, call_service, init_service};
+
+        let pool = test_support::pool().await;
+        let (org_a, org_b) = (test_support::new_org(), test_support::new_org());
+        let hr_a = test_support::user(&pool, org_a, None, Role::Hr).await;
+        let hr_b = test_support::user(&pool, org_b, None, Role::Hr).await;
+        let employee_b = test_support::employee(&pool, org_b).await;
+        let leave_b = test_support::leave(
+            &pool,
+            org_b,
+            employee_b,
+            "2026-03-02",
+            "2026-03-03",
+            "pending",
+        )
+        .await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/leave/{id}", web::get().to(get_leave)),
+        )
+        .await;
+        let get = |user: &test_support::TestUser| {
+            TestRequest::get()
+                .uri(&format!("/leave/{}", leave_b))
+                .insert_header(user.bearer())
+                .to_request()
+        };
+
+        assert_eq!(
+            call_service(&app, get(&hr_a)).await.status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            call_service(&app, get(&hr_b)).await.status(),
+            StatusCode::OK
+        );
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn employees_fetch_only_their_own_leave() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::test::{TestRequest, call_service, init_service};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let (alice, bob) = (
+            test_support::employee(&pool, org).await,
+            test_support::employee(&pool, org).await,
+        );
+        let alice_user = test_support::user(&pool, org, Some(alice), Role::Employee).await;
+        let hr = test_support::user(&pool, org, None, Role::Hr).await;
+        let own =
+            test_support::leave(&pool, org, alice, "2026-03-02", "2026-03-03", "pending").await;
+        let bobs =
+            test_support::leave(&pool, org, bob, "2026-03-02", "2026-03-03", "pending").await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/leave/{id}", web::get().to(get_leave)),
+        )
+        .await;
+        let get = |user: &test_support::TestUser, leave_id: u64| {
+            TestRequest::get()
+                .uri(&format!("/leave/{}", leave_id))
+                .insert_header(user.bearer())
+                .to_request()
+        };
+
+        assert_eq!(
+            call_service(&app, get(&alice_user, own)).await.status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            call_service(&app, get(&alice_user, bobs)).await.status(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            call_service(&app, get(&hr, bobs)).await.status(),
+            StatusCode::OK
+        );
+        // a missing id is 404 for everyone, not 403
+        let missing = bobs + 1_000_000;
+        assert_eq!(
+            call_service(&app, get(&alice_user, missing)).await.status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            call_service(&app, get(&hr, missing)).await.status(),
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn count_matches_the_list_total() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let hr = test_support::user(&pool, org, None, Role::Hr).await;
+        let (alice, bob) = (
+            test_support::employee(&pool, org).await,
+            test_support::employee(&pool, org).await,
+        );
+        for (employee, leave_type, start, end, status) in [
+            (alice, "annual", "2026-02-02", "2026-02-03", "pending"),
+            (alice, "sick", "2026-03-02", "2026-03-02", "approved"),
+            (alice, "annual", "2026-07-06", "2026-07-10", "approved"),
+            (bob, "annual", "2026-02-09", "2026-02-10", "pending"),
+        ] {
+            test_support::leave_of_type(&pool, org, employee, leave_type, start, end, status).await;
+        }
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/leave", web::get().to(leave_list))
+                .route("/leave/count", web::get().to(count_leaves)),
+        )
+        .await;
+
+        for filters in [
+            String::new(),
+            format!("employee_id={}", alice),
+            "status=pending".to_string(),
+            "leave_type=annual&from_date=2026-06-01".to_string(),
+            format!("employee_id={}&to_date=2026-02-28", bob),
+        ] {
+            let get = |path: &str| {
+                TestRequest::get()
+                    .uri(&format!("{}?per_page=1&{}", path, filters))
+                    .insert_header(hr.bearer())
+                    .to_request()
+            };
+            let list: serde_json::Value =
+                read_body_json(call_service(&app, get("/leave")).await).await;
+            let count: serde_json::Value =
+                read_body_json(call_service(&app, get("/leave/count")).await).await;
+
+            assert_eq!(count["count"], list["total"], "{}", filters);
+        }
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn approver_name_is_shown_for_decided_leaves() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let hr_employee = test_support::employee(&pool, org).await;
+        let hr = test_support::user(&pool, org, Some(hr_employee), Role::Hr).await;
+        // an approver without an employee record is shown by username
+        let admin = test_support::user(&pool, org, None, Role::Admin).await;
+        let employee = test_support::employee(&pool, org).await;
+        let by_hr =
+            test_support::leave(&pool, org, employee, "2026-03-02", "2026-03-03", "approved").await;
+        let by_admin =
+            test_support::leave(&pool, org, employee, "2026-04-06", "2026-04-07", "approved").await;
+        let pending =
+            test_support::leave(&pool, org, employee, "2026-05-04", "2026-05-05", "pending").await;
+        for (leave_id, approver) in [(by_hr, hr.user_id), (by_admin, admin.user_id)] {
+            sqlx::query(
+                "UPDATE leave_requests SET approved_by = ?, approved_at = NOW() WHERE id = ?",
+            )
+            .bind(approver)
+            .bind(leave_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let admin_username: String = sqlx::query_scalar("SELECT username FROM users WHERE id = ?")
+            .bind(admin.user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/leave", web::get().to(leave_list))
+                .route("/leave/{id}", web::get().to(get_leave)),
+        )
+        .await;
+        let get = |uri: String| {
+            TestRequest::get()
+                .uri(&uri)
+                .insert_header(hr.bearer())
+                .to_request()
+        };
+
+        let expected = [
+            (by_hr, serde_json::json!("Test Employee")),
+            (by_admin, serde_json::json!(admin_username)),
+            (pending, serde_json::Value::Null),
+        ];
+        for (leave_id, name) in &expected {
+            let leave: serde_json::Value =
+                read_body_json(call_service(&app, get(format!("/leave/{}", leave_id))).await).await;
+            assert_eq!(leave["approved_by_name"], *name, "leave {}", leave_id);
+        }
+
+        let list: serde_json::Value = read_body_json(
+            call_service(&app, get(format!("/leave?employee_id={}", employee))).await,
+        )
+        .await;
+        for leave in list["data"].as_array().unwrap() {
+            let (_, name) = expected.iter().find(|(id, _)| leave["id"] == *id).unwrap();
+            assert_eq!(leave["approved_by_name"], *name);
+        }
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn stats_are_grouped_by_type_and_status() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let hr = test_support::user(&pool, org, None, Role::Hr).await;
+        let employee = test_support::employee(&pool, org).await;
+        for (leave_type, start, end, status) in [
+            ("sick", "2026-02-02", "2026-02-03", "pending"),
+            ("sick", "2026-05-04", "2026-05-04", "pending"),
+            ("sick", "2026-06-01", "2026-06-01", "rejected"),
+            ("annual", "2026-03-02", "2026-03-06", "approved"),
+            // overlaps the start of the range, so it counts
+            ("annual", "2025-12-29", "2026-01-02", "approved"),
+            // outside the range
+            ("annual", "2025-07-01", "2025-07-03", "approved"),
+        ] {
+            test_support::leave_of_type(&pool, org, employee, leave_type, start, end, status).await;
+        }
+        // another organization's leave is not counted
+        let other_org = test_support::new_org();
+        let other = test_support::employee(&pool, other_org).await;
+        test_support::leave(
+            &pool,
+            other_org,
+            other,
+            "2026-03-02",
+            "2026-03-03",
+            "pending",
+        )
+        .await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/leave/stats", web::get().to(leave_stats)),
+        )
+        .await;
+        let res = call_service(
+            &app,
+            TestRequest::get()
+                .uri("/leave/stats?from=2026-01-01&to=2026-12-31")
+                .insert_header(hr.bearer())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let stats: serde_json::Value = read_body_json(res).await;
+        assert_eq!(stats["total"], 5);
+        let mut groups: Vec<(String, String, i64)> = stats["groups"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|g| {
+                (
+                    g["leave_type"].as_str().unwrap().to_string(),
+                    g["status"].as_str().unwrap().to_string(),
+                    g["count"].as_i64().unwrap(),
+                )
+            })
+            .collect();
+        groups.sort();
+        assert_eq!(
+            groups,
+            [
+                ("annual".to_string(), "approved".to_string(), 2),
+                ("sick".to_string(), "pending".to_string(), 2),
+                ("sick".to_string(), "rejected".to_string(), 1),
+            ]
+        );
+    }
+
+    /// Remaining annual days of `employee_id` in 2026
+    async fn remaining_2026(pool: &MySqlPool, employee_id: u64) -> i64 {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT remaining_days FROM leave_balances WHERE employee_id = ? AND year = 2026 AND leave_type = 'annual'",
+        )
+        .bind(employee_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn concurrent_approvals_deduct_once() {
+        use crate::config::Config;
+        use crate::model::role::Role;
+        use crate::test_support;
+        use actix_web::App;
+        use actix_web::http::StatusCode;
+        use actix_web::test::{TestRequest, call_service, init_service};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let hr = test_support::user(&pool, org, None, Role::Hr).await;
+        let employee = test_support::employee(&pool, org).await;
+        // Mon-Fri: 5 working days each, then a Mon-Tue of 2
+        let week_1 =
+            test_support::leave(&pool, org, employee, "2026-03-02", "2026-03-06", "pending").await;
+        let week_2 =
+            test_support::leave(&pool, org, employee, "2026-03-09", "2026-03-13", "pending").await;
+        let short =
+            test_support::leave(&pool, org, employee, "2026-03-16", "2026-03-17", "pending").await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::for_test(&[(
+                    "ANNUAL_LEAVE_DAYS",
+                    "8",
+                )])))
+                .route("/leave/{id}/approve", web::put().to(approve_leave)),
+        )
+        .await;
+        let approve = |leave_id: u64| {
+            TestRequest::put()
+                .uri(&format!("/leave/{}/approve", leave_id))
+                .insert_header(hr.bearer())
+                .to_request()
+        };
+
+        let sorted = |a: StatusCode, b: StatusCode| if a <= b { [a, b] } else { [b, a] };
+
+        // 8 days cover one week but not both, whichever is approved first
+        let (first, second) = futures::join!(
+            call_service(&app, approve(week_1)),
+            call_service(&app, approve(week_2))
+        );
+        assert_eq!(
+            sorted(first.status(), second.status()),
+            [StatusCode::OK, StatusCode::CONFLICT]
+        );
+        assert_eq!(remaining_2026(&pool, employee).await, 3);
+
+        // the same request approved twice at once
+        let (first, second) = futures::join!(
+            call_service(&app, approve(short)),
+            call_service(&app, approve(short))
+        );
+        assert_eq!(
+            sorted(first.status(), second.status()),
+            [StatusCode::OK, StatusCode::CONFLICT]
+        );
+        assert_eq!(remaining_2026(&pool, employee).await, 1);
+    }
+
+    fn sick_leave(start: &str, end: &str) -> CreateLeave {
+        CreateLeave {
+            start_date: chrono::NaiveDate::parse_from_str(start, "%Y-%m-%d").unwrap(),
+            end_date: chrono::NaiveDate::parse_from_str(end, "%Y-%m-%d").unwrap(),
+            leave_type: "sick".to_string(),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn manager_is_told_about_the_submitted_leave() {
+        let contact = ManagerContact {
+            first_name: "Ann".to_string(),
+            last_name: Some("Lee".to_string()),
+            manager_id: 7,
+            manager_email: Some("boss@example.com".to_string()),
+        };
+
+        let notification =
+            leave_submitted(contact, 1001, 55, &sick_leave("2030-03-04", "2030-03-05")).unwrap();
+
+        assert_eq!(notification.event, "leave.submitted");
+        assert_eq!(notification.recipient, "boss@example.com");
+        assert_eq!(notification.recipient_id, 7);
+        assert_eq!(notification.payload["leave_id"], 55);
+        assert_eq!(notification.payload["employee_id"], 1001);
+        assert_eq!(notification.payload["employee_name"], "Ann Lee");
+        assert_eq!(notification.payload["start_date"], "2030-03-04");
+    }
+
+    #[test]
+    fn manager_without_email_is_skipped() {
+        for email in [None, Some("  ".to_string())] {
+            let contact = ManagerContact {
+                first_name: "Ann".to_string(),
+                last_name: None,
+                manager_id: 7,
+                manager_email: email,
+            };
+            assert!(
+                leave_submitted(contact, 1001, 55, &sick_leave("2030-03-04", "2030-03-05"))
+                    .is_none()
+            );
+        }
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn submitting_a_leave_notifies_the_manager() {
+        use crate::model::role::Role;
+        use crate::test_support;
+        use crate::utils::notifier::RecordingNotifier;
+        use actix_web::App;
+        use actix_web::http::StatusCode;
+        use actix_web::test::{TestRequest, call_service, init_service};
+        use std::sync::Arc;
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let manager = test_support::employee(&pool, org).await;
+        let employee = test_support::employee(&pool, org).await;
+        sqlx::query("UPDATE employees SET manager_id = ? WHERE id = ?")
+            .bind(manager)
+            .bind(employee)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let user = test_support::user(&pool, org, Some(employee), Role::Employee).await;
+
+        let recorder = Arc::new(RecordingNotifier::default());
+        let notifier: web::Data<dyn Notifier> =
+            web::Data::from(recorder.clone() as Arc<dyn Notifier>);
+        let config = Config::for_test(&[("LEAVE_NOTIFICATIONS_ENABLED", "true")]);
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(config))
+                .app_data(notifier)
+                .route("/leave", web::post().to(create_leave)),
+        )
+        .await;
+
+        let res = call_service(
+            &app,
+            TestRequest::post()
+                .uri("/leave")
+                .insert_header(user.bearer())
+                .set_json(serde_json::json!({
+                    "start_date": "2030-03-04",
+                    "end_date": "2030-03-05",
+                    "leave_type": "sick"
+                }))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let sent = recorder.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].event, "leave.submitted");
+        assert_eq!(sent[0].recipient_id, manager);
+        assert_eq!(sent[0].payload["employee_id"], employee);
+    }
 }
@@ -1,4 +1,12 @@
 pub mod employee;
 pub mod attendance;
 pub mod leave_request;
-pub mod payroll;
\ No newline at end of file
+pub mod payroll;
+pub mod department;
+pub mod job_title;
+pub mod user;
+pub mod admin;
+pub mod leave_balance;
+pub mod timeline;
+pub mod audit;
+pub mod holiday;
\ No newline at end of file
@@ -1,16 +1,31 @@
-use actix_web::{HttpResponse, Responder, web};
-use chrono::NaiveDate;
+use actix_web::{
+    HttpRequest, HttpResponse, Responder,
+    http::header::{CONTENT_DISPOSITION, LINK},
+    web,
+};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::MySqlPool;
+use sqlx::{FromRow, MySqlPool};
 use utoipa::{IntoParams, ToSchema};
 
 use crate::auth::auth::AuthUser;
+use crate::config::Config;
+use crate::model::payroll::{PayrollStatus, compute_net_salary};
+use crate::model::role::Role;
+use crate::utils::audit::{self, AuditEntry};
+use crate::utils::date_param;
+use crate::utils::db_utils::{SqlValue, bind_values};
+use crate::utils::omit_null;
+use crate::utils::precondition::{ensure_unmodified, if_unmodified_since};
+use crate::utils::pagination::{CountResponse, link_header};
+use crate::utils::payslip_pdf::{self, PDF_CONTENT_TYPE, Payslip};
 
 #[derive(Deserialize, ToSchema)]
 pub struct CreatePayroll {
     #[schema(example = 1001)]
     pub employee_id: u64,
 
+    /// First day of the payroll month
     #[schema(example = "2026-01-01", value_type = String, format = "date")]
     pub month: NaiveDate,
 
@@ -36,7 +51,7 @@ pub struct UpdatePayroll {
     pub deductions: Option<f64>,
 }
 
-#[derive(Serialize, ToSchema)]
+#[derive(Serialize, FromRow, ToSchema)]
 pub struct PayrollResponse {
     pub id: u64,
     pub employee_id: u64,
@@ -48,8 +63,38 @@ pub struct PayrollResponse {
     pub bonus: f64,
     pub deductions: f64,
     pub net_salary: f64,
+
+    /// base_salary + bonus
+    pub gross: f64,
+    /// Tax withheld from gross (`PAYROLL_TAX_PERCENT` at the time it was computed)
+    pub tax: f64,
+
+    /// draft, approved or paid
+    #[schema(example = "draft")]
+    pub status: String,
+
+    /// user id of the approver
+    #[serde(skip_serializing_if = "omit_null::skip")]
+    pub approved_by: Option<u64>,
+    #[schema(value_type = Option<String>, format = "date-time")]
+    #[serde(skip_serializing_if = "omit_null::skip")]
+    pub approved_at: Option<DateTime<Utc>>,
+
+    /// user id of whoever marked it paid
+    #[serde(skip_serializing_if = "omit_null::skip")]
+    pub paid_by: Option<u64>,
+    #[schema(value_type = Option<String>, format = "date-time")]
+    #[serde(skip_serializing_if = "omit_null::skip")]
+    pub paid_at: Option<DateTime<Utc>>,
 }
 
+/// Columns `PAYROLL_LIST_SORT` may name
+pub const SORT_COLUMNS: &[&str] = &["id", "month", "net_salary"];
+
+/// Columns selected into `PayrollResponse`
+const PAYROLL_COLUMNS: &str = "id, employee_id, month, base_salary, bonus, deductions, net_salary, \
+     base_salary + bonus AS gross, tax, status, approved_by, approved_at, paid_by, paid_at";
+
 #[derive(Deserialize, IntoParams, ToSchema)]
 pub struct PayrollQuery {
     #[schema(example = 1)]
@@ -60,6 +105,31 @@ pub struct PayrollQuery {
 
     #[schema(example = 1001)]
     pub employee_id: Option<u64>,
+
+    /// draft, approved or paid
+    #[schema(example = "approved")]
+    pub status: Option<String>,
+
+    /// First month to include, `YYYY-MM`
+    #[schema(example = "2026-01")]
+    pub from_month: Option<String>,
+
+    /// Last month to include, `YYYY-MM`
+    #[schema(example = "2026-03")]
+    pub to_month: Option<String>,
+}
+
+/// Sums over every payroll matching the filters, not just the current page
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct PayrollTotals {
+    #[schema(example = 150000.0)]
+    pub base_salary: f64,
+    #[schema(example = 15000.0)]
+    pub bonus: f64,
+    #[schema(example = 6000.0)]
+    pub deductions: f64,
+    #[schema(example = 159000.0)]
+    pub net_salary: f64,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -68,6 +138,79 @@ pub struct PaginatedPayrollResponse {
     pub page: u32,
     pub per_page: u32,
     pub total: i64,
+    pub totals: PayrollTotals,
+}
+
+/// An employee's view of their own payroll: no approver/payer user ids
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct PayslipResponse {
+    pub id: u64,
+
+    #[schema(value_type = String, format = "date")]
+    pub month: NaiveDate,
+
+    pub base_salary: f64,
+    pub bonus: f64,
+    pub deductions: f64,
+    pub net_salary: f64,
+
+    /// approved or paid
+    #[schema(example = "paid")]
+    pub status: String,
+
+    #[schema(value_type = Option<String>, format = "date-time")]
+    #[serde(skip_serializing_if = "omit_null::skip")]
+    pub paid_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, IntoParams, ToSchema)]
+pub struct PayslipQuery {
+    #[schema(example = 1)]
+    pub page: Option<u32>,
+
+    #[schema(example = 12)]
+    pub per_page: Option<u32>,
+}
+
+#[derive(Deserialize, IntoParams, ToSchema)]
+pub struct PayrollByMonthQuery {
+    #[schema(example = 1001)]
+    pub employee_id: u64,
+
+    /// Year and month, `YYYY-MM`
+    #[schema(example = "2026-01")]
+    pub month: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PaginatedPayslipResponse {
+    pub data: Vec<PayslipResponse>,
+    pub page: u32,
+    pub per_page: u32,
+    pub total: i64,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct GeneratePayroll {
+    /// First day of the payroll month
+    #[schema(example = "2026-01-01", value_type = String, format = "date")]
+    pub month: NaiveDate,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct GeneratePayrollResponse {
+    /// Draft payrolls created
+    #[schema(example = 42)]
+    pub created: u64,
+    /// Active employees that already had a payroll for the month or have no base salary
+    #[schema(example = 3)]
+    pub skipped: u64,
+}
+
+fn duplicate_payroll() -> HttpResponse {
+    HttpResponse::Conflict().json(serde_json::json!({
+        "message": "Payroll already exists for this employee and month"
+    }))
 }
 
 #[utoipa::path(
@@ -76,8 +219,16 @@ pub struct PaginatedPayrollResponse {
     request_body = CreatePayroll,
     responses(
         (status = 201, description = "Payroll created"),
+        (status = 400, description = "month is not the first day of a month", body = Object, example = json!({
+            "message": "month must be the first day of a month"
+        })),
         (status = 401),
-        (status = 403)
+        (status = 403),
+        (status = 404, description = "Employee not found"),
+        (status = 409, description = "Payroll already exists for this employee and month", body = Object, example = json!({
+            "message": "Payroll already exists for this employee and month"
+        })),
+        (status = 500, description = "Internal server error")
     ),
     security(("bearer_auth" = [])),
     tag = "Payroll"
@@ -85,28 +236,91 @@ pub struct PaginatedPayrollResponse {
 pub async fn create_payroll(
     auth: AuthUser,
     pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
     payload: web::Json<CreatePayroll>,
 ) -> actix_web::Result<impl Responder> {
-    auth.require_admin()?;
+    auth.require_permission("payroll.create")?;
+
+    // one row per month, keyed by its first day, keeps per-month aggregation clean
+    if payload.month.day() != 1 {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "month must be the first day of a month"
+        })));
+    }
+
+    // the employee must belong to the caller's organization
+    let employee_exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM employees WHERE id = ? AND org_id = ?)",
+    )
+    .bind(payload.employee_id)
+    .bind(auth.org_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id = payload.employee_id, "Failed to look up employee");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    if !employee_exists {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "message": "Employee not found"
+        })));
+    }
+
+    let already_exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM payroll WHERE org_id = ? AND employee_id = ? AND month = ?)",
+    )
+    .bind(auth.org_id)
+    .bind(payload.employee_id)
+    .bind(payload.month)
+    .fetch_one(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id = payload.employee_id, "Failed to check existing payroll");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    if already_exists {
+        return Ok(duplicate_payroll());
+    }
 
-    let net_salary = payload.base_salary + payload.bonus - payload.deductions;
+    let amounts = compute_net_salary(
+        payload.base_salary,
+        payload.bonus,
+        payload.deductions,
+        config.payroll_tax_percent,
+    );
 
-    sqlx::query!(
+    let inserted = sqlx::query!(
         r#"
         INSERT INTO payroll
-        (employee_id, month, base_salary, bonus, deductions, net_salary)
-        VALUES (?, ?, ?, ?, ?, ?)
+        (org_id, employee_id, month, base_salary, bonus, deductions, tax, net_salary)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
         "#,
+        auth.org_id,
         payload.employee_id,
         payload.month,
         payload.base_salary,
         payload.bonus,
         payload.deductions,
-        net_salary,
+        amounts.tax,
+        amounts.net,
     )
     .execute(pool.get_ref())
     .await;
 
+    match inserted {
+        Ok(_) => {}
+        // a concurrent create for the same month won the unique key
+        Err(sqlx::Error::Database(db_err)) if db_err.code().as_deref() == Some("23000") => {
+            return Ok(duplicate_payroll());
+        }
+        Err(e) => {
+            tracing::error!(error = %e, employee_id = payload.employee_id, "Failed to create payroll");
+            return Err(actix_web::error::ErrorInternalServerError("Internal Server Error"));
+        }
+    }
+
     Ok(HttpResponse::Created().json(serde_json::json!({
         "message": "Payroll created successfully"
     })))
@@ -117,11 +331,16 @@ pub async fn create_payroll(
     path = "/api/v1/payroll/{payroll_id}",
     request_body = UpdatePayroll,
     params(
-        ("payroll_id", description = "Payroll ID")
+        ("payroll_id", description = "Payroll ID"),
+        ("If-Unmodified-Since" = Option<String>, Header, description = "Only proceed if the payroll hasn't changed since this HTTP date")
     ),
     responses(
         (status = 200, description = "Payroll updated"),
-        (status = 404, description = "Payroll not found")
+        (status = 404, description = "Payroll not found, or no longer a draft by the time of the update"),
+        (status = 412, description = "Payroll was modified after If-Unmodified-Since"),
+        (status = 409, description = "Payroll is no longer a draft", body = Object, example = json!({
+            "message": "Only draft payrolls can be edited (current status: paid)"
+        }))
     ),
     security(("bearer_auth" = [])),
     tag = "Payroll"
@@ -129,22 +348,25 @@ pub async fn create_payroll(
 
 pub async fn update_payroll(
     auth: AuthUser,
+    req: HttpRequest,
     pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
     path: web::Path<u64>,
     body: web::Json<UpdatePayroll>,
 ) -> actix_web::Result<impl Responder> {
-    auth.require_admin()?;
+    auth.require_permission("payroll.update")?;
 
     let payroll_id = path.into_inner();
 
-    let current = sqlx::query!(
+    let current = sqlx::query_as::<_, (f64, f64, f64, String, Option<DateTime<Utc>>)>(
         r#"
-    SELECT base_salary, bonus, deductions
+    SELECT base_salary, bonus, deductions, status, updated_at
     FROM payroll
-    WHERE id = ?
+    WHERE id = ? AND org_id = ?
     "#,
-        payroll_id
     )
+    .bind(payroll_id)
+    .bind(auth.org_id)
     .fetch_optional(pool.get_ref())
     .await
     .map_err(|e| {
@@ -152,7 +374,7 @@ pub async fn update_payroll(
         actix_web::error::ErrorInternalServerError("Internal Server Error")
     })?;
 
-    let current = match current {
+    let (current_base, current_bonus, current_deductions, status, updated_at) = match current {
         Some(c) => c,
         None => {
             return Ok(HttpResponse::NotFound().json(serde_json::json!({
@@ -161,25 +383,48 @@ pub async fn update_payroll(
         }
     };
 
-    let base_salary = body.base_salary.unwrap_or(current.base_salary);
-    let bonus = body.bonus.unwrap_or(current.bonus);
-    let deductions = body.deductions.unwrap_or(current.deductions);
-    let net_salary = base_salary + bonus - deductions;
+    ensure_unmodified(if_unmodified_since(&req), updated_at)?;
+
+    if PayrollStatus::from_name(&status) != Some(PayrollStatus::Draft) {
+        return Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "message": format!("Only draft payrolls can be edited (current status: {})", status)
+        })));
+    }
+
+    let base_salary = body.base_salary.unwrap_or(current_base);
+    let bonus = body.bonus.unwrap_or(current_bonus);
+    let deductions = body.deductions.unwrap_or(current_deductions);
+    let amounts = compute_net_salary(base_salary, bonus, deductions, config.payroll_tax_percent);
 
-    sqlx::query!(
+    let updated = sqlx::query!(
         r#"
         UPDATE payroll
-        SET base_salary = ?, bonus = ?, deductions = ?, net_salary = ?
+        SET base_salary = ?, bonus = ?, deductions = ?, tax = ?, net_salary = ?
         WHERE id = ?
+        AND org_id = ?
+        AND status = 'draft'
         "#,
         base_salary,
         bonus,
         deductions,
-        net_salary,
-        payroll_id
+        amounts.tax,
+        amounts.net,
+        payroll_id,
+        auth.org_id
     )
     .execute(pool.get_ref())
-    .await;
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, payroll_id, "Failed to update payroll");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    // deleted, or approved by someone else, since the SELECT above
+    if updated.rows_affected() == 0 {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "message": "Payroll record not found"
+        })));
+    }
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "message": "Payroll updated successfully"
@@ -204,19 +449,16 @@ pub async fn get_payroll(
     pool: web::Data<MySqlPool>,
     path: web::Path<u64>,
 ) -> actix_web::Result<impl Responder> {
-    auth.require_admin()?;
+    auth.require_permission("payroll.read")?;
 
     let payroll_id = path.into_inner();
 
-    let payroll = sqlx::query_as!(
-        PayrollResponse,
-        r#"
-        SELECT id, employee_id, month, base_salary, bonus, deductions, net_salary
-        FROM payroll
-        WHERE id = ?
-        "#,
-        payroll_id
-    )
+    let payroll = sqlx::query_as::<_, PayrollResponse>(&format!(
+        "SELECT {} FROM payroll WHERE id = ? AND org_id = ?",
+        PAYROLL_COLUMNS
+    ))
+    .bind(payroll_id)
+    .bind(auth.org_id)
     .fetch_optional(pool.get_ref())
     .await
     .map_err(|e| {
@@ -232,12 +474,119 @@ pub async fn get_payroll(
     }
 }
 
+/// WHERE clause and bindings for the payroll list filters, shared by the list and count endpoints
+fn payroll_filters(org_id: u64, query: &PayrollQuery) -> actix_web::Result<(String, Vec<SqlValue>)> {
+    // always scoped to the caller's organization
+    let mut conditions = vec!["org_id = ?"];
+    let mut args = vec![SqlValue::I64(org_id as i64)];
+
+    if let Some(employee_id) = query.employee_id {
+        conditions.push("employee_id = ?");
+        args.push(SqlValue::I64(employee_id as i64));
+    }
+
+    if let Some(s) = query.status.as_deref() {
+        let status = PayrollStatus::from_name(s).ok_or_else(|| {
+            actix_web::error::ErrorBadRequest("Invalid status. Allowed: draft, approved, paid")
+        })?;
+        conditions.push("status = ?");
+        args.push(SqlValue::String(status.as_str().to_string()));
+    }
+
+    if let Some(from) = query.from_month.as_deref() {
+        conditions.push("month >= ?");
+        args.push(SqlValue::Date(date_param::parse_month("from_month", from)?));
+    }
+
+    if let Some(to) = query.to_month.as_deref() {
+        // inclusive: everything before the first day of the following month
+        let next = date_param::parse_month("to_month", to)?
+            .checked_add_months(chrono::Months::new(1))
+            .ok_or_else(|| actix_web::error::ErrorBadRequest("to_month out of range"))?;
+        conditions.push("month < ?");
+        args.push(SqlValue::Date(next));
+    }
+
+    Ok((format!("WHERE {}", conditions.join(" AND ")), args))
+}
+
+async fn count_matching_payrolls(
+    pool: &MySqlPool,
+    where_sql: &str,
+    args: Vec<SqlValue>,
+) -> actix_web::Result<i64> {
+    let count_sql = format!("SELECT COUNT(*) FROM payroll {}", where_sql);
+    let (count,) = bind_values(sqlx::query_as::<_, (i64,)>(&count_sql), args)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to count payrolls");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+    Ok(count)
+}
+
+async fn sum_matching_payrolls(
+    pool: &MySqlPool,
+    where_sql: &str,
+    args: Vec<SqlValue>,
+) -> actix_web::Result<PayrollTotals> {
+    let sum_sql = format!(
+        r#"
+        SELECT COALESCE(SUM(base_salary), 0) AS base_salary,
+               COALESCE(SUM(bonus), 0) AS bonus,
+               COALESCE(SUM(deductions), 0) AS deductions,
+               COALESCE(SUM(net_salary), 0) AS net_salary
+        FROM payroll
+        {}
+        "#,
+        where_sql
+    );
+
+    bind_values(sqlx::query_as::<_, PayrollTotals>(&sum_sql), args)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to sum payrolls");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })
+}
+
+/// Number of payrolls matching the list filters, without fetching them
+#[utoipa::path(
+    get,
+    path = "/api/v1/payroll/count",
+    params(PayrollQuery),
+    responses(
+        (status = 200, description = "Same number as `total` of the list endpoint with these filters", body = CountResponse),
+        (status = 400, description = "Invalid status, from_month or to_month")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Payroll"
+)]
+pub async fn count_payrolls(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    query: web::Query<PayrollQuery>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("payroll.read")?;
+
+    let (where_sql, args) = payroll_filters(auth.org_id, &query)?;
+    let count = count_matching_payrolls(pool.get_ref(), &where_sql, args).await?;
+
+    Ok(HttpResponse::Ok().json(CountResponse { count }))
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/payroll",
     params(PayrollQuery),
     responses(
-        (status = 200, body = PaginatedPayrollResponse)
+        (status = 200, body = PaginatedPayrollResponse,
+         headers(("Link" = String, description = "RFC 5988 pagination links (first, prev, next, last)"))),
+        (status = 400, description = "Invalid status, from_month or to_month"),
+        (status = 403, description = "Requires payroll.read or the api_user role")
     ),
     security(("bearer_auth" = [])),
     tag = "Payroll"
@@ -245,46 +594,896 @@ pub async fn get_payroll(
 
 pub async fn list_payrolls(
     auth: AuthUser,
+    req: HttpRequest,
     pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
     query: web::Query<PayrollQuery>,
 ) -> actix_web::Result<impl Responder> {
-    auth.require_admin()?;
+    // payroll integrations read the list without being granted the rest of the admin set
+    if !auth.has_permission("payroll.read") {
+        auth.require_roles(&[Role::ApiUser])?;
+    }
 
     let page = query.page.unwrap_or(1).max(1);
     let per_page = query.per_page.unwrap_or(10).clamp(1, 100);
     let offset = (page - 1) * per_page;
 
-    let total = sqlx::query_scalar!(r#"SELECT COUNT(*) FROM payroll"#)
-        .fetch_one(pool.get_ref())
+    let (where_sql, args) = payroll_filters(auth.org_id, &query)?;
+    let total = count_matching_payrolls(pool.get_ref(), &where_sql, args.clone()).await?;
+    let totals = sum_matching_payrolls(pool.get_ref(), &where_sql, args.clone()).await?;
+
+    let data_sql = format!(
+        r#"
+        SELECT {}
+        FROM payroll
+        {}
+        {}
+        LIMIT ? OFFSET ?
+        "#,
+        PAYROLL_COLUMNS,
+        where_sql,
+        config.payroll_list_sort.order_by()
+    );
+    let data = bind_values(sqlx::query_as::<_, PayrollResponse>(&data_sql), args)
+        .bind(per_page as i64)
+        .bind(offset as i64)
+        .fetch_all(pool.get_ref())
         .await
         .map_err(|e| {
-            tracing::error!(error = %e, "Failed to count payrolls");
+            tracing::error!(error = %e, "Failed to fetch payroll list");
             actix_web::error::ErrorInternalServerError("Internal Server Error")
         })?;
 
-    let data = sqlx::query_as!(
-        PayrollResponse,
+    let link = link_header(
+        &config.public_base_url,
+        &req,
+        page as u64,
+        per_page as u64,
+        total,
+    );
+
+    Ok(HttpResponse::Ok()
+        .insert_header((LINK, link))
+        .json(PaginatedPayrollResponse {
+            data,
+            page,
+            per_page,
+            total,
+            totals,
+        }))
+}
+
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/payroll/{payroll_id}/approve",
+    params(
+        ("payroll_id", description = "Payroll ID"),
+        ("If-Unmodified-Since" = Option<String>, Header, description = "Only proceed if the payroll hasn't changed since this HTTP date")
+    ),
+    responses(
+        (status = 200, description = "Payroll approved", body = Object, example = json!({
+            "message": "Payroll approved"
+        })),
+        (status = 404, description = "Payroll not found"),
+        (status = 409, description = "Payroll is not a draft", body = Object, example = json!({
+            "message": "Cannot change payroll status from paid to approved"
+        })),
+        (status = 412, description = "Payroll was modified after If-Unmodified-Since")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Payroll"
+)]
+pub async fn approve_payroll(
+    auth: AuthUser,
+    req: HttpRequest,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("payroll.approve")?;
+
+    transition_payroll(
+        pool.get_ref(),
+        auth.org_id,
+        path.into_inner(),
+        PayrollStatus::Approved,
+        auth.user_id,
+        if_unmodified_since(&req),
+    )
+    .await
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/payroll/{payroll_id}/pay",
+    params(
+        ("payroll_id", description = "Payroll ID"),
+        ("If-Unmodified-Since" = Option<String>, Header, description = "Only proceed if the payroll hasn't changed since this HTTP date")
+    ),
+    responses(
+        (status = 200, description = "Payroll marked as paid", body = Object, example = json!({
+            "message": "Payroll paid"
+        })),
+        (status = 404, description = "Payroll not found"),
+        (status = 409, description = "Payroll is not approved", body = Object, example = json!({
+            "message": "Cannot change payroll status from draft to paid"
+        })),
+        (status = 412, description = "Payroll was modified after If-Unmodified-Since")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Payroll"
+)]
+pub async fn pay_payroll(
+    auth: AuthUser,
+    req: HttpRequest,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("payroll.pay")?;
+
+    transition_payroll(
+        pool.get_ref(),
+        auth.org_id,
+        path.into_inner(),
+        PayrollStatus::Paid,
+        auth.user_id,
+        if_unmodified_since(&req),
+    )
+    .await
+}
+
+/// Move a payroll to `next`, recording who did it and when; the status change and
+/// its audit entry are committed together
+async fn transition_payroll(
+    pool: &MySqlPool,
+    org_id: u64,
+    payroll_id: u64,
+    next: PayrollStatus,
+    actor_id: u64,
+    since: Option<DateTime<Utc>>,
+) -> actix_web::Result<HttpResponse> {
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, payroll_id, "Failed to start payroll transition");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let current = sqlx::query_as::<_, (String, Option<DateTime<Utc>>)>(
+        "SELECT status, updated_at FROM payroll WHERE id = ? AND org_id = ? FOR UPDATE",
+    )
+    .bind(payroll_id)
+    .bind(org_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, payroll_id, "Failed to fetch payroll");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let (current, updated_at) = match current {
+        Some(row) => row,
+        None => {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "message": "Payroll not found"
+            })));
+        }
+    };
+
+    ensure_unmodified(since, updated_at)?;
+
+    let allowed = PayrollStatus::from_name(&current).is_some_and(|from| from.can_transition_to(next));
+
+    let (by_column, at_column) = match next {
+        PayrollStatus::Approved if allowed => ("approved_by", "approved_at"),
+        PayrollStatus::Paid if allowed => ("paid_by", "paid_at"),
+        _ => {
+            return Ok(HttpResponse::Conflict().json(serde_json::json!({
+                "message": format!("Cannot change payroll status from {} to {}", current, next.as_str())
+            })));
+        }
+    };
+
+    // the row is locked, but the UPDATE still only applies to the state the checks above
+    // were made on: same status, no edit since (updated_at)
+    let result = sqlx::query(&format!(
+        "UPDATE payroll SET status = ?, {} = ?, {} = NOW() WHERE id = ? AND status = ? AND updated_at <=> ?",
+        by_column, at_column
+    ))
+    .bind(next.as_str())
+    .bind(actor_id)
+    .bind(payroll_id)
+    .bind(&current)
+    .bind(updated_at)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, payroll_id, status = next.as_str(), "Failed to update payroll status");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    if result.rows_affected() == 0 {
+        return Ok(HttpResponse::Conflict().json(serde_json::json!({
+            "message": "Payroll changed concurrently, reload and retry"
+        })));
+    }
+
+    audit::record(
+        &mut *tx,
+        AuditEntry {
+            org_id,
+            actor_user_id: Some(actor_id),
+            entity: "payroll",
+            entity_id: Some(payroll_id),
+            action: match next {
+                PayrollStatus::Paid => "pay",
+                _ => "approve",
+            },
+            details: Some(serde_json::json!({ "previous_status": current })),
+        },
+    )
+    .await?;
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, payroll_id, "Failed to commit payroll transition");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": format!("Payroll {}", next.as_str())
+    })))
+}
+
+/// The caller's own payslips. Drafts are still being prepared and are not shown.
+#[utoipa::path(
+    get,
+    path = "/api/v1/payroll/mine",
+    params(PayslipQuery),
+    responses(
+        (status = 200, body = PaginatedPayslipResponse,
+         headers(("Link" = String, description = "RFC 5988 pagination links (first, prev, next, last)"))),
+        (status = 403, description = "User has no linked employee profile")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Payroll"
+)]
+pub async fn my_payslips(
+    auth: AuthUser,
+    req: HttpRequest,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    query: web::Query<PayslipQuery>,
+) -> actix_web::Result<impl Responder> {
+    let employee_id: u64 = auth
+        .employee_id
+        .ok_or_else(|| actix_web::error::ErrorForbidden("No employee profile"))?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(12).clamp(1, 100);
+    let offset = (page - 1) * per_page;
+
+    let total = sqlx::query_scalar::<_, i64>(
         r#"
-        SELECT id, employee_id, month, base_salary, bonus, deductions, net_salary
+        SELECT COUNT(*)
         FROM payroll
-        ORDER BY month DESC
+        WHERE org_id = ? AND employee_id = ? AND status IN ('approved', 'paid')
+        "#,
+    )
+    .bind(auth.org_id)
+    .bind(employee_id)
+    .fetch_one(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to count payslips");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let data = sqlx::query_as::<_, PayslipResponse>(
+        r#"
+        SELECT id, month, base_salary, bonus, deductions, net_salary, status, paid_at
+        FROM payroll
+        WHERE org_id = ? AND employee_id = ? AND status IN ('approved', 'paid')
+        ORDER BY month DESC, id DESC
         LIMIT ? OFFSET ?
         "#,
-        per_page as i64,
-        offset as i64
     )
+    .bind(auth.org_id)
+    .bind(employee_id)
+    .bind(per_page as i64)
+    .bind(offset as i64)
     .fetch_all(pool.get_ref())
     .await
     .map_err(|e| {
-        tracing::error!(error = %e, "Failed to fetch payroll list");
+        tracing::error!(error = %e, employee_id, "Failed to fetch payslips");
         actix_web::error::ErrorInternalServerError("Internal Server Error")
     })?;
 
-    Ok(HttpResponse::Ok().json(PaginatedPayrollResponse {
-        data,
-        page,
-        per_page,
+    let link = link_header(
+        &config.public_base_url,
+        &req,
+        page as u64,
+        per_page as u64,
         total,
+    );
+
+    Ok(HttpResponse::Ok()
+        .insert_header((LINK, link))
+        .json(PaginatedPayslipResponse {
+            data,
+            page,
+            per_page,
+            total,
+        }))
+}
+
+/// One employee's payroll for a month. Payroll readers get the full record; an
+/// employee may look up their own, seen as a payslip (drafts are not shown).
+#[utoipa::path(
+    get,
+    path = "/api/v1/payroll/by-month",
+    params(PayrollByMonthQuery),
+    responses(
+        (status = 200, description = "PayrollResponse for payroll readers, PayslipResponse for the employee themself", body = PayrollResponse),
+        (status = 400, description = "month is not YYYY-MM", body = Object, example = json!({
+            "error": "invalid_date",
+            "field": "month",
+            "value": "2026-1"
+        })),
+        (status = 403, description = "Neither a payroll reader nor the employee"),
+        (status = 404, description = "No payroll for that employee and month")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Payroll"
+)]
+pub async fn payroll_by_month(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    query: web::Query<PayrollByMonthQuery>,
+) -> actix_web::Result<impl Responder> {
+    let full_access = auth.has_permission("payroll.read");
+    if !full_access && auth.employee_id != Some(query.employee_id) {
+        return Err(actix_web::error::ErrorForbidden("Missing permission: payroll.read"));
+    }
+
+    let start = date_param::parse_month("month", &query.month)?;
+    let end = start
+        .checked_add_months(chrono::Months::new(1))
+        .ok_or_else(|| actix_web::error::ErrorBadRequest("month out of range"))?;
+    let employee_id = query.employee_id;
+
+    let not_found = || {
+        HttpResponse::NotFound().json(serde_json::json!({
+            "message": "Payroll not found"
+        }))
+    };
+    let db_error = |e: sqlx::Error| {
+        tracing::error!(error = %e, employee_id, "Failed to fetch payroll by month");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    };
+
+    if full_access {
+        let payroll = sqlx::query_as::<_, PayrollResponse>(&format!(
+            r#"
+            SELECT {} FROM payroll
+            WHERE org_id = ? AND employee_id = ? AND month >= ? AND month < ?
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+            PAYROLL_COLUMNS
+        ))
+        .bind(auth.org_id)
+        .bind(employee_id)
+        .bind(start)
+        .bind(end)
+        .fetch_optional(pool.get_ref())
+        .await
+        .map_err(db_error)?;
+
+        return Ok(match payroll {
+            Some(p) => HttpResponse::Ok().json(p),
+            None => not_found(),
+        });
+    }
+
+    let payslip = sqlx::query_as::<_, PayslipResponse>(
+        r#"
+        SELECT id, month, base_salary, bonus, deductions, net_salary, status, paid_at
+        FROM payroll
+        WHERE org_id = ? AND employee_id = ? AND month >= ? AND month < ?
+        AND status IN ('approved', 'paid')
+        ORDER BY id DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(auth.org_id)
+    .bind(employee_id)
+    .bind(start)
+    .bind(end)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(db_error)?;
+
+    Ok(match payslip {
+        Some(p) => HttpResponse::Ok().json(p),
+        None => not_found(),
+    })
+}
+
+/// Download a payroll record as a PDF payslip. Payroll readers may download any;
+/// an employee only their own approved or paid ones.
+#[utoipa::path(
+    get,
+    path = "/api/v1/payroll/{payroll_id}/payslip",
+    params(
+        ("payroll_id", description = "Payroll ID")
+    ),
+    responses(
+        (status = 200, description = "PDF attachment", content_type = "application/pdf", body = Vec<u8>),
+        (status = 403, description = "Neither a payroll reader nor the owning employee"),
+        (status = 404, description = "Payroll not found")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Payroll"
+)]
+pub async fn download_payslip(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+) -> actix_web::Result<impl Responder> {
+    let payroll_id = path.into_inner();
+
+    let row = sqlx::query_as::<_, (u64, NaiveDate, f64, f64, f64, f64, f64, String, String, String, String)>(
+        r#"
+        SELECT p.employee_id, p.month, p.base_salary, p.bonus, p.deductions, p.tax, p.net_salary, p.status,
+               e.employee_code, e.first_name, e.last_name
+        FROM payroll p
+        JOIN employees e ON e.id = p.employee_id
+        WHERE p.id = ? AND p.org_id = ?
+        "#,
+    )
+    .bind(payroll_id)
+    .bind(auth.org_id)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, payroll_id, "Failed to fetch payroll for payslip");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let Some((
+        employee_id,
+        month,
+        base_salary,
+        bonus,
+        deductions,
+        tax,
+        net_salary,
+        status,
+        employee_code,
+        first_name,
+        last_name,
+    )) = row
+    else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "message": "Payroll not found"
+        })));
+    };
+
+    if !auth.has_permission("payroll.read") {
+        if auth.employee_id != Some(employee_id) {
+            return Err(actix_web::error::ErrorForbidden("Missing permission: payroll.read"));
+        }
+        // drafts are not visible to the employee yet, same as /payroll/mine
+        if PayrollStatus::from_name(&status) == Some(PayrollStatus::Draft) {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "message": "Payroll not found"
+            })));
+        }
+    }
+
+    let employee_name = format!("{} {}", first_name, last_name);
+    let pdf = payslip_pdf::render(&Payslip {
+        employee_name: &employee_name,
+        employee_code: &employee_code,
+        month,
+        base_salary,
+        bonus,
+        deductions,
+        tax,
+        net_salary,
+        status: &status,
+    })
+    .map_err(|e| {
+        tracing::error!(error = %e, payroll_id, "Failed to render payslip PDF");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(PDF_CONTENT_TYPE)
+        .insert_header((
+            CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"payslip-{}-{}.pdf\"",
+                employee_code,
+                month.format("%Y-%m")
+            ),
+        ))
+        .body(pdf))
+}
+
+/// Create draft payrolls for the month from each active employee's stored base salary
+#[utoipa::path(
+    post,
+    path = "/api/v1/payroll/generate",
+    request_body = GeneratePayroll,
+    responses(
+        (status = 200, description = "Payrolls created in one transaction; existing ones are left alone", body = GeneratePayrollResponse),
+        (status = 400, description = "month is not the first day of a month"),
+        (status = 403, description = "Forbidden")
+    ),
+    security(("bearer_auth" = [])),
+    tag = "Payroll"
+)]
+pub async fn generate_payrolls(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    payload: web::Json<GeneratePayroll>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("payroll.create")?;
+
+    let month = payload.month;
+    if month.day() != 1 {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "month must be the first day of a month"
+        })));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to start payroll generation transaction");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let active = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM employees WHERE org_id = ? AND status = 'active' AND deleted_at IS NULL",
+    )
+    .bind(auth.org_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to count active employees");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let pending = sqlx::query_as::<_, (u64, f64)>(
+        r#"
+        SELECT e.id, e.base_salary
+        FROM employees e
+        WHERE e.org_id = ?
+        AND e.status = 'active'
+        AND e.deleted_at IS NULL
+        AND e.base_salary IS NOT NULL
+        AND NOT EXISTS (
+            SELECT 1 FROM payroll p
+            WHERE p.org_id = e.org_id AND p.employee_id = e.id AND p.month = ?
+        )
+        FOR UPDATE
+        "#,
+    )
+    .bind(auth.org_id)
+    .bind(month)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, %month, "Failed to find employees for payroll generation");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let mut created = 0;
+
+    for (employee_id, base_salary) in pending {
+        let amounts = compute_net_salary(base_salary, 0.0, 0.0, config.payroll_tax_percent);
+
+        sqlx::query(
+            r#"
+            INSERT INTO payroll
+            (org_id, employee_id, month, base_salary, bonus, deductions, tax, net_salary)
+            VALUES (?, ?, ?, ?, 0, 0, ?, ?)
+            "#,
+        )
+        .bind(auth.org_id)
+        .bind(employee_id)
+        .bind(month)
+        .bind(base_salary)
+        .bind(amounts.tax)
+        .bind(amounts.net)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, employee_id, %month, "Failed to generate payroll");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+        created += 1;
+    }
+
+    audit::record(
+        &mut *tx,
+        AuditEntry {
+            org_id: auth.org_id,
+            actor_user_id: Some(auth.user_id),
+            entity: "payroll",
+            entity_id: None,
+            action: "generate",
+            details: Some(serde_json::json!({ "month": month, "created": created })),
+        },
+    )
+    .await?;
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to commit payroll generation");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(GeneratePayrollResponse {
+        created,
+        skipped: (active as u64).saturating_sub(created),
     }))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use actix_web::App;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+    use serde_json::Value;
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn another_organizations_payroll_is_hidden() {
+        let pool = test_support::pool().await;
+        let (org_a, org_b) = (test_support::new_org(), test_support::new_org());
+        let admin_a = test_support::user(&pool, org_a, None, Role::Admin).await;
+        let admin_b = test_support::user(&pool, org_b, None, Role::Admin).await;
+        let employee_b = test_support::employee(&pool, org_b).await;
+        let payroll_b =
+            test_support::payroll(&pool, org_b, employee_b, "2026-01-01", "draft").await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/payroll", web::get().to(list_payrolls))
+                .route("/payroll/{id}", web::get().to(get_payroll)),
+        )
+        .await;
+        let get = |uri: String, user: &test_support::TestUser| {
+            TestRequest::get()
+                .uri(&uri)
+                .insert_header(user.bearer())
+                .to_request()
+        };
+
+        let by_id = format!("/payroll/{}", payroll_b);
+        assert_eq!(
+            call_service(&app, get(by_id.clone(), &admin_a))
+                .await
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            call_service(&app, get(by_id, &admin_b)).await.status(),
+            StatusCode::OK
+        );
+
+        let list: Value =
+            read_body_json(call_service(&app, get("/payroll".to_string(), &admin_a)).await).await;
+        assert_eq!(list["total"], 0);
+        assert_eq!(list["data"], Value::Array(vec![]));
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn editing_a_paid_payroll_is_409() {
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let admin = test_support::user(&pool, org, None, Role::Admin).await;
+        let employee = test_support::employee(&pool, org).await;
+        let payroll = test_support::payroll(&pool, org, employee, "2026-01-01", "paid").await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/payroll/{id}", web::put().to(update_payroll)),
+        )
+        .await;
+        let res = call_service(
+            &app,
+            TestRequest::put()
+                .uri(&format!("/payroll/{}", payroll))
+                .insert_header(admin.bearer())
+                .set_json(serde_json::json!({"bonus": 500.0}))
+                .to_request(),
+        )
+        .await;
+
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+        let bonus: f64 = sqlx::query_scalar("SELECT bonus FROM payroll WHERE id = ?")
+            .bind(payroll)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(bonus, 0.0);
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn paying_commits_the_status_with_its_audit_entry() {
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let admin = test_support::user(&pool, org, None, Role::Admin).await;
+        let employee = test_support::employee(&pool, org).await;
+        let payroll = test_support::payroll(&pool, org, employee, "2026-01-01", "approved").await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/payroll/{id}/pay", web::put().to(pay_payroll)),
+        )
+        .await;
+        let pay = || {
+            TestRequest::put()
+                .uri(&format!("/payroll/{}/pay", payroll))
+                .insert_header(admin.bearer())
+                .to_request()
+        };
+
+        assert_eq!(call_service(&app, pay()).await.status(), StatusCode::OK);
+        // paid is final
+        assert_eq!(
+            call_service(&app, pay()).await.status(),
+            StatusCode::CONFLICT
+        );
+
+        let audited: Vec<String> = sqlx::query_scalar(
+            "SELECT action FROM audit_log WHERE org_id = ? AND entity = 'payroll' AND entity_id = ?",
+        )
+        .bind(org)
+        .bind(payroll)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(audited, ["pay"]);
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn employees_see_only_their_own_payslips() {
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let (alice, bob) = (
+            test_support::employee(&pool, org).await,
+            test_support::employee(&pool, org).await,
+        );
+        let alice_paid = test_support::payroll(&pool, org, alice, "2026-01-01", "paid").await;
+        let alice_approved =
+            test_support::payroll(&pool, org, alice, "2026-02-01", "approved").await;
+        // drafts aren't payslips yet
+        test_support::payroll(&pool, org, alice, "2026-03-01", "draft").await;
+        test_support::payroll(&pool, org, bob, "2026-01-01", "paid").await;
+        let alice_user = test_support::user(&pool, org, Some(alice), Role::Employee).await;
+        let no_profile = test_support::user(&pool, org, None, Role::Employee).await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/payroll/mine", web::get().to(my_payslips)),
+        )
+        .await;
+        let mine = |user: &test_support::TestUser| {
+            TestRequest::get()
+                .uri("/payroll/mine")
+                .insert_header(user.bearer())
+                .to_request()
+        };
+
+        let res = call_service(&app, mine(&alice_user)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let body: Value = read_body_json(res).await;
+        assert_eq!(body["total"], 2);
+        let ids: Vec<u64> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["id"].as_u64().unwrap())
+            .collect();
+        assert_eq!(ids, [alice_approved, alice_paid]);
+
+        assert_eq!(
+            call_service(&app, mine(&no_profile)).await.status(),
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn equal_months_page_in_a_stable_order() {
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let admin = test_support::user(&pool, org, None, Role::Admin).await;
+        // same month and amounts, so only the id tells them apart
+        let mut ids = Vec::new();
+        for _ in 0..4 {
+            let employee = test_support::employee(&pool, org).await;
+            ids.push(test_support::payroll(&pool, org, employee, "2026-01-01", "draft").await);
+        }
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/payroll", web::get().to(list_payrolls)),
+        )
+        .await;
+        let page = async |page: u32| -> Vec<u64> {
+            let res = call_service(
+                &app,
+                TestRequest::get()
+                    .uri(&format!("/payroll?per_page=2&page={}", page))
+                    .insert_header(admin.bearer())
+                    .to_request(),
+            )
+            .await;
+            let body: Value = read_body_json(res).await;
+            body["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p["id"].as_u64().unwrap())
+                .collect()
+        };
+
+        let (first, second) = (page(1).await, page(2).await);
+        assert_eq!([first.clone(), second].concat(), ids);
+        assert_eq!(page(1).await, first);
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn api_user_can_list_payroll_but_employee_cannot() {
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let employee = test_support::employee(&pool, org).await;
+        test_support::payroll(&pool, org, employee, "2026-01-01", "paid").await;
+        let employee_user = test_support::user(&pool, org, Some(employee), Role::Employee).await;
+        let api_user = test_support::user(&pool, org, None, Role::ApiUser).await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/payroll", web::get().to(list_payrolls)),
+        )
+        .await;
+        let list = |user: &test_support::TestUser| {
+            TestRequest::get()
+                .uri("/payroll")
+                .insert_header(user.bearer())
+                .to_request()
+        };
+
+        let res = call_service(&app, list(&employee_user)).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        let body: Value = read_body_json(res).await;
+        assert_eq!(body["code"], "role_not_allowed");
+
+        let res = call_service(&app, list(&api_user)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let body: Value = read_body_json(res).await;
+        assert_eq!(body["total"], 1);
+    }
+}
@@ -0,0 +1,352 @@
+use crate::auth::auth::AuthUser;
+use crate::utils::date_param;
+use actix_web::{HttpResponse, Responder, web};
+use chrono::{Duration, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySqlPool};
+use utoipa::{IntoParams, ToSchema};
+
+/// Longest range one request may cover; leaves are expanded per day
+const MAX_RANGE_DAYS: i64 = 366;
+
+#[derive(Deserialize, IntoParams, ToSchema)]
+pub struct TimelineQuery {
+    /// `YYYY-MM-DD`, defaults to 30 days before `to`
+    #[schema(example = "2026-01-01", format = "date")]
+    pub from: Option<String>,
+    /// `YYYY-MM-DD`, defaults to today
+    #[schema(example = "2026-01-31", format = "date")]
+    pub to: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEventKind {
+    Leave,
+    CheckIn,
+    CheckOut,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TimelineEvent {
+    #[schema(example = "2026-01-05", value_type = String, format = "date")]
+    pub date: NaiveDate,
+
+    /// Not set for leave days
+    #[schema(example = "09:02:00", value_type = Option<String>)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<NaiveTime>,
+
+    pub kind: TimelineEventKind,
+
+    #[schema(example = 55)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leave_id: Option<u64>,
+
+    #[schema(example = "annual")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub leave_type: Option<String>,
+
+    /// Leave status (`pending` or `approved`)
+    #[schema(example = "approved")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TimelineResponse {
+    #[schema(example = 1001)]
+    pub employee_id: u64,
+    #[schema(example = "2026-01-01", value_type = String, format = "date")]
+    pub from: NaiveDate,
+    #[schema(example = "2026-01-31", value_type = String, format = "date")]
+    pub to: NaiveDate,
+    pub events: Vec<TimelineEvent>,
+}
+
+#[derive(FromRow)]
+struct LeaveRow {
+    id: u64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    leave_type: String,
+    status: String,
+}
+
+#[derive(FromRow)]
+struct AttendanceRow {
+    date: NaiveDate,
+    check_in: Option<NaiveTime>,
+    check_out: Option<NaiveTime>,
+}
+
+/// Leave days and attendance of one employee, merged into one chronological list
+#[utoipa::path(
+    get,
+    path = "/api/v1/employees/{employee_id}/timeline",
+    params(
+        ("employee_id", Path, description = "Employee ID"),
+        TimelineQuery
+    ),
+    responses(
+        (status = 200, description = "Events sorted by date; on each day leave comes first, then check-in/out by time", body = TimelineResponse),
+        (status = 400, description = "Malformed date or invalid range", body = Object, example = json!({
+            "error": "invalid_date",
+            "field": "from",
+            "value": "2026-1-5"
+        })),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Not HR/Admin, the employee or their manager"),
+        (status = 404, description = "Employee not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Employee"
+)]
+pub async fn employee_timeline(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+    query: web::Query<TimelineQuery>,
+) -> actix_web::Result<impl Responder> {
+    let employee_id = path.into_inner();
+
+    let to = date_param::parse("to", query.to.as_deref())?
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+    let from = date_param::parse("from", query.from.as_deref())?
+        .unwrap_or(to - Duration::days(30));
+
+    if from > to {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "from must not be after to"
+        })));
+    }
+    if (to - from).num_days() >= MAX_RANGE_DAYS {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": format!("Range must not exceed {} days", MAX_RANGE_DAYS)
+        })));
+    }
+
+    // 1️⃣ employee exists in this organization (404 before 403)
+    let manager_id = sqlx::query_scalar::<_, Option<u64>>(
+        "SELECT manager_id FROM employees WHERE id = ? AND org_id = ?",
+    )
+    .bind(employee_id)
+    .bind(auth.org_id)
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to fetch employee");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let Some(manager_id) = manager_id else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "message": "Employee not found"
+        })));
+    };
+
+    // 2️⃣ HR/Admin, the employee themselves or their manager
+    let is_self = auth.employee_id == Some(employee_id);
+    let is_manager = manager_id.is_some() && auth.employee_id == manager_id;
+    if !auth.has_permission("employee.timeline") && !is_self && !is_manager {
+        return Err(actix_web::error::ErrorForbidden("Not allowed to view this timeline"));
+    }
+
+    // 3️⃣ both sources
+    let leaves = sqlx::query_as::<_, LeaveRow>(
+        r#"
+        SELECT id, start_date, end_date, leave_type, status
+        FROM leave_requests
+        WHERE employee_id = ?
+        AND org_id = ?
+        AND status IN ('pending', 'approved')
+        AND end_date >= ?
+        AND start_date <= ?
+        "#,
+    )
+    .bind(employee_id)
+    .bind(auth.org_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to fetch leaves for timeline");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let attendance = sqlx::query_as::<_, AttendanceRow>(
+        r#"
+        SELECT date, check_in, check_out
+        FROM attendance
+        WHERE employee_id = ?
+        AND date BETWEEN ? AND ?
+        "#,
+    )
+    .bind(employee_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, employee_id, "Failed to fetch attendance for timeline");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(TimelineResponse {
+        employee_id,
+        from,
+        to,
+        events: merge_events(leaves, attendance, from, to),
+    }))
+}
+
+/// One `leave` event per leave day inside the range plus one event per check-in/out,
+/// sorted by date, then time (leave days have none and sort first)
+fn merge_events(
+    leaves: Vec<LeaveRow>,
+    attendance: Vec<AttendanceRow>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<TimelineEvent> {
+    let mut events = Vec::new();
+
+    for leave in leaves {
+        let mut day = leave.start_date.max(from);
+        let last = leave.end_date.min(to);
+        while day <= last {
+            events.push(TimelineEvent {
+                date: day,
+                time: None,
+                kind: TimelineEventKind::Leave,
+                leave_id: Some(leave.id),
+                leave_type: Some(leave.leave_type.clone()),
+                status: Some(leave.status.clone()),
+            });
+            day += Duration::days(1);
+        }
+    }
+
+    for row in attendance {
+        let punches = [
+            (row.check_in, TimelineEventKind::CheckIn),
+            (row.check_out, TimelineEventKind::CheckOut),
+        ];
+        for (time, kind) in punches {
+            if let Some(time) = time {
+                events.push(TimelineEvent {
+                    date: row.date,
+                    time: Some(time),
+                    kind,
+                    leave_id: None,
+                    leave_type: None,
+                    status: None,
+                });
+            }
+        }
+    }
+
+    // stable: a check-in and check-out at the same minute keep their order
+    events.sort_by_key(|e| (e.date, e.time));
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TimelineEventKind::{CheckIn, CheckOut, Leave};
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn time(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    fn leave(id: u64, start: &str, end: &str) -> LeaveRow {
+        LeaveRow {
+            id,
+            start_date: date(start),
+            end_date: date(end),
+            leave_type: "annual".to_string(),
+            status: "approved".to_string(),
+        }
+    }
+
+    fn attendance(day: &str, check_in: Option<&str>, check_out: Option<&str>) -> AttendanceRow {
+        AttendanceRow {
+            date: date(day),
+            check_in: check_in.map(time),
+            check_out: check_out.map(time),
+        }
+    }
+
+    /// (date, kind, time or leave id) of each event, in order
+    fn summary(events: &[TimelineEvent]) -> Vec<(NaiveDate, &TimelineEventKind, String)> {
+        events
+            .iter()
+            .map(|e| {
+                let detail = match (e.time, e.leave_id) {
+                    (Some(time), _) => time.format("%H:%M").to_string(),
+                    (None, Some(id)) => id.to_string(),
+                    (None, None) => String::new(),
+                };
+                (e.date, &e.kind, detail)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn leave_and_attendance_are_interleaved_by_date() {
+        let events = merge_events(
+            vec![leave(7, "2026-01-06", "2026-01-07")],
+            // fetched in no particular order
+            vec![
+                attendance("2026-01-08", Some("09:15"), None),
+                attendance("2026-01-05", Some("09:00"), Some("17:30")),
+            ],
+            date("2026-01-01"),
+            date("2026-01-31"),
+        );
+
+        assert_eq!(
+            summary(&events),
+            [
+                (date("2026-01-05"), &CheckIn, "09:00".to_string()),
+                (date("2026-01-05"), &CheckOut, "17:30".to_string()),
+                (date("2026-01-06"), &Leave, "7".to_string()),
+                (date("2026-01-07"), &Leave, "7".to_string()),
+                (date("2026-01-08"), &CheckIn, "09:15".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn leave_day_comes_before_punches_on_the_same_day() {
+        let events = merge_events(
+            vec![leave(3, "2026-01-05", "2026-01-05")],
+            vec![attendance("2026-01-05", Some("13:00"), Some("17:00"))],
+            date("2026-01-01"),
+            date("2026-01-31"),
+        );
+
+        let kinds: Vec<&TimelineEventKind> = events.iter().map(|e| &e.kind).collect();
+        assert_eq!(kinds, [&Leave, &CheckIn, &CheckOut]);
+    }
+
+    #[test]
+    fn leave_days_outside_the_range_are_left_out() {
+        let events = merge_events(
+            vec![leave(9, "2025-12-30", "2026-01-02")],
+            vec![],
+            date("2026-01-01"),
+            date("2026-01-31"),
+        );
+
+        let days: Vec<NaiveDate> = events.iter().map(|e| e.date).collect();
+        assert_eq!(days, [date("2026-01-01"), date("2026-01-02")]);
+    }
+}
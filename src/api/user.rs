@@ -0,0 +1,557 @@
+use crate::auth::{auth::AuthUser, password::{generate_temporary_password, hash_password}};
+use crate::utils::audit::{self, AuditEntry};
+use crate::utils::{username_cache, username_filter};
+use actix_web::{HttpResponse, Responder, web};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, MySql, MySqlPool, Transaction};
+use utoipa::ToSchema;
+
+#[derive(Serialize, FromRow, ToSchema)]
+pub struct MeEmployee {
+    #[schema(example = "John")]
+    pub first_name: String,
+    #[schema(example = "Doe", nullable = true)]
+    pub last_name: Option<String>,
+    #[schema(example = "john.doe@company.com")]
+    pub email: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MeResponse {
+    #[schema(example = 7)]
+    pub user_id: u64,
+    #[schema(example = "jdoe")]
+    pub username: String,
+    #[schema(example = "employee")]
+    pub role: &'static str,
+    #[schema(example = 1001, nullable = true)]
+    pub employee_id: Option<u64>,
+    /// `null` when the user has no employee record
+    pub employee: Option<MeEmployee>,
+}
+
+/// The authenticated user's identity and linked employee profile
+#[utoipa::path(
+    get,
+    path = "/api/v1/me",
+    responses(
+        (status = 200, description = "Current user", body = MeResponse),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "User"
+)]
+pub async fn me(auth: AuthUser, pool: web::Data<MySqlPool>) -> actix_web::Result<impl Responder> {
+    let employee = match auth.employee_id {
+        Some(employee_id) => sqlx::query_as::<_, MeEmployee>(
+            "SELECT first_name, last_name, email FROM employees WHERE id = ? AND org_id = ?",
+        )
+        .bind(employee_id)
+        .bind(auth.org_id)
+        .fetch_optional(pool.get_ref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, employee_id, "Failed to fetch current user's employee");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?,
+        None => None,
+    };
+
+    Ok(HttpResponse::Ok().json(MeResponse {
+        user_id: auth.user_id,
+        username: auth.username,
+        role: auth.role.as_str(),
+        employee_id: auth.employee_id,
+        employee,
+    }))
+}
+
+/// Issue a temporary password (Admin)
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{user_id}/reset-password",
+    params(
+        ("user_id" = u64, Path, description = "ID of the user to reset")
+    ),
+    responses(
+        (status = 200, description = "Temporary password issued; it is shown only once and must be changed at next login", body = Object, example = json!({
+            "message": "Temporary password issued",
+            "temporary_password": "h7Kp2mQx9sTa"
+        })),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "User not found", body = Object, example = json!({
+            "message": "User not found"
+        })),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "User"
+)]
+pub async fn reset_user_password(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("user.reset_password")?;
+
+    let user_id = path.into_inner();
+    let temporary_password = generate_temporary_password();
+    let hashed = hash_password(&temporary_password).map_err(|e| {
+        tracing::error!(error = %e, user_id, "Failed to hash temporary password");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let result = sqlx::query(
+        r#"
+        UPDATE users
+        SET password = ?, must_change_password = 1
+        WHERE id = ? AND org_id = ?
+        "#,
+    )
+    .bind(hashed)
+    .bind(user_id)
+    .bind(auth.org_id)
+    .execute(pool.get_ref())
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, user_id, "Failed to reset user password");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    if result.rows_affected() == 0 {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "message": "User not found"
+        })));
+    }
+
+    // end every existing session of that user
+    sqlx::query("UPDATE refresh_tokens SET revoked = 1 WHERE user_id = ?")
+        .bind(user_id)
+        .execute(pool.get_ref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, user_id, "Failed to revoke sessions after password reset");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+    audit::record(
+        pool.get_ref(),
+        AuditEntry {
+            org_id: auth.org_id,
+            actor_user_id: Some(auth.user_id),
+            entity: "user",
+            entity_id: Some(user_id),
+            action: "reset_password",
+            details: None,
+        },
+    )
+    .await?;
+
+    tracing::info!(user_id, admin_id = auth.user_id, "Temporary password issued");
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Temporary password issued",
+        "temporary_password": temporary_password
+    })))
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetUserActive {
+    #[schema(example = false)]
+    pub is_active: bool,
+}
+
+/// Disable or re-enable a user account (Admin).
+/// Disabling takes effect immediately: sessions are revoked and existing access tokens
+/// are rejected with 403 `account_disabled`.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/users/{user_id}/active",
+    params(
+        ("user_id" = u64, Path, description = "ID of the user to disable or enable")
+    ),
+    request_body = SetUserActive,
+    responses(
+        (status = 200, description = "Account status updated", body = Object, example = json!({
+            "message": "Account disabled"
+        })),
+        (status = 400, description = "Admins can't disable their own account"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "User not found", body = Object, example = json!({
+            "message": "User not found"
+        }))
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "User"
+)]
+pub async fn set_user_active(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+    payload: web::Json<SetUserActive>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("user.disable")?;
+
+    let user_id = path.into_inner();
+    if user_id == auth.user_id && !payload.is_active {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "You can't disable your own account"
+        })));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, user_id, "Failed to start account status transaction");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM users WHERE id = ? AND org_id = ?)",
+    )
+    .bind(user_id)
+    .bind(auth.org_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, user_id, "Failed to look up user");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    if !exists {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "message": "User not found"
+        })));
+    }
+
+    // re-enabling restarts the inactivity clock, or the dormant-account sweep would
+    // disable the user again right away
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET is_active = ?,
+            last_login_at = IF(?, NOW(), last_login_at)
+        WHERE id = ?
+        "#,
+    )
+    .bind(payload.is_active)
+    .bind(payload.is_active)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, user_id, "Failed to update account status");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    if !payload.is_active {
+        sqlx::query("UPDATE refresh_tokens SET revoked = 1 WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, user_id, "Failed to revoke sessions of disabled user");
+                actix_web::error::ErrorInternalServerError("Internal Server Error")
+            })?;
+    }
+
+    audit::record(
+        &mut *tx,
+        AuditEntry {
+            org_id: auth.org_id,
+            actor_user_id: Some(auth.user_id),
+            entity: "user",
+            entity_id: Some(user_id),
+            action: if payload.is_active { "enable" } else { "disable" },
+            details: None,
+        },
+    )
+    .await?;
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, user_id, "Failed to commit account status change");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": if payload.is_active { "Account enabled" } else { "Account disabled" }
+    })))
+}
+
+/// Disable the accounts linked to a (soft-)deleted employee and end their sessions.
+pub async fn disable_employee_users(
+    tx: &mut Transaction<'_, MySql>,
+    org_id: u64,
+    employee_id: u64,
+) -> Result<(), sqlx::Error> {
+    let user_ids = sqlx::query_scalar::<_, u64>(
+        "SELECT id FROM users WHERE employee_id = ? AND org_id = ? AND is_active = TRUE",
+    )
+    .bind(employee_id)
+    .bind(org_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    for &user_id in &user_ids {
+        sqlx::query("UPDATE users SET is_active = FALSE WHERE id = ?")
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?;
+
+        sqlx::query("UPDATE refresh_tokens SET revoked = 1 WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Permanently delete a user account (admin).
+/// The username becomes available again; a linked employee record is kept.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/users/{user_id}",
+    params(
+        ("user_id", Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User deleted", body = Object, example = json!({
+            "message": "User deleted"
+        })),
+        (status = 400, description = "Admins can't delete their own account"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "User not found", body = Object, example = json!({
+            "message": "User not found"
+        }))
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "User"
+)]
+pub async fn delete_user(
+    auth: AuthUser,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<u64>,
+) -> actix_web::Result<impl Responder> {
+    auth.require_permission("user.delete")?;
+
+    let user_id = path.into_inner();
+    if user_id == auth.user_id {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "message": "You can't delete your own account"
+        })));
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| {
+        tracing::error!(error = %e, user_id, "Failed to start user deletion");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let username = sqlx::query_scalar::<_, String>(
+        "SELECT username FROM users WHERE id = ? AND org_id = ? FOR UPDATE",
+    )
+    .bind(user_id)
+    .bind(auth.org_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, user_id, "Failed to look up user");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    let Some(username) = username else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "message": "User not found"
+        })));
+    };
+
+    for sql in [
+        "DELETE FROM refresh_tokens WHERE user_id = ?",
+        "DELETE FROM users WHERE id = ?",
+    ] {
+        sqlx::query(sql)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, user_id, "Failed to delete user");
+                actix_web::error::ErrorInternalServerError("Internal Server Error")
+            })?;
+    }
+
+    audit::record(
+        &mut *tx,
+        AuditEntry {
+            org_id: auth.org_id,
+            actor_user_id: Some(auth.user_id),
+            entity: "user",
+            entity_id: Some(user_id),
+            action: "delete",
+            details: Some(serde_json::json!({ "username": username })),
+        },
+    )
+    .await?;
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!(error = %e, user_id, "Failed to commit user deletion");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    // registration checks the filter and cache before the DB; keep them in sync
+    username_filter::remove(&username);
+    username_cache::forget(&username).await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "User deleted"
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::handlers::refresh_token;
+    use crate::model::role::Role;
+    use crate::test_support;
+    use actix_web::App;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn disabled_user_is_refused_right_away() {
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let admin = test_support::user(&pool, org, None, Role::Admin).await;
+        let employee = test_support::employee(&pool, org).await;
+        let disabled = test_support::user(&pool, org, Some(employee), Role::Employee).await;
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/auth/refresh", web::post().to(refresh_token))
+                .route("/me", web::get().to(me))
+                .route(
+                    "/admin/users/{user_id}/active",
+                    web::put().to(set_user_active),
+                ),
+        )
+        .await;
+
+        let res = call_service(
+            &app,
+            TestRequest::put()
+                .uri(&format!("/admin/users/{}/active", disabled.user_id))
+                .insert_header(admin.bearer())
+                .set_json(serde_json::json!({"is_active": false}))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let refresh = call_service(
+            &app,
+            TestRequest::post()
+                .uri("/auth/refresh")
+                .insert_header(disabled.refresh_bearer())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(refresh.status(), StatusCode::UNAUTHORIZED);
+
+        let me = call_service(
+            &app,
+            TestRequest::get()
+                .uri("/me")
+                .insert_header(disabled.bearer())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(me.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn refresh_checks_the_account_not_just_the_token() {
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let user = test_support::user(&pool, org, None, Role::Hr).await;
+
+        // disabled without revoking its sessions, e.g. by a direct database update
+        sqlx::query("UPDATE users SET is_active = FALSE WHERE id = ?")
+            .bind(user.user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/auth/refresh", web::post().to(refresh_token)),
+        )
+        .await;
+        let res = call_service(
+            &app,
+            TestRequest::post()
+                .uri("/auth/refresh")
+                .insert_header(user.refresh_bearer())
+                .to_request(),
+        )
+        .await;
+
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        let body: serde_json::Value = read_body_json(res).await;
+        assert_eq!(body["code"], "account_disabled");
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn deleted_username_is_available_again() {
+        use crate::auth::handlers::is_username_available;
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let admin = test_support::user(&pool, org, None, Role::Admin).await;
+        let user = test_support::user(&pool, org, None, Role::Employee).await;
+        let username: String = sqlx::query_scalar("SELECT username FROM users WHERE id = ?")
+            .bind(user.user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        // the DB hit puts the name into the filter and cache
+        assert!(!is_username_available(&username, &pool).await);
+        assert!(username_filter::might_exist(&username));
+        assert!(username_cache::is_taken(&username).await);
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/users/{id}", web::delete().to(delete_user)),
+        )
+        .await;
+        let res = call_service(
+            &app,
+            TestRequest::delete()
+                .uri(&format!("/users/{}", user.user_id))
+                .insert_header(admin.bearer())
+                .to_request(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        assert!(!username_cache::is_taken(&username).await);
+        assert!(is_username_available(&username, &pool).await);
+    }
+}
@@ -0,0 +1,14 @@
+use sqlx::MySqlPool;
+
+/// Whether the user exists and is active.
+/// Read on every request, not cached: a disable done through any instance applies to
+/// the very next request everywhere.
+pub async fn is_active(pool: &MySqlPool, user_id: u64) -> Result<bool, sqlx::Error> {
+    let active = sqlx::query_scalar::<_, bool>("SELECT is_active FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .unwrap_or(false);
+
+    Ok(active)
+}
@@ -1,9 +1,26 @@
-use crate::{model::role::Role, models::Claims};
-use actix_web::{FromRequest, HttpRequest, dev::Payload, error::ErrorUnauthorized, web::Data};
-use futures::future::{Ready, ready};
-use jsonwebtoken::decode;
-use jsonwebtoken::{DecodingKey, Validation};
 use crate::config::Config;
+use crate::utils::api_error::ApiError;
+use crate::{
+    auth::{
+        active_user,
+        jwt::{TokenError, verify_token},
+        permissions, revoked_tokens,
+    },
+    model::role::Role,
+    models::{Claims, TokenType},
+};
+use actix_web::{
+    FromRequest, HttpMessage, HttpRequest, HttpResponse,
+    dev::Payload,
+    error::InternalError,
+    http::{StatusCode, header::WWW_AUTHENTICATE},
+    web::Data,
+};
+use futures::future::{LocalBoxFuture, ready};
+use serde_json::json;
+use sqlx::MySqlPool;
+
+#[derive(Clone)]
 pub struct AuthUser {
     pub user_id: u64,
     pub username: String,
@@ -11,74 +28,291 @@ pub struct AuthUser {
 
     /// Present only if this user is linked to an employee record
     pub employee_id: Option<u64>,
+
+    /// Organization (tenant); every data query must be scoped to it
+    pub org_id: u64,
+
+    /// Id and expiry of the access token the request came with, for revocation
+    pub jti: String,
+    pub exp: usize,
+}
+
+/// Paths a user holding a temporary password may still reach
+const PASSWORD_CHANGE_PATHS: &[&str] = &["/change-password", "/auth/revoke"];
+
+/// `{"error": .., "code": ..}` body of a refused request; 401s carry a Bearer challenge
+fn rejection(status: StatusCode, message: &'static str, code: &'static str) -> actix_web::Error {
+    let mut response = HttpResponse::build(status);
+    if status == StatusCode::UNAUTHORIZED {
+        response.insert_header((WWW_AUTHENTICATE, "Bearer"));
+    }
+    let response = response.json(json!({"error": message, "code": code}));
+    InternalError::from_response(message, response).into()
+}
+
+fn unauthorized(message: &'static str, code: &'static str) -> actix_web::Error {
+    rejection(StatusCode::UNAUTHORIZED, message, code)
+}
+
+/// Claims of the request's bearer token; refresh tokens are refused
+fn verify_bearer(req: &HttpRequest, secret: &str) -> Result<Claims, actix_web::Error> {
+    let header = req
+        .headers()
+        .get("Authorization")
+        .ok_or_else(|| unauthorized("Missing Authorization header", "missing_token"))?;
+
+    let token = header
+        .to_str()
+        .ok()
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .ok_or_else(|| {
+            unauthorized(
+                "Authorization header must start with Bearer",
+                "malformed_authorization",
+            )
+        })?;
+
+    verify_token(token, secret, TokenType::Access)
+        .map_err(|e: TokenError| unauthorized(e.message(), e.code()))
+}
+
+/// Temporary password issued by HR: only changing it (or giving up the token) is allowed
+fn ensure_password_changed(claims: &Claims, path: &str) -> Result<(), actix_web::Error> {
+    if claims.must_change_password && !PASSWORD_CHANGE_PATHS.iter().any(|p| path.ends_with(p)) {
+        return Err(rejection(
+            StatusCode::FORBIDDEN,
+            "password_change_required",
+            "password_change_required",
+        ));
+    }
+    Ok(())
+}
+
+/// Authenticate a request by its bearer access token. `auth_middleware` and the extractor
+/// both go through here, so every route applies the same checks.
+pub async fn authenticate(req: &HttpRequest) -> Result<AuthUser, actix_web::Error> {
+    let config = req
+        .app_data::<Data<Config>>()
+        .ok_or_else(|| actix_web::error::ErrorInternalServerError("App config missing"))?;
+    let pool = req
+        .app_data::<Data<MySqlPool>>()
+        .ok_or_else(|| actix_web::error::ErrorInternalServerError("Database pool missing"))?;
+
+    let claims = verify_bearer(req, &config.jwt_secret)?;
+
+    // logged-out or compromised tokens are blacklisted until they expire
+    match revoked_tokens::is_revoked(pool.get_ref(), &claims.jti).await {
+        Ok(false) => {}
+        Ok(true) => return Err(unauthorized("Token revoked", "token_revoked")),
+        Err(e) => {
+            tracing::error!(error = %e, user_id = claims.user_id, "Failed to check token revocation");
+            return Err(actix_web::error::ErrorInternalServerError(
+                "Internal Server Error",
+            ));
+        }
+    }
+
+    ensure_password_changed(&claims, req.path())?;
+
+    let role =
+        Role::from_id(claims.role).ok_or_else(|| unauthorized("Invalid role", "invalid_role"))?;
+
+    // tokens stay valid until expiry, so a disabled account is checked on every request
+    match active_user::is_active(pool.get_ref(), claims.user_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(rejection(
+                StatusCode::FORBIDDEN,
+                "account_disabled",
+                "account_disabled",
+            ));
+        }
+        Err(e) => {
+            tracing::error!(error = %e, user_id = claims.user_id, "Failed to check account status");
+            return Err(actix_web::error::ErrorInternalServerError(
+                "Internal Server Error",
+            ));
+        }
+    }
+
+    Ok(AuthUser {
+        user_id: claims.user_id,
+        username: claims.sub,
+        role,
+        employee_id: claims.employee_id,
+        org_id: claims.org_id,
+        jti: claims.jti,
+        exp: claims.exp,
+    })
 }
 
 impl FromRequest for AuthUser {
     type Error = actix_web::Error;
-    type Future = Ready<Result<Self, Self::Error>>;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
 
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        let token = match req
-            .headers()
-            .get("Authorization")
-            .and_then(|h| h.to_str().ok())
-            .and_then(|h| h.strip_prefix("Bearer "))
-        {
-            Some(t) => t,
-            None => return ready(Err(ErrorUnauthorized("Missing token"))),
-        };
-
-        let config = match req.app_data::<Data<Config>>() {
-            Some(c) => c,
-            None => {
-                return ready(Err(
-                    actix_web::error::ErrorInternalServerError("Config missing"),
-                ))
-            }
-        };
-
-        let data = match decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
-            &Validation::default(),
-        ) {
-            Ok(d) => d,
-            Err(_) => return ready(Err(ErrorUnauthorized("Invalid token"))),
-        };
-
-        let role = match Role::from_id(data.claims.role) {
-            Some(r) => r,
-            None => return ready(Err(ErrorUnauthorized("Invalid role"))),
-        };
-
-        ready(Ok(AuthUser {
-            user_id: data.claims.user_id,
-            username: data.claims.sub,
-            role,
-            employee_id: data.claims.employee_id,
-        }))
+        // protected routes were already authenticated by `auth_middleware`
+        if let Some(user) = req.extensions().get::<AuthUser>() {
+            return Box::pin(ready(Ok(user.clone())));
+        }
+
+        let req = req.clone();
+        Box::pin(async move { authenticate(&req).await })
     }
 }
 
 impl AuthUser {
-    pub fn require_admin(&self) -> actix_web::Result<()> {
-        if self.role == Role::Admin {
+    /// Whether the user's role is granted `permission` (see `auth::permissions`)
+    pub fn has_permission(&self, permission: &str) -> bool {
+        permissions::is_allowed(self.role, permission)
+    }
+
+    pub fn require_permission(&self, permission: &str) -> actix_web::Result<()> {
+        if self.has_permission(permission) {
             Ok(())
         } else {
-            Err(actix_web::error::ErrorForbidden("Admin only"))
+            Err(ApiError::forbidden(
+                "missing_permission",
+                format!("Missing permission: {}", permission),
+            )
+            .into())
         }
     }
 
-    pub fn require_hr_or_admin(&self) -> actix_web::Result<()> {
-        if matches!(self.role, Role::Admin | Role::Hr) {
+    pub fn has_role(&self, role: Role) -> bool {
+        self.role == role
+    }
+
+    /// For endpoints open to specific roles regardless of the permission map,
+    /// e.g. machine-to-machine `ApiUser` integrations
+    pub fn require_roles(&self, roles: &[Role]) -> actix_web::Result<()> {
+        if roles.iter().any(|role| self.has_role(*role)) {
             Ok(())
         } else {
-            Err(actix_web::error::ErrorForbidden("HR/Admin only"))
+            let allowed: Vec<&str> = roles.iter().map(Role::as_str).collect();
+            Err(ApiError::forbidden(
+                "role_not_allowed",
+                format!("Requires one of the roles: {}", allowed.join(", ")),
+            )
+            .into())
         }
     }
+}
+
+#[cfg(test)]
+impl AuthUser {
+    /// A signed-in user for handler and helper tests
+    pub fn for_test(role: Role, employee_id: Option<u64>, org_id: u64) -> Self {
+        AuthUser {
+            user_id: employee_id.unwrap_or(1),
+            username: "test-user".to_string(),
+            role,
+            employee_id,
+            org_id,
+            jti: "test-jti".to_string(),
+            exp: usize::MAX,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::jwt::{TokenSubject, generate_access_token, generate_refresh_token};
+    use actix_web::test::TestRequest;
+
+    const SECRET: &str = "test-secret";
+
+    fn subject(must_change_password: bool) -> TokenSubject {
+        TokenSubject {
+            user_id: 7,
+            username: "alice".to_string(),
+            role: Role::Employee as u8,
+            employee_id: Some(1001),
+            org_id: 1,
+            must_change_password,
+        }
+    }
+
+    fn bearer(token: &str) -> HttpRequest {
+        TestRequest::default()
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_http_request()
+    }
+
+    fn status_of(err: actix_web::Error) -> StatusCode {
+        err.as_response_error().status_code()
+    }
+
+    #[test]
+    fn access_token_is_accepted() {
+        let token = generate_access_token(&subject(false), SECRET, 60);
+        let claims = verify_bearer(&bearer(&token), SECRET).unwrap();
+
+        assert_eq!(claims.user_id, 7);
+        assert_eq!(claims.org_id, 1);
+    }
+
+    #[test]
+    fn refresh_token_is_refused() {
+        let (token, _) = generate_refresh_token(&subject(false), SECRET, 60);
+        let err = verify_bearer(&bearer(&token), SECRET).unwrap_err();
+
+        assert_eq!(status_of(err), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn missing_or_malformed_header_is_refused() {
+        let token = generate_access_token(&subject(false), SECRET, 60);
+        let missing = TestRequest::default().to_http_request();
+        let basic = TestRequest::default()
+            .insert_header(("Authorization", format!("Basic {}", token)))
+            .to_http_request();
+
+        for req in [missing, basic] {
+            let err = verify_bearer(&req, SECRET).unwrap_err();
+            let response = err.error_response();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert_eq!(response.headers().get(WWW_AUTHENTICATE).unwrap(), "Bearer");
+        }
+    }
+
+    #[test]
+    fn token_signed_with_another_key_is_refused() {
+        let token = generate_access_token(&subject(false), "other-secret", 60);
+        let err = verify_bearer(&bearer(&token), SECRET).unwrap_err();
+
+        assert_eq!(status_of(err), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn temporary_password_only_reaches_change_password() {
+        let token = generate_access_token(&subject(true), SECRET, 60);
+        let claims = verify_bearer(&bearer(&token), SECRET).unwrap();
+
+        assert!(ensure_password_changed(&claims, "/api/v1/change-password").is_ok());
+        assert!(ensure_password_changed(&claims, "/auth/revoke").is_ok());
+        let err = ensure_password_changed(&claims, "/api/v1/employee").unwrap_err();
+        assert_eq!(status_of(err), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn permanent_password_reaches_everything() {
+        let token = generate_access_token(&subject(false), SECRET, 60);
+        let claims = verify_bearer(&bearer(&token), SECRET).unwrap();
+
+        assert!(ensure_password_changed(&claims, "/api/v1/employee").is_ok());
+    }
+
+    #[test]
+    fn only_the_listed_roles_pass_require_roles() {
+        let employee = AuthUser::for_test(Role::Employee, Some(1001), 1);
+        let api_user = AuthUser::for_test(Role::ApiUser, None, 1);
 
-    /// Returns true if the user is an employee
-    pub fn is_employee(&self) -> bool {
-        self.role == Role::Employee
+        let err = employee.require_roles(&[Role::ApiUser]).unwrap_err();
+        assert_eq!(status_of(err), StatusCode::FORBIDDEN);
+        assert!(api_user.require_roles(&[Role::ApiUser]).is_ok());
+        assert!(api_user.has_role(Role::ApiUser));
+        assert!(!api_user.has_role(Role::Admin));
     }
 }
@@ -1,17 +1,34 @@
 use crate::{
     auth::{
-        jwt::{generate_access_token, generate_refresh_token, verify_token},
-        password::{hash_password, verify_password},
+        jwt::{
+            TokenError, TokenSubject, generate_access_token, generate_refresh_token, verify_token,
+        },
+        password::{
+            generate_reset_token, hash_password, hash_reset_token, validate_password_strength,
+            verify_password,
+        },
+        password_history,
+        refresh_guard,
+        revoked_tokens,
     },
     config::Config,
-    models::{LoginReqDto, TokenType, UserReq, UserSql},
+    auth::auth::AuthUser,
+    maintenance,
+    model::role::Role,
+    models::{
+        ChangePasswordReq, ForgotPasswordReq, LoginReqDto, ResetPasswordReq, RevokeTokenReq,
+        TokenType, UserReq, UserSql,
+    },
 };
-use actix_web::{HttpMessage, HttpRequest, HttpResponse, Responder, get, post, web};
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, Responder, get, http::StatusCode, web};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sqlx::MySqlPool;
 use tracing::{debug, error, info, instrument};
 // use scalable_cuckoo_filter::ScalableCuckooFilter;
+use crate::rate_limit::ClientIp;
+use crate::utils::api_error::ApiError;
+use crate::utils::notifier::{Notification, Notifier};
 use crate::utils::username_cache;
 use crate::utils::username_filter;
 // auth end points
@@ -20,10 +37,13 @@ use crate::utils::username_filter;
 async fn insert_user(
     username: &str,
     password: &str,
-    role: &u8,
+    _role: &u8,
     pool: &MySqlPool,
-) -> Result<(), HttpResponse> {
-    let hashed = hash_password(password);
+) -> Result<(), ApiError> {
+    let hashed = hash_password(password).map_err(|e| {
+        error!(error = %e, "Failed to hash password");
+        ApiError::internal()
+    })?;
 
     let result = sqlx::query!(
         r#"INSERT INTO users (username, password) VALUES (?, ?)"#,
@@ -37,21 +57,19 @@ async fn insert_user(
         Ok(_) => {
             // if insert succcess, insert/populate filter using username. and keep cache populated.
             username_filter::insert(username);
-            username_cache::mark_taken(username);
+            username_cache::mark_taken(username).await;
             Ok(())
         }
         Err(e) => {
-            if let sqlx::Error::Database(db_err) = &e {
-                if db_err.code() == Some("23000".into()) {
-                    return Err(HttpResponse::Conflict().json(json!({
-                        "error": "Username already exists"
-                    })));
-                }
+            if let sqlx::Error::Database(db_err) = &e
+                && db_err.code() == Some("23000".into())
+            {
+                return Err(ApiError::conflict("username_taken", "Username already exists")
+                    .with_field("username"));
             }
 
-            Err(HttpResponse::InternalServerError().json(json!({
-                "error": "Failed to register user"
-            })))
+            error!(error = %e, "Failed to register user");
+            Err(ApiError::internal())
         }
     }
 }
@@ -80,54 +98,68 @@ pub async fn is_username_available(username: &str, pool: &MySqlPool) -> bool {
     )
     .bind(&username)
     .fetch_one(pool)
-    .await
-    .unwrap_or(true); // fail-safe
+    .await;
 
-    if exists {
-        // username_filter::insert(&username);
-        // username_cache::mark_taken(&username).await;
-        return false;
+    match exists {
+        // remember it, so the next check stops at the filter/cache
+        Ok(true) => {
+            username_filter::insert(&username);
+            username_cache::mark_taken(&username).await;
+            false
+        }
+        Ok(false) => true,
+        // fail-safe, but don't cache a name we couldn't confirm
+        Err(e) => {
+            error!(error = %e, "Failed to check username availability");
+            false
+        }
     }
-
-    true
 }
 
 // #[post("/register")]
 
 /// User registration handler
-pub async fn register(user: web::Json<UserReq>, pool: web::Data<MySqlPool>) -> impl Responder {
+pub async fn register(
+    user: web::Json<UserReq>,
+    pool: web::Data<MySqlPool>,
+) -> Result<HttpResponse, ApiError> {
     let username = user.username.trim();
     let password = &user.password;
     let role = &user.role_id;
 
     if username.is_empty() || password.is_empty() {
-        return HttpResponse::BadRequest().json(json!({
-            "error": "Username and password must not be empty"
-        }));
+        return Err(ApiError::bad_request(
+            "missing_credentials",
+            "Username and password must not be empty",
+        ));
+    }
+
+    if let Err(message) = validate_password_strength(password) {
+        return Err(ApiError::bad_request("weak_password", message).with_field("password"));
     }
 
     if !is_username_available(&user.username, pool.get_ref()).await {
-        return HttpResponse::Conflict().json(json!({
-            "error": "Username already taken"
-        }));
+        return Err(ApiError::conflict("username_taken", "Username already taken").with_field("username"));
     }
 
     // Safe to insert after DB check
-    match insert_user(username, password, role, pool.get_ref()).await {
-        Ok(_) => HttpResponse::Created().json(json!({
-            "message": "User registered successfully"
-        })),
-        Err(err_resp) => err_resp,
-    }
+    insert_user(username, password, role, pool.get_ref()).await?;
+
+    Ok(HttpResponse::Created().json(json!({
+        "message": "User registered successfully"
+    })))
 }
 
 #[derive(Serialize, Deserialize)]
 struct LoginResponse {
     access_token: String,
     refresh_token: String,
+    /// true when a temporary password must be replaced before using the API
+    must_change_password: bool,
 }
 
 // #[post("/login")]
+// `user` is skipped on purpose: only the username is recorded, never the password
 #[instrument(
     name = "auth_login",
     skip(pool, config, user),
@@ -137,13 +169,16 @@ pub async fn login(
     user: web::Json<LoginReqDto>,
     pool: web::Data<MySqlPool>,
     config: web::Data<Config>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     info!("Login request received");
 
     // 1️⃣ Basic validation
     if user.username.trim().is_empty() || user.password.is_empty() {
         info!("Validation failed: empty username or password");
-        return HttpResponse::BadRequest().body("Username or password required");
+        return Err(ApiError::bad_request(
+            "missing_credentials",
+            "Username or password required",
+        ));
     }
 
     debug!("Fetching user from database");
@@ -151,7 +186,7 @@ pub async fn login(
     // 2️⃣ Fetch user
     let db_user = match sqlx::query_as::<_, UserSql>(
         r#"
-        SELECT id, username, password, role_id, employee_id
+        SELECT id, username, password, role_id, employee_id, org_id, must_change_password, is_active
         FROM users
         WHERE username = ?
         "#,
@@ -166,47 +201,69 @@ pub async fn login(
         }
         Ok(None) => {
             info!("Invalid credentials: user not found");
-            return HttpResponse::Unauthorized().body("Invalid credentials");
+            return Err(ApiError::unauthorized("invalid_credentials", "Invalid credentials"));
         }
         Err(e) => {
             error!(error = %e, "Database error while fetching user");
-            return HttpResponse::InternalServerError().finish();
+            return Err(ApiError::internal());
         }
     };
 
     // 3️⃣ Verify password
     debug!("Verifying password");
 
-    if let Err(e) = verify_password(&user.password, &db_user.password) {
-        info!(error = %e, "Invalid credentials: password mismatch");
-        return HttpResponse::Unauthorized().body("Invalid credentials");
+    match verify_password(&user.password, &db_user.password) {
+        Ok(()) => {}
+        Err(argon2::password_hash::Error::Password) => {
+            info!("Invalid credentials: password mismatch");
+            return Err(ApiError::unauthorized("invalid_credentials", "Invalid credentials"));
+        }
+        Err(e) => {
+            error!(error = %e, user_id = db_user.id, "Stored password hash is unusable");
+            return Err(ApiError::internal());
+        }
     }
 
     debug!("Password verified");
 
+    // checked after the password so the response doesn't reveal which accounts exist
+    if !db_user.is_active {
+        info!(user_id = db_user.id, "Login rejected: account disabled");
+        return Err(ApiError::forbidden("account_disabled", "Account disabled"));
+    }
+
+    // maintenance lets login through so an operator can get in and switch it off
+    if maintenance::is_enabled()
+        && !matches!(Role::from_id(db_user.role_id), Some(Role::System | Role::Admin))
+    {
+        info!(user_id = db_user.id, "Login rejected: maintenance mode");
+        return Err(ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "maintenance",
+            "Only system and admin accounts can log in during maintenance",
+        ));
+    }
+
+    let subject = TokenSubject {
+        user_id: db_user.id,
+        username: db_user.username.clone(),
+        role: db_user.role_id,
+        employee_id: db_user.employee_id,
+        org_id: db_user.org_id,
+        must_change_password: db_user.must_change_password,
+    };
+
     // 4️⃣ Generate access token
     debug!("Generating access token");
 
-    let access_token = generate_access_token(
-        db_user.id,
-        db_user.username.clone(),
-        db_user.role_id,
-        db_user.employee_id,
-        &config.jwt_secret,
-        config.access_token_ttl,
-    );
+    let access_token =
+        generate_access_token(&subject, &config.jwt_secret, config.access_token_ttl);
 
     // 5️⃣ Generate refresh token
     debug!("Generating refresh token");
 
-    let (refresh_token, refresh_claims) = generate_refresh_token(
-        db_user.id,
-        db_user.username.clone(),
-        db_user.role_id,
-        db_user.employee_id,
-        &config.jwt_secret,
-        config.refresh_token_ttl,
-    );
+    let (refresh_token, refresh_claims) =
+        generate_refresh_token(&subject, &config.jwt_secret, config.refresh_token_ttl);
 
     // 6️⃣ Store refresh token
     debug!(
@@ -228,7 +285,7 @@ pub async fn login(
     .await
     {
         error!(error = %e, "Failed to store refresh token");
-        return HttpResponse::InternalServerError().finish();
+        return Err(ApiError::internal());
     }
 
     // 7️⃣ Update last_login_at (non-fatal)
@@ -247,109 +304,160 @@ pub async fn login(
 
     info!("Login successful");
 
-    HttpResponse::Ok().json(LoginResponse {
+    Ok(HttpResponse::Ok().json(LoginResponse {
         access_token,
         refresh_token,
-    })
+        must_change_password: db_user.must_change_password,
+    }))
 }
 
 #[get("/protected")]
-pub async fn protected(req: HttpRequest) -> impl Responder {
+pub async fn protected(req: HttpRequest) -> Result<HttpResponse, ApiError> {
     match req.extensions().get::<String>() {
-        Some(user) => HttpResponse::Ok().body(user.clone()),
-        None => HttpResponse::Unauthorized().body("No user"),
+        Some(user) => Ok(HttpResponse::Ok().body(user.clone())),
+        None => Err(ApiError::unauthorized("unauthorized", "No user")),
     }
 }
 
+fn refresh_blocked() -> ApiError {
+    ApiError::too_many_requests("refresh_blocked", "Too many failed refresh attempts")
+}
+
 // #[post("/refresh")]
 pub async fn refresh_token(
     req: HttpRequest,
     pool: web::Data<MySqlPool>,
     config: web::Data<Config>,
-) -> impl Responder {
+) -> Result<HttpResponse, ApiError> {
     let header = match req.headers().get("Authorization") {
         Some(h) => h.to_str().unwrap_or(""),
-        None => return HttpResponse::Unauthorized().body("No token"),
+        None => return Err(ApiError::unauthorized("missing_token", "Missing token")),
     };
 
     let token = match header.strip_prefix("Bearer ") {
         Some(t) => t,
-        None => return HttpResponse::Unauthorized().body("Invalid token"),
+        None => {
+            return Err(ApiError::unauthorized(
+                "malformed_header",
+                "Authorization header must start with Bearer",
+            ));
+        }
     };
 
-    let claims = match verify_token(token, &config.jwt_secret) {
+    // behind a proxy every peer is the proxy; one bad client must not lock everyone out
+    let ip_key = format!(
+        "ip:{}",
+        ClientIp::new(&config)
+            .resolve(req.peer_addr().map(|a| a.ip()), req.headers())
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    );
+
+    if refresh_guard::is_blocked(std::slice::from_ref(&ip_key)).await {
+        return Err(refresh_blocked());
+    }
+
+    let claims = match verify_token(token, &config.jwt_secret, TokenType::Refresh) {
         Ok(c) => c,
-        Err(_) => return HttpResponse::Unauthorized().finish(),
+        // an expired refresh token is the normal end of a session
+        Err(e @ TokenError::Expired) => {
+            info!("Refresh rejected: token expired");
+            return Err(ApiError::unauthorized(e.code(), e.message()));
+        }
+        Err(e @ TokenError::Invalid) => {
+            refresh_guard::record_failure(std::slice::from_ref(&ip_key), "invalid_token").await;
+            return Err(ApiError::unauthorized(e.code(), e.message()));
+        }
+        Err(e @ TokenError::WrongType) => {
+            refresh_guard::record_failure(std::slice::from_ref(&ip_key), "wrong_token_type").await;
+            return Err(ApiError::unauthorized(e.code(), e.message()));
+        }
     };
 
-    if claims.token_type != TokenType::Refresh {
-        return HttpResponse::Unauthorized().finish();
+    let guard_keys = [ip_key, format!("user:{}", claims.user_id)];
+
+    if refresh_guard::is_blocked(&guard_keys).await {
+        return Err(refresh_blocked());
     }
 
-    // 🔍 find refresh token in DB
-    let record = sqlx::query!(
+    // 🔍 find refresh token in DB, with the state of its account
+    let record = sqlx::query_as::<_, (u64, u64, bool, bool)>(
         r#"
-        SELECT id, user_id, revoked
-        FROM refresh_tokens
-        WHERE jti = ?
+        SELECT rt.id, rt.user_id, rt.revoked, COALESCE(u.is_active, FALSE)
+        FROM refresh_tokens rt
+        LEFT JOIN users u ON u.id = rt.user_id
+        WHERE rt.jti = ?
         "#,
-        claims.jti
     )
+    .bind(&claims.jti)
     .fetch_optional(pool.get_ref())
     .await
-    .unwrap();
-
-    let record = match record {
-        Some(r) if r.revoked == 0 => r, // 0=not revoked
-        _ => return HttpResponse::Unauthorized().finish(),
+    .map_err(|e| {
+        error!(error = %e, "Failed to look up refresh token");
+        ApiError::internal()
+    })?;
+
+    let (record_id, record_user_id) = match record {
+        Some((id, user_id, false, true)) => (id, user_id),
+        // validly signed but unknown jti: possible forgery
+        None => {
+            refresh_guard::record_failure(&guard_keys, "token_not_found").await;
+            return Err(ApiError::unauthorized("token_invalid", "Invalid token"));
+        }
+        // a rotated token presented again: possible theft
+        Some((_, _, true, _)) => {
+            refresh_guard::record_failure(&guard_keys, "token_reused").await;
+            return Err(ApiError::unauthorized("token_revoked", "Token revoked"));
+        }
+        // sessions end with the account, even if a token escaped revocation
+        Some((_, user_id, false, false)) => {
+            info!(user_id, "Refresh rejected: account disabled");
+            return Err(ApiError::unauthorized("account_disabled", "Account disabled"));
+        }
     };
 
+    refresh_guard::reset(&guard_keys).await;
+
     // 🔥 revoke old refresh token
-    sqlx::query!(
-        "UPDATE refresh_tokens SET revoked = TRUE WHERE id = ?",
-        record.id
-    )
-    .execute(pool.get_ref())
-    .await
-    .unwrap();
+    sqlx::query("UPDATE refresh_tokens SET revoked = TRUE WHERE id = ?")
+        .bind(record_id)
+        .execute(pool.get_ref())
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to rotate refresh token");
+            ApiError::internal()
+        })?;
+
+    let subject = TokenSubject::from(&claims);
 
     // 🔄 issue new refresh token
-    let (new_refresh_token, new_claims) = generate_refresh_token(
-        claims.user_id.clone(),
-        claims.sub.clone(),
-        claims.role.clone(),
-        claims.employee_id,
-        &config.jwt_secret,
-        config.refresh_token_ttl,
-    );
+    let (new_refresh_token, new_claims) =
+        generate_refresh_token(&subject, &config.jwt_secret, config.refresh_token_ttl);
 
     sqlx::query!(
         r#"
         INSERT INTO refresh_tokens (user_id, jti, expires_at)
         VALUES (?, ?, FROM_UNIXTIME(?))
         "#,
-        record.user_id,
+        record_user_id,
         new_claims.jti,
         new_claims.exp as i64
     )
     .execute(pool.get_ref())
     .await
-    .unwrap();
+    .map_err(|e| {
+        error!(error = %e, "Failed to rotate refresh token");
+        ApiError::internal()
+    })?;
 
     // 🎫 new access token
-    let access_token = generate_access_token(
-        claims.user_id.clone(),
-        claims.sub.clone(),
-        claims.role.clone(),
-        claims.employee_id,
-        &config.jwt_secret,
-        config.access_token_ttl,
-    );
+    let access_token =
+        generate_access_token(&subject, &config.jwt_secret, config.access_token_ttl);
 
-    HttpResponse::Ok().json(serde_json::json!({
+    Ok(HttpResponse::Ok().json(serde_json::json!({
         "access_token": access_token,
         "refresh_token": new_refresh_token
-    }))
+    })))
 }
 
 // #[post("/logout")]
@@ -369,18 +477,13 @@ pub async fn logout(
         None => return HttpResponse::NoContent().finish(),
     };
 
-    // 2️⃣ verify JWT
-    let claims = match verify_token(token, &config.jwt_secret) {
+    // 2️⃣ verify JWT; only refresh tokens can logout
+    let claims = match verify_token(token, &config.jwt_secret, TokenType::Refresh) {
         Ok(c) => c,
         Err(_) => return HttpResponse::NoContent().finish(),
     };
 
-    // 3️⃣ only refresh tokens can logout
-    if claims.token_type != TokenType::Refresh {
-        return HttpResponse::NoContent().finish();
-    }
-
-    // 4️⃣ revoke refresh token (idempotent)
+    // 3️⃣ revoke refresh token (idempotent)
     let _ = sqlx::query!(
         r#"
         UPDATE refresh_tokens
@@ -392,6 +495,446 @@ pub async fn logout(
     .execute(pool.get_ref())
     .await;
 
-    // 5️⃣ success (even if token didn't exist)
+    // 4️⃣ success (even if token didn't exist)
     HttpResponse::NoContent().finish()
 }
+
+/// Blacklist an access token until it expires, so it stops working right away.
+/// Revokes the caller's own token, or with `token` (admin) another one of the same organization.
+pub async fn revoke_token(
+    auth: AuthUser,
+    body: Option<web::Json<RevokeTokenReq>>,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    let other = body.and_then(|b| b.into_inner().token);
+
+    let (jti, user_id, exp) = match other {
+        None => (auth.jti.clone(), auth.user_id, auth.exp),
+        Some(token) => {
+            if !auth.has_permission("auth.revoke_any") {
+                return Err(ApiError::forbidden(
+                    "missing_permission",
+                    "Missing permission: auth.revoke_any",
+                ));
+            }
+
+            let claims = match verify_token(&token, &config.jwt_secret, TokenType::Access) {
+                Ok(c) => c,
+                // already unusable, nothing to blacklist
+                Err(TokenError::Expired) => return Ok(HttpResponse::NoContent().finish()),
+                Err(e) => {
+                    return Err(ApiError::bad_request(e.code(), e.message()).with_field("token"));
+                }
+            };
+
+            if claims.org_id != auth.org_id {
+                return Err(ApiError::not_found("Token not found"));
+            }
+
+            (claims.jti, claims.user_id, claims.exp)
+        }
+    };
+
+    if let Err(e) = revoked_tokens::revoke(pool.get_ref(), &jti, user_id, exp).await {
+        error!(error = %e, user_id, "Failed to revoke access token");
+        return Err(ApiError::internal());
+    }
+
+    info!(revoked_by = auth.user_id, user_id, "Access token revoked");
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Change the caller's own password.
+/// Clears a pending forced change and revokes every refresh token, so the user logs in again.
+pub async fn change_password(
+    auth: AuthUser,
+    body: web::Json<ChangePasswordReq>,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    if body.new_password.is_empty() {
+        return Err(ApiError::validation("new_password", "New password must not be empty"));
+    }
+
+    if body.new_password == body.current_password {
+        return Err(ApiError::validation(
+            "new_password",
+            "New password must differ from the current one",
+        ));
+    }
+
+    if let Err(message) = validate_password_strength(&body.new_password) {
+        return Err(ApiError::bad_request("weak_password", message).with_field("new_password"));
+    }
+
+    let current_hash = match sqlx::query_scalar::<_, String>("SELECT password FROM users WHERE id = ?")
+        .bind(auth.user_id)
+        .fetch_optional(pool.get_ref())
+        .await
+    {
+        Ok(Some(hash)) => hash,
+        Ok(None) => return Err(ApiError::unauthorized("unknown_user", "Unknown user")),
+        Err(e) => {
+            error!(error = %e, user_id = auth.user_id, "Failed to fetch user for password change");
+            return Err(ApiError::internal());
+        }
+    };
+
+    match verify_password(&body.current_password, &current_hash) {
+        Ok(()) => {}
+        Err(argon2::password_hash::Error::Password) => {
+            return Err(ApiError::unauthorized("invalid_credentials", "Invalid credentials"));
+        }
+        Err(e) => {
+            error!(error = %e, user_id = auth.user_id, "Stored password hash is unusable");
+            return Err(ApiError::internal());
+        }
+    }
+
+    // password and history change together
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!(error = %e, user_id = auth.user_id, "Failed to start password change transaction");
+            return Err(ApiError::internal());
+        }
+    };
+
+    match password_history::is_reused(
+        &mut tx,
+        auth.user_id,
+        &current_hash,
+        &body.new_password,
+        config.password_history_size,
+    )
+    .await
+    {
+        Ok(false) => {}
+        Ok(true) => {
+            return Err(password_reused());
+        }
+        Err(e) => {
+            error!(error = %e, user_id = auth.user_id, "Failed to check password history");
+            return Err(ApiError::internal());
+        }
+    }
+
+    let hashed = match hash_password(&body.new_password) {
+        Ok(hashed) => hashed,
+        Err(e) => {
+            error!(error = %e, user_id = auth.user_id, "Failed to hash password");
+            return Err(ApiError::internal());
+        }
+    };
+
+    if let Err(e) = sqlx::query(
+        "UPDATE users SET password = ?, must_change_password = 0 WHERE id = ?",
+    )
+    .bind(hashed)
+    .bind(auth.user_id)
+    .execute(&mut *tx)
+    .await
+    {
+        error!(error = %e, user_id = auth.user_id, "Failed to change password");
+        return Err(ApiError::internal());
+    }
+
+    if let Err(e) =
+        password_history::record(&mut tx, auth.user_id, &current_hash, config.password_history_size)
+            .await
+    {
+        error!(error = %e, user_id = auth.user_id, "Failed to record password history");
+        return Err(ApiError::internal());
+    }
+
+    if let Err(e) = tx.commit().await {
+        error!(error = %e, user_id = auth.user_id, "Failed to commit password change");
+        return Err(ApiError::internal());
+    }
+
+    if let Err(e) = sqlx::query("UPDATE refresh_tokens SET revoked = 1 WHERE user_id = ?")
+        .bind(auth.user_id)
+        .execute(pool.get_ref())
+        .await
+    {
+        error!(error = %e, user_id = auth.user_id, "Failed to revoke sessions after password change");
+    }
+
+    info!(user_id = auth.user_id, "Password changed");
+
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Password changed, please log in again"
+    })))
+}
+
+fn password_reused() -> ApiError {
+    ApiError::bad_request("password_reused", "New password was used recently")
+        .with_field("new_password")
+}
+
+/// Always the same answer, so the endpoint can't be used to find out which accounts exist
+fn forgot_password_accepted() -> HttpResponse {
+    HttpResponse::Ok().json(json!({
+        "message": "If the account exists, a reset link has been sent"
+    }))
+}
+
+fn reset_unavailable() -> ApiError {
+    ApiError::new(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "reset_unavailable",
+        "Password reset is unavailable: no notification channel is configured",
+    )
+}
+
+/// Start a password reset: store a hashed single-use token and send it to the
+/// employee email linked to the account
+pub async fn forgot_password(
+    body: web::Json<ForgotPasswordReq>,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    notifier: web::Data<dyn Notifier>,
+) -> Result<HttpResponse, ApiError> {
+    let (column, value) = match (&body.username, &body.email) {
+        (Some(username), _) if !username.trim().is_empty() => ("u.username", username.trim()),
+        (_, Some(email)) if !email.trim().is_empty() => ("e.email", email.trim()),
+        _ => {
+            return Err(ApiError::bad_request(
+                "validation_error",
+                "username or email is required",
+            ));
+        }
+    };
+
+    // refuse up front rather than issue tokens nobody will receive
+    if !notifier.is_available() {
+        return Err(reset_unavailable());
+    }
+
+    let sql = format!(
+        r#"
+        SELECT u.id, e.email
+        FROM users u
+        LEFT JOIN employees e ON e.id = u.employee_id
+        WHERE {} = ?
+        AND u.is_active = TRUE
+        LIMIT 1
+        "#,
+        column
+    );
+
+    let account = match sqlx::query_as::<_, (u64, Option<String>)>(&sql)
+        .bind(value)
+        .fetch_optional(pool.get_ref())
+        .await
+    {
+        Ok(account) => account,
+        Err(e) => {
+            error!(error = %e, "Failed to look up account for password reset");
+            return Err(ApiError::internal());
+        }
+    };
+
+    let Some((user_id, email)) = account else {
+        info!("Password reset requested for unknown or disabled account");
+        return Ok(forgot_password_accepted());
+    };
+
+    let Some(email) = email else {
+        info!(user_id, "Password reset requested for account without email");
+        return Ok(forgot_password_accepted());
+    };
+
+    let token = generate_reset_token();
+
+    if let Err(e) = sqlx::query(
+        r#"
+        INSERT INTO password_resets (user_id, token_hash, expires_at)
+        VALUES (?, ?, NOW() + INTERVAL ? SECOND)
+        "#,
+    )
+    .bind(user_id)
+    .bind(hash_reset_token(&token))
+    .bind(config.password_reset_ttl)
+    .execute(pool.get_ref())
+    .await
+    {
+        error!(error = %e, user_id, "Failed to store password reset token");
+        return Err(ApiError::internal());
+    }
+
+    if !notifier.notify(Notification {
+        event: "password.reset_requested",
+        recipient: email,
+        recipient_id: user_id,
+        payload: json!({
+            "reset_token": token,
+            "expires_in": config.password_reset_ttl,
+        }),
+    }) {
+        return Err(reset_unavailable());
+    }
+
+    info!(user_id, "Password reset token issued");
+    Ok(forgot_password_accepted())
+}
+
+/// Finish a password reset with the token from `forgot-password`
+pub async fn reset_password(
+    body: web::Json<ResetPasswordReq>,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, ApiError> {
+    if let Err(message) = validate_password_strength(&body.new_password) {
+        return Err(ApiError::bad_request("weak_password", message).with_field("new_password"));
+    }
+
+    let mut tx = match pool.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            error!(error = %e, "Failed to start password reset transaction");
+            return Err(ApiError::internal());
+        }
+    };
+
+    // locked so two requests can't both consume the token
+    let reset = sqlx::query_as::<_, (u64, u64)>(
+        r#"
+        SELECT id, user_id
+        FROM password_resets
+        WHERE token_hash = ?
+        AND used_at IS NULL
+        AND expires_at > NOW()
+        FOR UPDATE
+        "#,
+    )
+    .bind(hash_reset_token(&body.token))
+    .fetch_optional(&mut *tx)
+    .await;
+
+    let (reset_id, user_id) = match reset {
+        Ok(Some(reset)) => reset,
+        Ok(None) => {
+            info!("Password reset rejected: unknown, used or expired token");
+            return Err(ApiError::bad_request(
+                "invalid_or_expired_token",
+                "Reset token is invalid or expired",
+            )
+            .with_field("token"));
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to look up password reset token");
+            return Err(ApiError::internal());
+        }
+    };
+
+    let current_hash = match sqlx::query_scalar::<_, String>("SELECT password FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await
+    {
+        Ok(hash) => hash,
+        Err(e) => {
+            error!(error = %e, user_id, "Failed to fetch user for password reset");
+            return Err(ApiError::internal());
+        }
+    };
+
+    match password_history::is_reused(
+        &mut tx,
+        user_id,
+        &current_hash,
+        &body.new_password,
+        config.password_history_size,
+    )
+    .await
+    {
+        // the token stays usable for another attempt
+        Ok(true) => {
+            return Err(password_reused());
+        }
+        Ok(false) => {}
+        Err(e) => {
+            error!(error = %e, user_id, "Failed to check password history");
+            return Err(ApiError::internal());
+        }
+    }
+
+    let hashed = match hash_password(&body.new_password) {
+        Ok(hashed) => hashed,
+        Err(e) => {
+            error!(error = %e, user_id, "Failed to hash password");
+            return Err(ApiError::internal());
+        }
+    };
+
+    let statements = [
+        // this token and any other outstanding one of the user
+        sqlx::query("UPDATE password_resets SET used_at = NOW() WHERE user_id = ? AND used_at IS NULL")
+            .bind(user_id),
+        sqlx::query("UPDATE users SET password = ?, must_change_password = 0 WHERE id = ?")
+            .bind(hashed)
+            .bind(user_id),
+        // whoever had the old password loses their sessions
+        sqlx::query("UPDATE refresh_tokens SET revoked = 1 WHERE user_id = ?").bind(user_id),
+    ];
+
+    for statement in statements {
+        if let Err(e) = statement.execute(&mut *tx).await {
+            error!(error = %e, user_id, reset_id, "Failed to reset password");
+            return Err(ApiError::internal());
+        }
+    }
+
+    if let Err(e) =
+        password_history::record(&mut tx, user_id, &current_hash, config.password_history_size).await
+    {
+        error!(error = %e, user_id, "Failed to record password history");
+        return Err(ApiError::internal());
+    }
+
+    if let Err(e) = tx.commit().await {
+        error!(error = %e, user_id, "Failed to commit password reset");
+        return Err(ApiError::internal());
+    }
+
+    info!(user_id, "Password reset");
+
+    Ok(HttpResponse::Ok().json(json!({
+        "message": "Password reset, please log in"
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn taken_username_is_remembered_after_the_db_hit() {
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let user = test_support::user(&pool, org, None, Role::Employee).await;
+        let username: String = sqlx::query_scalar("SELECT username FROM users WHERE id = ?")
+            .bind(user.user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        assert!(!is_username_available(&username, &pool).await);
+        assert!(username_filter::might_exist(&username));
+        assert!(username_cache::is_taken(&username).await);
+
+        // with the row gone behind the caches' back, only the cache can still say taken
+        sqlx::query("DELETE FROM users WHERE id = ?")
+            .bind(user.user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(!is_username_available(&username, &pool).await);
+
+        username_cache::forget(&username).await;
+    }
+}
@@ -0,0 +1,168 @@
+use crate::model::role::Role;
+use crate::utils::audit::{self, AuditEntry};
+use sqlx::MySqlPool;
+use std::time::Duration;
+
+/// How often dormant accounts are looked for
+const SWEEP_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Start the background task disabling users who haven't logged in for `days` days.
+/// `0` turns it off. Service accounts (`system`, `api_user`) never expire; users who
+/// never logged in are left alone too.
+pub fn start(pool: MySqlPool, days: u32) {
+    if days == 0 {
+        return;
+    }
+
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            match disable_dormant(&pool, days).await {
+                Ok(0) => {}
+                Ok(n) => tracing::info!(disabled = n, days, "Disabled dormant accounts"),
+                Err(e) => tracing::error!(error = %e, "Failed to disable dormant accounts"),
+            }
+        }
+    });
+}
+
+async fn disable_dormant(pool: &MySqlPool, days: u32) -> Result<usize, sqlx::Error> {
+    let dormant = sqlx::query_as::<_, (u64, u64)>(
+        r#"
+        SELECT id, org_id
+        FROM users
+        WHERE is_active = TRUE
+        AND role_id NOT IN (?, ?)
+        AND last_login_at < NOW() - INTERVAL ? DAY
+        "#,
+    )
+    .bind(Role::System as u8)
+    .bind(Role::ApiUser as u8)
+    .bind(days)
+    .fetch_all(pool)
+    .await?;
+
+    let mut disabled = 0;
+    for (user_id, org_id) in dormant {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("UPDATE users SET is_active = FALSE WHERE id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // sessions end with the account
+        sqlx::query("UPDATE refresh_tokens SET revoked = 1 WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let entry = AuditEntry {
+            org_id,
+            actor_user_id: None,
+            entity: "user",
+            entity_id: Some(user_id),
+            action: "auto_disable",
+            details: Some(serde_json::json!({ "inactive_days": days })),
+        };
+        if audit::record(&mut *tx, entry).await.is_err() {
+            // already logged by `audit::record`; retried on the next sweep
+            continue;
+        }
+
+        tx.commit().await?;
+        disabled += 1;
+    }
+
+    Ok(disabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::password::hash_password;
+    use crate::test_support;
+
+    const PASSWORD: &str = "correct horse 1";
+
+    /// A user with a known password who last logged in `days_ago` days ago
+    async fn user_last_seen(
+        pool: &MySqlPool,
+        org_id: u64,
+        role: Role,
+        days_ago: u32,
+    ) -> (u64, String) {
+        let user_id = test_support::user(pool, org_id, None, role).await.user_id;
+        sqlx::query(
+            "UPDATE users SET password = ?, last_login_at = NOW() - INTERVAL ? DAY WHERE id = ?",
+        )
+        .bind(hash_password(PASSWORD).unwrap())
+        .bind(days_ago)
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .unwrap();
+
+        let username = sqlx::query_scalar("SELECT username FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        (user_id, username)
+    }
+
+    async fn is_active(pool: &MySqlPool, user_id: u64) -> bool {
+        sqlx::query_scalar("SELECT is_active FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn dormant_user_cannot_log_in() {
+        use crate::auth::handlers::login;
+        use actix_web::http::StatusCode;
+        use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+        use actix_web::{App, web};
+
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let (dormant, dormant_name) = user_last_seen(&pool, org, Role::Employee, 100).await;
+        let (service, _) = user_last_seen(&pool, org, Role::ApiUser, 100).await;
+        let (recent, recent_name) = user_last_seen(&pool, org, Role::Employee, 5).await;
+
+        disable_dormant(&pool, 90).await.unwrap();
+
+        assert!(!is_active(&pool, dormant).await);
+        // service accounts never expire
+        assert!(is_active(&pool, service).await);
+        assert!(is_active(&pool, recent).await);
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(test_support::config()))
+                .route("/auth/login", web::post().to(login)),
+        )
+        .await;
+        let login_as = |username: &str| {
+            TestRequest::post()
+                .uri("/auth/login")
+                .set_json(serde_json::json!({"username": username, "password": PASSWORD}))
+                .to_request()
+        };
+
+        let res = call_service(&app, login_as(&dormant_name)).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        let body: serde_json::Value = read_body_json(res).await;
+        assert_eq!(body["code"], "account_disabled");
+
+        assert_eq!(
+            call_service(&app, login_as(&recent_name)).await.status(),
+            StatusCode::OK
+        );
+    }
+}
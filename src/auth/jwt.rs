@@ -1,7 +1,9 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::{model::user, models::{Claims, TokenType}};
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode, errors::Error};
+use crate::models::{Claims, TokenType};
+use jsonwebtoken::{
+    DecodingKey, EncodingKey, Header, Validation, decode, encode, errors::ErrorKind,
+};
 use uuid::Uuid;
 
 fn now() -> usize {
@@ -11,23 +13,45 @@ fn now() -> usize {
         .as_secs() as usize
 }
 
-pub fn generate_access_token(
-    user_id: u64,
-    username: String,
-    role: u8,
-    employee_id: Option<u64>,
-    secret: &str,
-    ttl: usize,
-) -> String {
-    let claims = Claims {
-        user_id: user_id,
-        sub: username,
-        role: role,
+/// Who a token is issued for; copied into the access and refresh claims
+pub struct TokenSubject {
+    pub user_id: u64,
+    pub username: String,
+    pub role: u8,
+    pub employee_id: Option<u64>,
+    pub org_id: u64,
+    pub must_change_password: bool,
+}
+
+impl From<&Claims> for TokenSubject {
+    fn from(claims: &Claims) -> Self {
+        TokenSubject {
+            user_id: claims.user_id,
+            username: claims.sub.clone(),
+            role: claims.role,
+            employee_id: claims.employee_id,
+            org_id: claims.org_id,
+            must_change_password: claims.must_change_password,
+        }
+    }
+}
+
+fn build_claims(subject: &TokenSubject, token_type: TokenType, ttl: usize) -> Claims {
+    Claims {
+        user_id: subject.user_id,
+        sub: subject.username.clone(),
+        role: subject.role,
         exp: now() + ttl,
         jti: Uuid::new_v4().to_string(),
-        token_type: TokenType::Access,
-        employee_id: employee_id,
-    };
+        token_type,
+        employee_id: subject.employee_id,
+        org_id: subject.org_id,
+        must_change_password: subject.must_change_password,
+    }
+}
+
+pub fn generate_access_token(subject: &TokenSubject, secret: &str, ttl: usize) -> String {
+    let claims = build_claims(subject, TokenType::Access, ttl);
 
     encode(
         &Header::default(),
@@ -38,22 +62,11 @@ pub fn generate_access_token(
 }
 
 pub fn generate_refresh_token(
-    user_id: u64,
-    username: String,
-    role: u8,
-    employee_id: Option<u64>,
+    subject: &TokenSubject,
     secret: &str,
     ttl: usize,
 ) -> (String, Claims) {
-    let claims = Claims {
-        user_id: user_id,
-        sub: username,
-        role: role,
-        exp: now() + ttl,
-        jti: Uuid::new_v4().to_string(),
-        token_type: TokenType::Refresh,
-        employee_id: employee_id,
-    };
+    let claims = build_claims(subject, TokenType::Refresh, ttl);
 
     let token = encode(
         &Header::default(),
@@ -65,12 +78,51 @@ pub fn generate_refresh_token(
     (token, claims)
 }
 
-pub fn verify_token(token: &str, secret: &str) -> Result<Claims, String> {
-    decode::<Claims>(
+/// Why a token was rejected; lets clients tell "refresh" from "log in again"
+#[derive(Debug, PartialEq)]
+pub enum TokenError {
+    /// Well-formed and correctly signed, but past `exp`
+    Expired,
+    /// Malformed, tampered with or signed with another key
+    Invalid,
+    /// An access token where a refresh token was expected, or the reverse
+    WrongType,
+}
+
+impl TokenError {
+    /// Machine-readable `code` of the 401 body
+    pub fn code(&self) -> &'static str {
+        match self {
+            TokenError::Expired => "token_expired",
+            TokenError::Invalid => "token_invalid",
+            TokenError::WrongType => "wrong_token_type",
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            TokenError::Expired => "Token expired",
+            TokenError::Invalid => "Invalid token",
+            TokenError::WrongType => "Wrong token type",
+        }
+    }
+}
+
+pub fn verify_token(token: &str, secret: &str, expected: TokenType) -> Result<Claims, TokenError> {
+    let claims = decode::<Claims>(
         token,
         &DecodingKey::from_secret(secret.as_bytes()),
         &Validation::default(),
     )
     .map(|data| data.claims)
-    .map_err(|e| e.to_string())
+    .map_err(|e| match e.kind() {
+        ErrorKind::ExpiredSignature => TokenError::Expired,
+        _ => TokenError::Invalid,
+    })?;
+
+    if claims.token_type != expected {
+        return Err(TokenError::WrongType);
+    }
+
+    Ok(claims)
 }
@@ -1,5 +1,12 @@
+pub mod active_user;
 pub mod handlers;
+pub mod inactivity;
 pub mod jwt;
 pub mod middleware;
 pub mod password;
+pub mod password_history;
+pub mod permissions;
+pub mod refresh_guard;
+pub mod revoked_tokens;
+#[allow(clippy::module_inception)]
 pub mod auth;
@@ -1,21 +1,151 @@
 use argon2::{
-    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
+    password_hash::{
+        rand_core::{OsRng, RngCore},
+        Error, PasswordHash, PasswordHasher, PasswordVerifier, SaltString,
+    },
+    Algorithm, Argon2, Params, Version,
 };
+use once_cell::sync::OnceCell;
+use sha2::{Digest, Sha256};
 
-pub fn hash_password(password: &str) -> String {
-    let argon2 = Argon2::default();
+/// Characters used for generated passwords (no look-alikes such as 0/O, 1/l/I)
+const TEMP_PASSWORD_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const TEMP_PASSWORD_LEN: usize = 12;
+
+/// Shortest password users may choose
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+/// Longest password accepted, so hashing cost stays bounded
+pub const MAX_PASSWORD_LEN: usize = 128;
+
+static HASHER: OnceCell<Argon2<'static>> = OnceCell::new();
+
+/// Configure the Argon2id cost for new hashes; until called the crate defaults are used.
+/// Existing hashes keep verifying since they carry their own parameters.
+pub fn init(memory_kib: u32, iterations: u32, parallelism: u32) {
+    let params = Params::new(memory_kib, iterations, parallelism, None)
+        .expect("ARGON2_MEMORY_KIB / ARGON2_ITERATIONS / ARGON2_PARALLELISM out of range");
+
+    let _ = HASHER.set(Argon2::new(Algorithm::Argon2id, Version::V0x13, params));
+}
+
+fn hasher() -> Argon2<'static> {
+    HASHER.get().cloned().unwrap_or_default()
+}
+
+pub fn hash_password(password: &str) -> Result<String, Error> {
     let salt = SaltString::generate(&mut OsRng);
 
-    argon2
-        .hash_password(password.as_bytes(), &salt)
-        .unwrap()
-        .to_string()
+    Ok(hasher()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+/// `Error::Password` on a mismatch; any other error means the stored hash is unusable
+pub fn verify_password(password: &str, hashed: &str) -> Result<(), Error> {
+    let parsed = PasswordHash::new(hashed)?;
+
+    hasher().verify_password(password.as_bytes(), &parsed)
+}
+
+/// Password policy for user-chosen passwords: `MIN_PASSWORD_LEN` to `MAX_PASSWORD_LEN`
+/// characters, with at least one letter and one digit. The error is shown to the user.
+pub fn validate_password_strength(password: &str) -> Result<(), String> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(format!(
+            "Password must be at least {} characters long",
+            MIN_PASSWORD_LEN
+        ));
+    }
+    if password.chars().count() > MAX_PASSWORD_LEN {
+        return Err(format!(
+            "Password must be at most {} characters long",
+            MAX_PASSWORD_LEN
+        ));
+    }
+    if !password.chars().any(|c| c.is_alphabetic()) {
+        return Err("Password must contain at least one letter".to_string());
+    }
+    if !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err("Password must contain at least one digit".to_string());
+    }
+    Ok(())
+}
+
+/// Random one-time password handed out by HR; always contains a letter and a digit
+pub fn generate_temporary_password() -> String {
+    loop {
+        let password: String = (0..TEMP_PASSWORD_LEN)
+            .map(|_| {
+                let idx = OsRng.next_u32() as usize % TEMP_PASSWORD_ALPHABET.len();
+                TEMP_PASSWORD_ALPHABET[idx] as char
+            })
+            .collect();
+
+        if password.chars().any(|c| c.is_ascii_digit())
+            && password.chars().any(|c| c.is_ascii_alphabetic())
+        {
+            return password;
+        }
+    }
 }
 
-pub fn verify_password(password: &str, hashed: &str) -> Result<(), argon2::password_hash::Error> {
-    let argon2 = Argon2::default();
-    let parsed = PasswordHash::new(hashed).unwrap();
+/// Random single-use password reset token (64 hex chars), sent to the user
+pub fn generate_reset_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Reset tokens are stored hashed so a leaked table can't be used to take over accounts.
+/// The token is random and long, so a fast unsalted hash is enough.
+pub fn hash_reset_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `len` characters: one digit, the rest letters
+    fn password_of(len: usize) -> String {
+        format!("1{}", "a".repeat(len - 1))
+    }
+
+    #[test]
+    fn minimum_length_is_inclusive() {
+        assert!(validate_password_strength(&password_of(MIN_PASSWORD_LEN)).is_ok());
+        assert!(validate_password_strength(&password_of(MIN_PASSWORD_LEN - 1)).is_err());
+    }
+
+    #[test]
+    fn maximum_length_is_inclusive() {
+        assert!(validate_password_strength(&password_of(MAX_PASSWORD_LEN)).is_ok());
+        assert!(validate_password_strength(&password_of(MAX_PASSWORD_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn length_counts_characters_not_bytes() {
+        // 8 characters, 15 bytes
+        assert!(validate_password_strength("1ééééééé").is_ok());
+    }
+
+    #[test]
+    fn letter_and_digit_are_required() {
+        assert_eq!(
+            validate_password_strength("12345678"),
+            Err("Password must contain at least one letter".to_string())
+        );
+        assert_eq!(
+            validate_password_strength("abcdefgh"),
+            Err("Password must contain at least one digit".to_string())
+        );
+    }
 
-    argon2.verify_password(password.as_bytes(), &parsed)
+    #[test]
+    fn generated_passwords_pass_the_policy() {
+        for _ in 0..100 {
+            assert!(validate_password_strength(&generate_temporary_password()).is_ok());
+        }
+    }
 }
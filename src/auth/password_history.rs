@@ -0,0 +1,155 @@
+use crate::auth::password::verify_password;
+use sqlx::MySqlConnection;
+
+/// Whether `new_password` is the current password or one of the `depth` before it.
+/// `depth == 0` turns the history check off.
+pub async fn is_reused(
+    conn: &mut MySqlConnection,
+    user_id: u64,
+    current_hash: &str,
+    new_password: &str,
+    depth: u32,
+) -> Result<bool, sqlx::Error> {
+    if depth == 0 {
+        return Ok(false);
+    }
+
+    if verify_password(new_password, current_hash).is_ok() {
+        return Ok(true);
+    }
+
+    let previous = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT password_hash
+        FROM password_history
+        WHERE user_id = ?
+        ORDER BY id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(user_id)
+    .bind(depth)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(previous
+        .iter()
+        .any(|hash| verify_password(new_password, hash).is_ok()))
+}
+
+/// Remember the hash being replaced and drop entries beyond the newest `depth`
+pub async fn record(
+    conn: &mut MySqlConnection,
+    user_id: u64,
+    replaced_hash: &str,
+    depth: u32,
+) -> Result<(), sqlx::Error> {
+    if depth == 0 {
+        return Ok(());
+    }
+
+    sqlx::query("INSERT INTO password_history (user_id, password_hash) VALUES (?, ?)")
+        .bind(user_id)
+        .bind(replaced_hash)
+        .execute(&mut *conn)
+        .await?;
+
+    // MySQL can't LIMIT inside IN (...), hence the derived table
+    sqlx::query(
+        r#"
+        DELETE FROM password_history
+        WHERE user_id = ?
+        AND id NOT IN (
+            SELECT id FROM (
+                SELECT id
+                FROM password_history
+                WHERE user_id = ?
+                ORDER BY id DESC
+                LIMIT ?
+            ) AS newest
+        )
+        "#,
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .bind(depth)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::auth::handlers::change_password;
+    use crate::auth::password::hash_password;
+    use crate::config::Config;
+    use crate::model::role::Role;
+    use crate::test_support;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+    use actix_web::{App, web};
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn recent_passwords_cannot_be_reused() {
+        let pool = test_support::pool().await;
+        let org = test_support::new_org();
+        let user = test_support::user(&pool, org, None, Role::Employee).await;
+        sqlx::query("UPDATE users SET password = ? WHERE id = ?")
+            .bind(hash_password("first-pass1").unwrap())
+            .bind(user.user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(pool.clone()))
+                .app_data(web::Data::new(Config::for_test(&[(
+                    "PASSWORD_HISTORY_SIZE",
+                    "2",
+                )])))
+                .route("/auth/change-password", web::post().to(change_password)),
+        )
+        .await;
+        let change = |current: &str, new: &str| {
+            TestRequest::post()
+                .uri("/auth/change-password")
+                .insert_header(user.bearer())
+                .set_json(serde_json::json!({"current_password": current, "new_password": new}))
+                .to_request()
+        };
+
+        for (current, new) in [
+            ("first-pass1", "second-pass2"),
+            ("second-pass2", "third-pass3"),
+        ] {
+            assert_eq!(
+                call_service(&app, change(current, new)).await.status(),
+                StatusCode::OK
+            );
+        }
+
+        for recent in ["first-pass1", "second-pass2"] {
+            let res = call_service(&app, change("third-pass3", recent)).await;
+            assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{}", recent);
+            let body: serde_json::Value = read_body_json(res).await;
+            assert_eq!(body["code"], "password_reused");
+        }
+
+        assert_eq!(
+            call_service(&app, change("third-pass3", "fourth-pass4"))
+                .await
+                .status(),
+            StatusCode::OK
+        );
+        // only the last 2 are kept, so the first one is free again
+        assert_eq!(
+            call_service(&app, change("fourth-pass4", "first-pass1"))
+                .await
+                .status(),
+            StatusCode::OK
+        );
+    }
+}
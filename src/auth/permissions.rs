@@ -0,0 +1,193 @@
+use std::collections::{HashMap, HashSet};
+
+use once_cell::sync::OnceCell;
+
+use crate::model::role::Role;
+
+/// role -> operations it may perform, e.g. `leave.approve`
+type PermissionMap = HashMap<Role, HashSet<String>>;
+
+static PERMISSIONS: OnceCell<PermissionMap> = OnceCell::new();
+
+const HR_PERMISSIONS: &[&str] = &[
+    "leave.read",
+    "leave.approve",
+    "leave.reject",
+    "leave.cancel_any",
+    "leave.export",
+    "employee.export",
+    "employee.photo",
+    "employee.timeline",
+    "employee.read_deleted",
+    "employee.restore",
+    "employee.status",
+    "attendance.present",
+    "attendance.read",
+    "attendance.correct",
+    "department.create",
+    "department.update",
+    "department.delete",
+    "job_title.create",
+    "job_title.update",
+    "job_title.delete",
+    "holiday.manage",
+];
+
+const ADMIN_ONLY_PERMISSIONS: &[&str] = &[
+    "payroll.read",
+    "payroll.create",
+    "payroll.update",
+    "payroll.approve",
+    "payroll.pay",
+    "user.reset_password",
+    "user.disable",
+    "user.delete",
+    "system.reconcile",
+    "leave.balance_adjust",
+    "audit.read",
+    "auth.revoke_any",
+];
+
+/// Operations that affect every organization, kept for the `system` role
+const SYSTEM_PERMISSIONS: &[&str] = &["system.maintenance"];
+
+/// The built-in mapping, equivalent to the old hardcoded role checks
+fn default_permissions() -> PermissionMap {
+    let hr: HashSet<String> = HR_PERMISSIONS.iter().map(|p| p.to_string()).collect();
+
+    let mut admin = hr.clone();
+    admin.extend(ADMIN_ONLY_PERMISSIONS.iter().map(|p| p.to_string()));
+
+    HashMap::from([
+        (Role::Admin, admin),
+        (Role::Hr, hr),
+        (Role::Employee, HashSet::new()),
+        (
+            Role::System,
+            SYSTEM_PERMISSIONS.iter().map(|p| p.to_string()).collect(),
+        ),
+        (Role::ApiUser, HashSet::new()),
+    ])
+}
+
+/// Load the permission map once at startup; panics on a bad `PERMISSIONS_FILE`.
+pub fn init(path: Option<&str>) {
+    let permissions = load(path).unwrap_or_else(|e| panic!("{}", e));
+
+    if let Some(path) = path {
+        tracing::info!(path, "Loaded permission overrides");
+    }
+
+    if PERMISSIONS.set(permissions).is_err() {
+        tracing::warn!("Permissions already initialised");
+    }
+}
+
+/// The permission map for `path`. Without a file the defaults are used.
+///
+/// The file is a JSON object of role name to permission list, e.g.
+/// `{"hr": ["leave.read", "payroll.read"]}`. A role listed in the file
+/// replaces its default set; roles not listed keep their defaults.
+fn load(path: Option<&str>) -> Result<PermissionMap, String> {
+    let mut permissions = default_permissions();
+
+    let Some(path) = path else {
+        return Ok(permissions);
+    };
+
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read PERMISSIONS_FILE {}: {}", path, e))?;
+    let overrides: HashMap<String, Vec<String>> = serde_json::from_str(&raw)
+        .map_err(|e| format!("Invalid PERMISSIONS_FILE {}: {}", path, e))?;
+
+    for (name, granted) in overrides {
+        let role = Role::from_name(&name)
+            .ok_or_else(|| format!("Unknown role '{}' in PERMISSIONS_FILE", name))?;
+        permissions.insert(role, granted.into_iter().collect());
+    }
+
+    Ok(permissions)
+}
+
+fn grants(map: &PermissionMap, role: Role, permission: &str) -> bool {
+    map.get(&role).is_some_and(|set| set.contains(permission))
+}
+
+/// Whether `role` is granted `permission`
+pub fn is_allowed(role: Role, permission: &str) -> bool {
+    match PERMISSIONS.get() {
+        Some(map) => grants(map, role, permission),
+        // not initialised (shouldn't happen outside of startup): fall back to the defaults
+        None => grants(&default_permissions(), role, permission),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A permissions file with `json` in the temp dir, removed on drop
+    struct PermissionsFile(std::path::PathBuf);
+
+    impl PermissionsFile {
+        fn new(json: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "permissions-{}.json",
+                uuid::Uuid::new_v4().to_simple()
+            ));
+            std::fs::write(&path, json).unwrap();
+            PermissionsFile(path)
+        }
+
+        fn path(&self) -> Option<&str> {
+            self.0.to_str()
+        }
+    }
+
+    impl Drop for PermissionsFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn defaults_keep_the_old_role_checks() {
+        let map = load(None).unwrap();
+
+        assert!(grants(&map, Role::Hr, "leave.approve"));
+        assert!(!grants(&map, Role::Hr, "payroll.read"));
+        assert!(grants(&map, Role::Admin, "payroll.read"));
+        assert!(!grants(&map, Role::Employee, "leave.approve"));
+    }
+
+    #[test]
+    fn custom_mapping_grants_hr_payroll() {
+        let file = PermissionsFile::new(r#"{"hr": ["leave.read", "payroll.read"]}"#);
+        let map = load(file.path()).unwrap();
+
+        assert!(grants(&map, Role::Hr, "payroll.read"));
+        // the listed set replaces HR's defaults
+        assert!(!grants(&map, Role::Hr, "leave.approve"));
+        // roles not in the file keep theirs
+        assert!(grants(&map, Role::Admin, "leave.approve"));
+    }
+
+    #[test]
+    fn bad_files_are_refused() {
+        let unknown_role = PermissionsFile::new(r#"{"manager": ["leave.read"]}"#);
+        assert!(
+            load(unknown_role.path())
+                .unwrap_err()
+                .contains("Unknown role 'manager'")
+        );
+
+        let not_json = PermissionsFile::new("hr: leave.read");
+        assert!(
+            load(not_json.path())
+                .unwrap_err()
+                .starts_with("Invalid PERMISSIONS_FILE")
+        );
+
+        assert!(load(Some("/nonexistent/permissions.json")).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use moka::future::Cache;
+use once_cell::sync::OnceCell;
+use std::time::Duration;
+
+/// Tracks failed refresh attempts per key (`ip:<addr>`, `user:<id>`) and
+/// temporarily blocks a key once it crosses the configured threshold.
+struct RefreshGuard {
+    /// failure count; expires once no failure happened for the window
+    failures: Cache<String, u32>,
+    /// keys currently locked out
+    blocked: Cache<String, ()>,
+    threshold: u32,
+}
+
+static GUARD: OnceCell<RefreshGuard> = OnceCell::new();
+
+impl RefreshGuard {
+    fn new(threshold: u32, window_secs: u64, lockout_secs: u64) -> Self {
+        RefreshGuard {
+            failures: Cache::builder()
+                .max_capacity(100_000)
+                .time_to_live(Duration::from_secs(window_secs))
+                .build(),
+            blocked: Cache::builder()
+                .max_capacity(100_000)
+                .time_to_live(Duration::from_secs(lockout_secs))
+                .build(),
+            threshold,
+        }
+    }
+
+    fn is_blocked(&self, keys: &[String]) -> bool {
+        keys.iter().any(|key| self.blocked.contains_key(key))
+    }
+
+    async fn record_failure(&self, keys: &[String], reason: &'static str) {
+        for key in keys {
+            let count = self.failures.get(key).await.unwrap_or(0) + 1;
+            self.failures.insert(key.clone(), count).await;
+
+            tracing::warn!(
+                target: "security",
+                key = %key,
+                reason,
+                count,
+                "Suspicious refresh attempt"
+            );
+
+            if self.threshold > 0 && count >= self.threshold {
+                self.blocked.insert(key.clone(), ()).await;
+                self.failures.invalidate(key).await;
+
+                tracing::warn!(
+                    target: "security",
+                    key = %key,
+                    reason,
+                    "Refresh blocked after repeated invalid attempts"
+                );
+            }
+        }
+    }
+
+    async fn reset(&self, keys: &[String]) {
+        for key in keys {
+            self.failures.invalidate(key).await;
+        }
+    }
+}
+
+/// Configure the guard; until called every check is a no-op
+pub fn init(threshold: u32, window_secs: u64, lockout_secs: u64) {
+    let _ = GUARD.set(RefreshGuard::new(threshold, window_secs, lockout_secs));
+}
+
+/// True if any of the keys is locked out
+pub async fn is_blocked(keys: &[String]) -> bool {
+    GUARD.get().is_some_and(|guard| guard.is_blocked(keys))
+}
+
+/// Count a suspicious refresh failure and lock the key out beyond the threshold
+pub async fn record_failure(keys: &[String], reason: &'static str) {
+    if let Some(guard) = GUARD.get() {
+        guard.record_failure(keys, reason).await;
+    }
+}
+
+/// Forget past failures after a successful refresh
+pub async fn reset(keys: &[String]) {
+    if let Some(guard) = GUARD.get() {
+        guard.reset(keys).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(user_id: u64) -> Vec<String> {
+        vec!["ip:203.0.113.7".to_string(), format!("user:{}", user_id)]
+    }
+
+    #[actix_web::test]
+    async fn repeated_forged_refreshes_block_the_ip_and_the_user() {
+        let guard = RefreshGuard::new(3, 60, 60);
+
+        for _ in 0..2 {
+            guard.record_failure(&keys(1), "token_not_found").await;
+        }
+        assert!(!guard.is_blocked(&keys(1)));
+
+        guard.record_failure(&keys(1), "token_not_found").await;
+        assert!(guard.is_blocked(&keys(1)));
+        // the IP is blocked for every user, the user from every IP
+        assert!(guard.is_blocked(&keys(2)));
+        assert!(guard.is_blocked(&["user:1".to_string()]));
+    }
+
+    #[actix_web::test]
+    async fn a_successful_refresh_resets_the_count() {
+        let guard = RefreshGuard::new(3, 60, 60);
+
+        for _ in 0..2 {
+            guard.record_failure(&keys(1), "token_not_found").await;
+        }
+        guard.reset(&keys(1)).await;
+        guard.record_failure(&keys(1), "token_not_found").await;
+
+        assert!(!guard.is_blocked(&keys(1)));
+    }
+
+    #[actix_web::test]
+    async fn zero_threshold_never_blocks() {
+        let guard = RefreshGuard::new(0, 60, 60);
+
+        for _ in 0..10 {
+            guard.record_failure(&keys(1), "token_reused").await;
+        }
+
+        assert!(!guard.is_blocked(&keys(1)));
+    }
+}
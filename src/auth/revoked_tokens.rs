@@ -0,0 +1,75 @@
+use moka::future::Cache;
+use once_cell::sync::Lazy;
+use sqlx::MySqlPool;
+use std::time::Duration;
+
+/// How often expired entries are deleted; they can't match a valid token anyway
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Whether a `jti` is in `revoked_tokens`, so auth doesn't hit the DB on every request.
+/// Short TTL: a token revoked through another instance stops working within a minute.
+static REVOKED_CACHE: Lazy<Cache<String, bool>> = Lazy::new(|| {
+    Cache::builder()
+        .max_capacity(100_000)
+        .time_to_live(Duration::from_secs(60))
+        .build()
+});
+
+/// Whether the access token with this `jti` was revoked
+pub async fn is_revoked(pool: &MySqlPool, jti: &str) -> Result<bool, sqlx::Error> {
+    if let Some(revoked) = REVOKED_CACHE.get(jti).await {
+        return Ok(revoked);
+    }
+
+    let revoked = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM revoked_tokens WHERE jti = ?")
+        .bind(jti)
+        .fetch_one(pool)
+        .await?
+        > 0;
+
+    REVOKED_CACHE.insert(jti.to_string(), revoked).await;
+    Ok(revoked)
+}
+
+/// Blacklist an access token until its own expiry (idempotent)
+pub async fn revoke(
+    pool: &MySqlPool,
+    jti: &str,
+    user_id: u64,
+    exp: usize,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT IGNORE INTO revoked_tokens (jti, user_id, expires_at)
+        VALUES (?, ?, FROM_UNIXTIME(?))
+        "#,
+    )
+    .bind(jti)
+    .bind(user_id)
+    .bind(exp as i64)
+    .execute(pool)
+    .await?;
+
+    REVOKED_CACHE.insert(jti.to_string(), true).await;
+    Ok(())
+}
+
+/// Start the background task deleting entries whose token has expired
+pub fn start_pruning(pool: MySqlPool) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(PRUNE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match sqlx::query("DELETE FROM revoked_tokens WHERE expires_at < NOW()")
+                .execute(&pool)
+                .await
+            {
+                Ok(r) if r.rows_affected() > 0 => {
+                    tracing::info!(pruned = r.rows_affected(), "Pruned expired revoked tokens")
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!(error = %e, "Failed to prune revoked tokens"),
+            }
+        }
+    });
+}
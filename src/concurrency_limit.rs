@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use actix_web::middleware::Next;
+use actix_web::{
+    Error, HttpResponse,
+    body::{BoxBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+};
+use once_cell::sync::OnceCell;
+use serde_json::json;
+use tokio::sync::Semaphore;
+
+/// Requests allowed in flight at once, sized from `MAX_IN_FLIGHT_REQUESTS`
+static IN_FLIGHT: OnceCell<Arc<Semaphore>> = OnceCell::new();
+
+pub fn init(max_in_flight: usize) {
+    let _ = IN_FLIGHT.set(Arc::new(Semaphore::new(max_in_flight)));
+}
+
+/// Global in-flight cap. Beyond it requests get 503 right away instead of queueing for a
+/// DB connection until they time out. The slot is held until the handler returns.
+pub async fn concurrency_limit_middleware<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let semaphore = IN_FLIGHT.get().expect("concurrency_limit::init not called");
+
+    limited(semaphore, req, next).await
+}
+
+/// Runs the request if `semaphore` has a slot free, else answers 503
+async fn limited<B: MessageBody + 'static>(
+    semaphore: &Semaphore,
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    // a busy instance is still alive; probes must not be turned away with the rest
+    if crate::health::is_probe(req.path()) {
+        return next.call(req).await.map(ServiceResponse::map_into_boxed_body);
+    }
+
+    let Ok(_permit) = semaphore.try_acquire() else {
+        tracing::warn!(path = %req.path(), "Request rejected, concurrency limit reached");
+        let resp = HttpResponse::ServiceUnavailable()
+            .insert_header(("Retry-After", "1"))
+            .json(json!({"error": "too_busy"}));
+        return Ok(req.into_response(resp.map_into_boxed_body()));
+    };
+
+    next.call(req).await.map(ServiceResponse::map_into_boxed_body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::middleware::from_fn;
+    use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+    use actix_web::{App, web};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[actix_web::test]
+    async fn requests_beyond_the_limit_get_503() {
+        let limit = Arc::new(Semaphore::new(2));
+        // handlers wait here until the test lets them finish
+        let gate = Arc::new(Semaphore::new(0));
+        let entered = Arc::new(AtomicUsize::new(0));
+
+        let app = init_service(
+            App::new()
+                .wrap(from_fn({
+                    let limit = limit.clone();
+                    move |req, next| {
+                        let limit = limit.clone();
+                        async move { limited(&limit, req, next).await }
+                    }
+                }))
+                .route("/health", web::get().to(HttpResponse::Ok))
+                .route(
+                    "/slow",
+                    web::get().to({
+                        let (gate, entered) = (gate.clone(), entered.clone());
+                        move || {
+                            let (gate, entered) = (gate.clone(), entered.clone());
+                            async move {
+                                entered.fetch_add(1, Ordering::SeqCst);
+                                gate.acquire().await.unwrap().forget();
+                                HttpResponse::Ok().finish()
+                            }
+                        }
+                    }),
+                ),
+        )
+        .await;
+        let get = |path: &str| TestRequest::get().uri(path).to_request();
+
+        let (first, second, _) = futures::join!(
+            call_service(&app, get("/slow")),
+            call_service(&app, get("/slow")),
+            async {
+                while entered.load(Ordering::SeqCst) < 2 {
+                    actix_web::rt::task::yield_now().await;
+                }
+
+                // both slots are taken: refused at once rather than left waiting
+                let res = call_service(&app, get("/slow")).await;
+                assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+                assert_eq!(res.headers().get("Retry-After").unwrap(), "1");
+                let body: serde_json::Value = read_body_json(res).await;
+                assert_eq!(body, json!({"error": "too_busy"}));
+
+                // probes still get through
+                let res = call_service(&app, get("/health")).await;
+                assert_eq!(res.status(), StatusCode::OK);
+
+                gate.add_permits(2);
+            }
+        );
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(second.status(), StatusCode::OK);
+        assert_eq!(entered.load(Ordering::SeqCst), 2);
+
+        // the finished requests gave their slots back
+        assert_eq!(limit.available_permits(), 2);
+    }
+}
@@ -1,12 +1,36 @@
 use std::env;
+use std::net::IpAddr;
 use dotenvy::dotenv;
+
+use crate::api::{employee, leave_request, payroll};
+use crate::docs;
+use crate::model::leave_request::LeaveStatus;
+use crate::utils::geo::Geofence;
+use crate::utils::sort::SortOrder;
 #[derive(Clone)]
 pub struct Config {
     pub database_url: String,
+    /// Size of the MySQL connection pool
+    pub db_max_connections: u32,
     pub jwt_secret: String,
     pub server_addr: String,
+    /// `HttpServer` worker threads; defaults to the number of physical CPU cores
+    pub http_workers: Option<usize>,
+    /// On SIGTERM/SIGINT, how long in-flight requests may take to finish before workers stop
+    pub shutdown_timeout_secs: u64,
+    /// Requests handled at once across all workers; more get 503. Defaults to the DB pool
+    /// size, since every request may hold a connection.
+    pub max_in_flight_requests: usize,
     pub access_token_ttl: usize,
     pub refresh_token_ttl: usize,
+    /// Lifetime of a password reset token, in seconds
+    pub password_reset_ttl: u64,
+    /// Previous passwords a new one must differ from (0 = no history check)
+    pub password_history_size: u32,
+    /// Argon2id cost for new password hashes
+    pub argon2_memory_kib: u32,
+    pub argon2_iterations: u32,
+    pub argon2_parallelism: u32,
 
     // Rate limiting
     pub rate_login_per_min: u32,
@@ -15,43 +39,499 @@ pub struct Config {
     pub rate_protected_per_min: u32,
 
     pub api_prefix: String, // <-- new
+
+    /// Externally visible base URL, used to build absolute links (e.g. pagination `Link` headers)
+    pub public_base_url: String,
+
+    // Employee code generation
+    pub employee_code_autogen: bool,
+    pub employee_code_prefix: String,
+    pub employee_code_width: usize,
+
+    // Notifications
+    pub leave_notifications_enabled: bool,
+    /// Only delivery channel; without it notifications are refused and password reset answers 503
+    pub notify_webhook_url: Option<String>,
+    /// HMAC-SHA256 key for the `X-Signature` header of webhook deliveries
+    pub notify_webhook_secret: Option<String>,
+
+    /// Query parameters whose values are masked in the access log
+    pub log_redact_params: Vec<String>,
+    /// `EnvFilter` directives: the `LOG_LEVEL` base level (default `info`) followed by
+    /// any `RUST_LOG` directives, so `RUST_LOG=actix_web=debug` doesn't silence the app
+    pub log_level: String,
+    /// Log to stdout (containers) instead of the daily rolling file under `logs/`
+    pub log_to_stdout: bool,
+
+    // Refresh token abuse protection
+    pub refresh_fail_threshold: u32,
+    pub refresh_fail_window_secs: u64,
+    pub refresh_lockout_secs: u64,
+
+    /// Status a newly submitted leave request starts in (`pending` or `approved`)
+    pub leave_default_status: LeaveStatus,
+
+    /// Yearly entitlements reported by `GET /leave/balance`
+    pub annual_leave_days: u32,
+    pub sick_leave_days: u32,
+    /// Month (1-12) the leave year starts in; used to prorate new hires' entitlement
+    pub leave_year_start_month: u32,
+
+    /// Mask employee PII (names, email, phone) in API responses, for non-production copies of real data
+    pub mask_pii: bool,
+
+    /// Leave `null` optional fields out of response bodies instead of emitting them
+    pub omit_null_fields: bool,
+
+    // CORS (defaults to the environment's server URL; no origins = cross-origin requests denied)
+    pub cors_allowed_origins: Vec<String>,
+    pub cors_allow_credentials: bool,
+    pub cors_max_age: usize,
+
+    /// Optional JSON file overriding the default role -> permission mapping
+    pub permissions_file: Option<String>,
+
+    /// Hard cap on rows written by a CSV export
+    pub export_max_rows: u64,
+
+    /// Default order of the employee, leave and payroll lists (`column[:asc|desc]`)
+    pub employee_list_sort: SortOrder,
+    pub leave_list_sort: SortOrder,
+    pub payroll_list_sort: SortOrder,
+
+    /// Reports and exports allowed to run at once; further ones get 503
+    pub report_max_concurrent: usize,
+
+    /// A check-out earlier than the check-in means the shift crossed midnight
+    /// (otherwise such a day has no hours worked)
+    pub attendance_overnight_shifts: bool,
+
+    /// Tax withheld from base + bonus when a payroll is computed; 0 leaves net unchanged
+    pub payroll_tax_percent: f64,
+
+    /// Switch maintenance mode on at startup (mutating requests get 503) for every instance;
+    /// `false` leaves the stored switch as it is
+    pub maintenance_mode: bool,
+
+    /// Reject login / password requests that did not arrive over HTTPS
+    pub require_https: bool,
+    /// Proxies whose `X-Forwarded-Proto` header is trusted; with `trust_proxy`, also the
+    /// only peers whose `X-Forwarded-For` is (any peer when empty)
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Behind a reverse proxy: rate limit by the leftmost `X-Forwarded-For` address
+    /// instead of the peer IP. The proxy must set the header, not append to a client's.
+    pub trust_proxy: bool,
+
+    /// Largest JSON request body; bigger ones get 413
+    pub max_json_bytes: usize,
+
+    // Employee photos
+    pub photo_max_upload_bytes: usize,
+    pub photo_max_dimension: u32,
+
+    /// Company-wide check-in geofence (`lat,lng,radius_m`); a department's own fence takes
+    /// precedence. Only enforced for employees with `geofence_required` set.
+    pub geofence: Option<Geofence>,
+
+    /// Disable accounts whose last login is older than this many days (0 = never)
+    pub inactivity_disable_days: u32,
 }
 
 impl Config {
     pub fn from_env() -> Self {
         dotenv().ok();
+        Self::from_vars(|key| env::var(key))
+    }
+
+    /// Test configuration: the given variables over the defaults
+    #[cfg(test)]
+    pub fn for_test(vars: &[(&str, &str)]) -> Self {
+        let required = [
+            ("SERVER_ADDR", "127.0.0.1:0"),
+            ("DATABASE_URL", "mysql://localhost/hrm_test"),
+            ("JWT_SECRET", "test-secret"),
+        ];
+        Self::from_vars(|key| {
+            vars.iter()
+                .chain(required.iter())
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+                .ok_or(env::VarError::NotPresent)
+        })
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Result<String, env::VarError>) -> Self {
+        let server_addr = var("SERVER_ADDR").expect("SERVER_ADDR must be set");
+
+        let db_max_connections: u32 = var("DB_MAX_CONNECTIONS")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .unwrap();
+
+        let environment = var("ENVIRONMENT").unwrap_or_else(|_| "local".to_string());
+        let environment_url =
+            docs::server_url(&environment).expect("ENVIRONMENT must be one of local, dev, preprod, prod");
+
+        // an empty CORS_ALLOWED_ORIGINS denies every cross-origin request
+        let cors_allowed_origins: Vec<String> = var("CORS_ALLOWED_ORIGINS")
+            .unwrap_or_else(|_| environment_url.to_string())
+            .split(',')
+            .map(|o| o.trim().to_string())
+            .filter(|o| !o.is_empty())
+            .collect();
+        let cors_allow_credentials: bool = var("CORS_ALLOW_CREDENTIALS")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap();
+        // any site could then send requests with the user's cookies
+        assert!(
+            !(cors_allow_credentials && cors_allowed_origins.iter().any(|o| o == "*")),
+            "CORS_ALLOW_CREDENTIALS can't be combined with CORS_ALLOWED_ORIGINS=*"
+        );
 
         Self {
-            server_addr: env::var("SERVER_ADDR").expect("SERVER_ADDR must be set"),
-            database_url: env::var("DATABASE_URL").expect("DATABASE_URL must be set"),
-            jwt_secret: env::var("JWT_SECRET").expect("JWT_SECRET must be set"),
-            access_token_ttl: env::var("ACCESS_TOKEN_TTL")
+            public_base_url: var("PUBLIC_BASE_URL")
+                .unwrap_or_else(|_| format!("http://{}", server_addr)),
+            server_addr,
+            database_url: var("DATABASE_URL").expect("DATABASE_URL must be set"),
+            db_max_connections,
+            http_workers: var("HTTP_WORKERS")
+                .ok()
+                .filter(|w| !w.is_empty())
+                .map(|w| w.parse().expect("HTTP_WORKERS must be a positive number")),
+            shutdown_timeout_secs: var("SHUTDOWN_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap(),
+            max_in_flight_requests: var("MAX_IN_FLIGHT_REQUESTS")
+                .map(|m| m.parse().unwrap())
+                .unwrap_or(db_max_connections as usize),
+            jwt_secret: var("JWT_SECRET").expect("JWT_SECRET must be set"),
+            access_token_ttl: var("ACCESS_TOKEN_TTL")
                 .unwrap_or_else(|_| "900".to_string()) // default 15 min
                 .parse()
                 .unwrap(),
-            refresh_token_ttl: env::var("REFRESH_TOKEN_TTL")
+            refresh_token_ttl: var("REFRESH_TOKEN_TTL")
                 .unwrap_or_else(|_| "604800".to_string()) // default 7 days
                 .parse()
                 .unwrap(),
+            password_reset_ttl: var("PASSWORD_RESET_TTL")
+                .unwrap_or_else(|_| "1800".to_string()) // default 30 min
+                .parse()
+                .unwrap(),
+            password_history_size: var("PASSWORD_HISTORY_SIZE")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap(),
+            // defaults match the argon2 crate, i.e. what existing hashes were made with
+            argon2_memory_kib: var("ARGON2_MEMORY_KIB")
+                .unwrap_or_else(|_| "19456".to_string())
+                .parse()
+                .unwrap(),
+            argon2_iterations: var("ARGON2_ITERATIONS")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .unwrap(),
+            argon2_parallelism: var("ARGON2_PARALLELISM")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .unwrap(),
 
-            rate_login_per_min: env::var("RATE_LOGIN_PER_MIN")
+            rate_login_per_min: var("RATE_LOGIN_PER_MIN")
                 .unwrap_or_else(|_| "60".to_string())
                 .parse()
                 .unwrap(),
-            rate_register_per_min: env::var("RATE_REGISTER_PER_MIN")
+            rate_register_per_min: var("RATE_REGISTER_PER_MIN")
                 .unwrap_or_else(|_| "30".to_string())
                 .parse()
                 .unwrap(),
-            rate_refresh_per_min: env::var("RATE_REFRESH_PER_MIN")
+            rate_refresh_per_min: var("RATE_REFRESH_PER_MIN")
                 .unwrap_or_else(|_| "30".to_string())
                 .parse()
                 .unwrap(),
-            rate_protected_per_min: env::var("RATE_PROTECTED_PER_MIN")
+            rate_protected_per_min: var("RATE_PROTECTED_PER_MIN")
                 .unwrap_or_else(|_| "1000".to_string())
                 .parse()
                 .unwrap(),
 
-            api_prefix: env::var("API_PREFIX").unwrap_or_else(|_| "/api".to_string()),
+            // canonical paths are lowercase, so the prefix must be too
+            api_prefix: var("API_PREFIX")
+                .unwrap_or_else(|_| "/api".to_string())
+                .to_lowercase(),
+
+            employee_code_autogen: var("EMPLOYEE_CODE_AUTOGEN")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap(),
+            employee_code_prefix: var("EMPLOYEE_CODE_PREFIX")
+                .unwrap_or_else(|_| "EMP-".to_string()),
+            employee_code_width: var("EMPLOYEE_CODE_WIDTH")
+                .unwrap_or_else(|_| "4".to_string())
+                .parse()
+                .unwrap(),
+
+            leave_notifications_enabled: var("LEAVE_NOTIFICATIONS_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap(),
+            notify_webhook_url: var("NOTIFY_WEBHOOK_URL").ok().filter(|u| !u.is_empty()),
+            notify_webhook_secret: var("NOTIFY_WEBHOOK_SECRET").ok().filter(|s| !s.is_empty()),
+
+            log_redact_params: var("LOG_REDACT_PARAMS")
+                .unwrap_or_else(|_| {
+                    "password,token,access_token,refresh_token,reset_token,secret".to_string()
+                })
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect(),
+            log_level: log_filter(var("LOG_LEVEL").ok(), var("RUST_LOG").ok()),
+            log_to_stdout: var("LOG_TO_STDOUT")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap(),
+
+            refresh_fail_threshold: var("REFRESH_FAIL_THRESHOLD")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap(),
+            refresh_fail_window_secs: var("REFRESH_FAIL_WINDOW_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .unwrap(),
+            refresh_lockout_secs: var("REFRESH_LOCKOUT_SECS")
+                .unwrap_or_else(|_| "900".to_string())
+                .parse()
+                .unwrap(),
+
+            leave_default_status: LeaveStatus::from_name(
+                &var("LEAVE_DEFAULT_STATUS").unwrap_or_else(|_| "pending".to_string()),
+            )
+            .filter(LeaveStatus::is_initial)
+            .expect("LEAVE_DEFAULT_STATUS must be pending or approved"),
+
+            annual_leave_days: var("ANNUAL_LEAVE_DAYS")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .unwrap(),
+            sick_leave_days: var("SICK_LEAVE_DAYS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap(),
+            leave_year_start_month: var("LEAVE_YEAR_START_MONTH")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .ok()
+                .filter(|m| (1..=12).contains(m))
+                .expect("LEAVE_YEAR_START_MONTH must be 1-12"),
+
+
+            mask_pii: var("MASK_PII")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap(),
+
+            omit_null_fields: var("OMIT_NULL_FIELDS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap(),
+
+            cors_allowed_origins,
+            cors_allow_credentials,
+            cors_max_age: var("CORS_MAX_AGE")
+                .unwrap_or_else(|_| "3600".to_string()) // 1 hour preflight cache
+                .parse()
+                .unwrap(),
+
+            permissions_file: var("PERMISSIONS_FILE").ok().filter(|p| !p.is_empty()),
+
+            export_max_rows: var("EXPORT_MAX_ROWS")
+                .unwrap_or_else(|_| "100000".to_string())
+                .parse()
+                .unwrap(),
+
+            employee_list_sort: SortOrder::parse(
+                &var("EMPLOYEE_LIST_SORT").unwrap_or_else(|_| "id:desc".to_string()),
+                employee::SORT_COLUMNS,
+            )
+            .expect("EMPLOYEE_LIST_SORT must be one of id, employee_code, first_name, last_name, hire_date, status with optional :asc/:desc"),
+            leave_list_sort: SortOrder::parse(
+                &var("LEAVE_LIST_SORT").unwrap_or_else(|_| "created_at:desc".to_string()),
+                leave_request::SORT_COLUMNS,
+            )
+            .expect("LEAVE_LIST_SORT must be one of id, created_at, start_date with optional :asc/:desc"),
+            payroll_list_sort: SortOrder::parse(
+                &var("PAYROLL_LIST_SORT").unwrap_or_else(|_| "month:desc".to_string()),
+                payroll::SORT_COLUMNS,
+            )
+            .expect("PAYROLL_LIST_SORT must be one of id, month, net_salary with optional :asc/:desc"),
+
+            report_max_concurrent: var("REPORT_MAX_CONCURRENT")
+                .unwrap_or_else(|_| "4".to_string())
+                .parse()
+                .unwrap(),
+
+            attendance_overnight_shifts: var("ATTENDANCE_OVERNIGHT_SHIFTS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap(),
+
+            payroll_tax_percent: var("PAYROLL_TAX_PERCENT")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .ok()
+                .filter(|p: &f64| (0.0..=100.0).contains(p))
+                .expect("PAYROLL_TAX_PERCENT must be 0-100"),
+
+            maintenance_mode: var("MAINTENANCE_MODE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap(),
+
+            require_https: var("REQUIRE_HTTPS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap(),
+            trust_proxy: var("TRUST_PROXY")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap(),
+            trusted_proxies: var("TRUSTED_PROXIES")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(|p| p.parse().expect("TRUSTED_PROXIES must be a list of IP addresses"))
+                .collect(),
+
+            max_json_bytes: var("MAX_JSON_BYTES")
+                .unwrap_or_else(|_| "2097152".to_string()) // 2 MiB, the actix default
+                .parse()
+                .unwrap(),
+
+            photo_max_upload_bytes: var("PHOTO_MAX_UPLOAD_BYTES")
+                .unwrap_or_else(|_| "10485760".to_string()) // 10 MiB
+                .parse()
+                .unwrap(),
+            photo_max_dimension: var("PHOTO_MAX_DIMENSION")
+                .unwrap_or_else(|_| "512".to_string())
+                .parse()
+                .unwrap(),
+
+            geofence: var("GEOFENCE").ok().filter(|g| !g.is_empty()).map(|g| {
+                Geofence::parse(&g).expect("GEOFENCE must be `lat,lng,radius_m`")
+            }),
+
+            inactivity_disable_days: var("INACTIVITY_DISABLE_DAYS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .unwrap(),
+        }
+    }
+}
+
+/// Base level first, then the `RUST_LOG` directives; a later bare level replaces the base
+fn log_filter(log_level: Option<String>, rust_log: Option<String>) -> String {
+    let base = log_level
+        .filter(|l| !l.trim().is_empty())
+        .unwrap_or_else(|| "info".to_string());
+
+    match rust_log.filter(|d| !d.trim().is_empty()) {
+        Some(directives) => format!("{},{}", base, directives),
+        None => base,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::EnvFilter;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
         }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Log lines that pass `filter`, one app line and one actix_web debug line
+    fn logged(filter: &str) -> String {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_env_filter(EnvFilter::try_new(filter).unwrap())
+            .with_writer(move || writer.clone())
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: "hrm", "app line");
+            tracing::debug!(target: "actix_web", "framework line");
+        });
+
+        String::from_utf8(captured.0.lock().unwrap().clone()).unwrap()
+    }
+
+    #[test]
+    fn rust_log_adds_to_the_base_level() {
+        let filter = log_filter(None, Some("actix_web=debug".to_string()));
+        assert_eq!(filter, "info,actix_web=debug");
+
+        let lines = logged(&filter);
+        assert!(lines.contains("app line"));
+        assert!(lines.contains("framework line"));
+    }
+
+    #[test]
+    fn log_level_sets_the_base() {
+        let filter = log_filter(Some("warn".to_string()), None);
+        assert_eq!(filter, "warn");
+        assert!(!logged(&filter).contains("app line"));
+    }
+
+    #[test]
+    fn bare_rust_log_level_replaces_the_base() {
+        let filter = log_filter(Some("warn".to_string()), Some("debug".to_string()));
+
+        let lines = logged(&filter);
+        assert!(lines.contains("app line"));
+        assert!(lines.contains("framework line"));
+    }
+
+    #[test]
+    fn cors_defaults_to_the_environment_server() {
+        for (environment, url, _) in docs::SERVERS {
+            let config = Config::for_test(&[("ENVIRONMENT", environment)]);
+            assert_eq!(config.cors_allowed_origins, [*url], "{}", environment);
+        }
+    }
+
+    #[test]
+    fn cors_origins_override_the_environment() {
+        let config = Config::for_test(&[
+            ("ENVIRONMENT", "prod"),
+            (
+                "CORS_ALLOWED_ORIGINS",
+                "https://a.example.com, https://b.example.com",
+            ),
+        ]);
+
+        assert_eq!(
+            config.cors_allowed_origins,
+            ["https://a.example.com", "https://b.example.com"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ENVIRONMENT must be one of")]
+    fn unknown_environment_is_refused() {
+        Config::for_test(&[("ENVIRONMENT", "staging")]);
     }
 }
@@ -0,0 +1,174 @@
+use actix_cors::Cors;
+use actix_web::http::{Method, header};
+
+use crate::config::Config;
+use crate::request_id::REQUEST_ID_HEADER;
+
+/// CORS policy for the whole app.
+/// Preflight (`OPTIONS`) requests are answered here, before routing and authentication,
+/// and cached by browsers for `CORS_MAX_AGE` seconds.
+pub fn build(config: &Config) -> Cors {
+    let mut cors = Cors::default()
+        .allowed_methods(vec![
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allowed_headers(vec![
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            header::ACCEPT,
+        ])
+        // pagination links and the correlation id must be readable by browser clients
+        .expose_headers(vec![header::LINK, header::HeaderName::from_static(REQUEST_ID_HEADER)])
+        .max_age(config.cors_max_age);
+
+    for origin in &config.cors_allowed_origins {
+        cors = if origin == "*" {
+            cors.allow_any_origin()
+        } else {
+            cors.allowed_origin(origin)
+        };
+    }
+
+    if config.cors_allow_credentials {
+        cors = cors.supports_credentials();
+    }
+
+    cors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{TestRequest, call_service, init_service};
+    use actix_web::{App, HttpResponse, web};
+
+    fn config() -> Config {
+        Config::for_test(&[
+            ("CORS_ALLOWED_ORIGINS", "https://app.example.com"),
+            ("CORS_MAX_AGE", "600"),
+        ])
+    }
+
+    fn preflight(origin: &str) -> TestRequest {
+        TestRequest::default()
+            .method(Method::OPTIONS)
+            .uri("/employee/1")
+            .insert_header((header::ORIGIN, origin))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, "PUT"))
+            .insert_header((
+                header::ACCESS_CONTROL_REQUEST_HEADERS,
+                "authorization, content-type",
+            ))
+    }
+
+    macro_rules! app {
+        ($config:expr) => {
+            init_service(
+                App::new()
+                    .wrap(build(&$config))
+                    .route("/employee/{id}", web::put().to(HttpResponse::Ok))
+                    .route(
+                        "/employee",
+                        web::get().to(|| async {
+                            HttpResponse::Ok()
+                                .insert_header((header::LINK, "</employee?page=2>; rel=\"next\""))
+                                .finish()
+                        }),
+                    ),
+            )
+        };
+    }
+
+    #[actix_web::test]
+    async fn preflight_from_an_allowed_origin_is_answered() {
+        let app = app!(config()).await;
+
+        let res = call_service(&app, preflight("https://app.example.com").to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let headers = res.headers();
+        assert_eq!(
+            headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://app.example.com"
+        );
+        let methods = headers
+            .get(header::ACCESS_CONTROL_ALLOW_METHODS)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(methods.contains("PUT"), "{}", methods);
+        let allowed = headers
+            .get(header::ACCESS_CONTROL_ALLOW_HEADERS)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_ascii_lowercase();
+        assert!(allowed.contains("authorization") && allowed.contains("content-type"));
+        assert_eq!(headers.get(header::ACCESS_CONTROL_MAX_AGE).unwrap(), "600");
+    }
+
+    #[actix_web::test]
+    async fn preflight_from_another_origin_is_refused() {
+        let app = app!(config()).await;
+
+        let res = call_service(&app, preflight("https://evil.example.org").to_request()).await;
+
+        assert!(res.status().is_client_error());
+        assert!(
+            res.headers()
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .is_none()
+        );
+    }
+
+    #[actix_web::test]
+    async fn link_header_is_exposed() {
+        let app = app!(config()).await;
+
+        let res = call_service(
+            &app,
+            TestRequest::get()
+                .uri("/employee")
+                .insert_header((header::ORIGIN, "https://app.example.com"))
+                .to_request(),
+        )
+        .await;
+
+        let exposed = res
+            .headers()
+            .get(header::ACCESS_CONTROL_EXPOSE_HEADERS)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_ascii_lowercase();
+        assert!(
+            exposed.contains("link") && exposed.contains(REQUEST_ID_HEADER),
+            "{}",
+            exposed
+        );
+    }
+
+    #[actix_web::test]
+    async fn environment_server_is_the_default_origin() {
+        let prod = crate::docs::server_url("prod").unwrap();
+        let dev = crate::docs::server_url("dev").unwrap();
+        let app = app!(Config::for_test(&[("ENVIRONMENT", "prod")])).await;
+
+        let res = call_service(&app, preflight(prod).to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers()
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .unwrap(),
+            prod
+        );
+
+        let res = call_service(&app, preflight(dev).to_request()).await;
+        assert!(res.status().is_client_error());
+    }
+}
@@ -1,15 +1,42 @@
+use crate::api::admin::{Anomaly, MaintenanceState, ReconcileFixResponse, ReconcileReport};
+use crate::api::attendance::{
+    AttendanceListResponse, AttendanceQuery, AttendanceRecord, AttendanceSummary,
+    AttendanceSummaryQuery, CheckInRequest, CheckOutRequest, CorrectAttendance, PresentEmployee,
+    PresentResponse,
+};
+use crate::api::audit::{AuditEvent, AuditListResponse, AuditQuery};
+use crate::api::department::{BatchCreateResponse, CreateDepartment};
 use crate::api::employee::{
-    CreateEmployee, EmployeeListResponse, EmployeeResponse, UpdateEmployee,
+    CreateEmployee, EmployeeFilterOptions, EmployeeListResponse, EmployeeResponse,
+    UpdateEmployee, UpdateEmployeeStatus,
+};
+use crate::api::holiday::{CreateHoliday, HolidayQuery, HolidayResponse};
+use crate::api::job_title::CreateJobTitle;
+use crate::api::leave_balance::{
+    BulkAdjustBalance, BulkAdjustResponse, EntitlementQuery, EntitlementResponse,
+    LeaveBalanceQuery, LeaveBalanceResponse,
 };
 use crate::api::leave_request::LeaveFilter;
 use crate::api::leave_request::LeaveListResponse;
 use crate::api::leave_request::LeaveResponse;
+use crate::api::leave_request::LeaveDecision;
+use crate::api::leave_request::{BulkDecisionResponse, BulkLeaveDecision};
+use crate::api::leave_request::{LeaveStats, LeaveStatsGroup, LeaveStatsQuery};
 use crate::api::payroll::{
-    CreatePayroll, PaginatedPayrollResponse, PayrollQuery, PayrollResponse, UpdatePayroll,
+    CreatePayroll, GeneratePayroll, GeneratePayrollResponse, PaginatedPayrollResponse,
+    PaginatedPayslipResponse, PayrollByMonthQuery, PayrollQuery, PayrollResponse, PayrollTotals,
+    PayslipQuery, PayslipResponse, UpdatePayroll,
 };
-use crate::model::employee::Employee;
-use utoipa::Modify;
-use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use crate::api::timeline::{TimelineEvent, TimelineEventKind, TimelineQuery, TimelineResponse};
+use crate::api::user::{MeEmployee, MeResponse, SetUserActive};
+use crate::model::department::Department;
+use crate::model::employee::{Employee, EmployeeNames};
+use crate::model::job_title::JobTitle;
+use crate::utils::api_error::ApiError;
+use crate::utils::pagination::CountResponse;
+use actix_web::{HttpRequest, HttpResponse};
+use once_cell::sync::Lazy;
+use utoipa::openapi::server::ServerBuilder;
 use utoipa::{OpenApi, openapi};
 #[derive(OpenApi)]
 #[openapi(
@@ -51,39 +78,151 @@ Built with **Rust**, **Actix Web**, **SQLx**, and **Utoipa**.
     ),
     paths(
         crate::api::leave_request::leave_list,
+        crate::api::leave_request::count_leaves,
         crate::api::leave_request::get_leave,
         crate::api::leave_request::create_leave,
         crate::api::leave_request::approve_leave,
         crate::api::leave_request::reject_leave,
+        crate::api::leave_request::bulk_approve_leaves,
+        crate::api::leave_request::bulk_reject_leaves,
+        crate::api::leave_request::cancel_leave,
+        crate::api::leave_request::export_leaves,
+        crate::api::leave_request::leave_stats,
+        crate::api::leave_balance::leave_balance,
+        crate::api::leave_balance::bulk_adjust_balances,
+        crate::api::leave_balance::employee_entitlement,
 
         crate::api::attendance::check_in,
         crate::api::attendance::check_out,
+        crate::api::attendance::present,
+        crate::api::attendance::list_attendance,
+        crate::api::attendance::attendance_summary,
+        crate::api::attendance::correct_attendance,
 
         crate::api::employee::create_employee,
         crate::api::employee::get_employee,
         crate::api::employee::list_employees,
         crate::api::employee::update_employee,
+        crate::api::employee::restore_employee,
+        crate::api::employee::update_employee_status,
+        crate::api::employee::employee_filter_options,
+        crate::api::employee::export_employees,
+        crate::api::employee::count_employees,
+        crate::api::employee::upload_employee_photo,
+        crate::api::timeline::employee_timeline,
 
         crate::api::payroll::create_payroll,
         crate::api::payroll::update_payroll,
         crate::api::payroll::get_payroll,
-        crate::api::payroll::list_payrolls
+        crate::api::payroll::list_payrolls,
+        crate::api::payroll::count_payrolls,
+        crate::api::payroll::approve_payroll,
+        crate::api::payroll::pay_payroll,
+        crate::api::payroll::my_payslips,
+        crate::api::payroll::payroll_by_month,
+        crate::api::payroll::download_payslip,
+        crate::api::payroll::generate_payrolls,
+
+        crate::api::department::create_department,
+        crate::api::department::list_departments,
+        crate::api::department::get_department,
+        crate::api::department::update_department,
+        crate::api::department::delete_department,
+        crate::api::department::batch_create_departments,
+
+        crate::api::job_title::create_job_title,
+        crate::api::job_title::list_job_titles,
+        crate::api::job_title::get_job_title,
+        crate::api::job_title::update_job_title,
+        crate::api::job_title::delete_job_title,
+        crate::api::job_title::batch_create_job_titles,
+
+        crate::api::holiday::create_holiday,
+        crate::api::holiday::list_holidays,
+
+        crate::api::user::me,
+        crate::api::user::reset_user_password,
+        crate::api::user::set_user_active,
+        crate::api::user::delete_user,
+
+        crate::api::admin::get_maintenance,
+        crate::api::admin::set_maintenance,
+        crate::api::admin::reconcile,
+        crate::api::admin::reconcile_fix,
+        crate::api::audit::search_audit,
+        crate::api::audit::export_audit
     ),
     components(
         schemas(
             LeaveFilter,
             LeaveResponse,
+            LeaveDecision,
+            BulkLeaveDecision,
+            BulkDecisionResponse,
             LeaveListResponse,
+            LeaveStatsQuery,
+            LeaveStats,
+            LeaveStatsGroup,
+            BulkAdjustBalance,
+            BulkAdjustResponse,
+            LeaveBalanceQuery,
+            LeaveBalanceResponse,
+            EntitlementQuery,
+            EntitlementResponse,
             CreateEmployee,
             UpdateEmployee,
+            UpdateEmployeeStatus,
             EmployeeResponse,
             Employee,
+            EmployeeNames,
             EmployeeListResponse,
+            EmployeeFilterOptions,
+            TimelineQuery,
+            TimelineEvent,
+            TimelineEventKind,
+            TimelineResponse,
             PaginatedPayrollResponse,
+            PayrollTotals,
+            GeneratePayroll,
+            GeneratePayrollResponse,
             PayrollResponse,
             CreatePayroll,
             UpdatePayroll,
-            PayrollQuery
+            PayrollQuery,
+            PayslipQuery,
+            PayrollByMonthQuery,
+            PayslipResponse,
+            PaginatedPayslipResponse,
+            CreateDepartment,
+            Department,
+            CreateJobTitle,
+            JobTitle,
+            BatchCreateResponse,
+            CreateHoliday,
+            HolidayQuery,
+            HolidayResponse,
+            CheckInRequest,
+            CheckOutRequest,
+            AttendanceQuery,
+            AttendanceRecord,
+            AttendanceListResponse,
+            AttendanceSummaryQuery,
+            AttendanceSummary,
+            CorrectAttendance,
+            PresentEmployee,
+            PresentResponse,
+            MaintenanceState,
+            Anomaly,
+            ReconcileReport,
+            ReconcileFixResponse,
+            MeResponse,
+            MeEmployee,
+            SetUserActive,
+            AuditQuery,
+            AuditEvent,
+            AuditListResponse,
+            CountResponse,
+            ApiError
         )
     ),
     tags(
@@ -91,6 +230,130 @@ Built with **Rust**, **Actix Web**, **SQLx**, and **Utoipa**.
         (name = "Attendance", description = "Attendance management APIs"),
         (name = "Employee", description = "Employee management APIs"),
         (name = "Payroll", description = "Payroll management APIs"),
+        (name = "Department", description = "Department management APIs"),
+        (name = "Job Title", description = "Job title management APIs"),
+        (name = "Holiday", description = "Company holiday calendar APIs"),
+        (name = "User", description = "User account administration APIs"),
+        (name = "Admin", description = "Operational controls such as maintenance mode"),
     )
 )]
 pub struct ApiDoc;
+
+/// Deployment environments (`ENVIRONMENT`) and their public server URL.
+/// Single source for the OpenAPI `servers` list and the default CORS origin.
+pub const SERVERS: &[(&str, &str, &str)] = &[
+    ("local", "http://localhost:3000", "Local development"),
+    ("dev", "https://dev.hrm.co.uk", "Development"),
+    ("preprod", "https://preprod.hrm.co.uk", "Pre-production"),
+    ("prod", "https://hrm.co.uk", "Production"),
+];
+
+/// Server URL of the given environment
+pub fn server_url(environment: &str) -> Option<&'static str> {
+    SERVERS
+        .iter()
+        .find(|(env, _, _)| *env == environment)
+        .map(|(_, url, _)| *url)
+}
+
+/// The OpenAPI document with `servers` filled in from `SERVERS`
+pub fn openapi() -> openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+
+    doc.servers = Some(
+        SERVERS
+            .iter()
+            .map(|(_, url, description)| {
+                ServerBuilder::new()
+                    .url(*url)
+                    .description(Some(*description))
+                    .build()
+            })
+            .collect(),
+    );
+
+    doc
+}
+
+static SPEC: Lazy<openapi::OpenApi> = Lazy::new(openapi);
+
+/// `GET /api-doc/openapi.json`: the spec with the host the request came in on as the
+/// first server, so Swagger "Try it out" works behind any hostname. The static
+/// `SERVERS` entries follow as alternatives.
+pub async fn serve_openapi(req: HttpRequest) -> HttpResponse {
+    let mut doc = SPEC.clone();
+
+    let info = req.connection_info();
+    let live_url = format!("{}://{}", info.scheme(), info.host());
+
+    let mut servers = vec![
+        ServerBuilder::new()
+            .url(live_url.clone())
+            .description(Some("Current host"))
+            .build(),
+    ];
+    servers.extend(
+        doc.servers
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| s.url != live_url),
+    );
+    doc.servers = Some(servers);
+
+    HttpResponse::Ok().json(doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+    use actix_web::{App, web};
+
+    /// Server URLs of the spec served for a request with this `Host`
+    async fn served_servers(host: &str) -> Vec<String> {
+        let app =
+            init_service(App::new().route("/api-doc/openapi.json", web::get().to(serve_openapi)))
+                .await;
+        let res = call_service(
+            &app,
+            TestRequest::get()
+                .uri("/api-doc/openapi.json")
+                .insert_header(("Host", host))
+                .to_request(),
+        )
+        .await;
+        let spec: serde_json::Value = read_body_json(res).await;
+
+        spec["servers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["url"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[actix_web::test]
+    async fn request_host_is_the_first_server() {
+        let servers = served_servers("hr.internal.example:8080").await;
+
+        assert_eq!(servers[0], "http://hr.internal.example:8080");
+        let fallbacks: Vec<&str> = SERVERS.iter().map(|(_, url, _)| *url).collect();
+        assert_eq!(servers[1..], fallbacks);
+    }
+
+    #[actix_web::test]
+    async fn known_host_is_not_listed_twice() {
+        let servers = served_servers("localhost:3000").await;
+
+        assert_eq!(servers[0], "http://localhost:3000");
+        assert_eq!(
+            servers
+                .iter()
+                .filter(|url| *url == "http://localhost:3000")
+                .count(),
+            1
+        );
+        assert_eq!(servers.len(), SERVERS.len());
+    }
+}
@@ -0,0 +1,24 @@
+use actix_web::{HttpResponse, web};
+use serde_json::json;
+use sqlx::MySqlPool;
+
+/// Probe endpoints: outside the API scope, unauthenticated and not rate limited
+pub fn is_probe(path: &str) -> bool {
+    path == "/health" || path == "/ready"
+}
+
+/// Liveness: the process is up and serving requests
+pub async fn health() -> HttpResponse {
+    HttpResponse::Ok().json(json!({"status": "ok"}))
+}
+
+/// Readiness: the database answers, so requests can actually be served
+pub async fn ready(pool: web::Data<MySqlPool>) -> HttpResponse {
+    match sqlx::query("SELECT 1").execute(pool.get_ref()).await {
+        Ok(_) => HttpResponse::Ok().json(json!({"status": "ok", "db": "up"})),
+        Err(e) => {
+            tracing::warn!(error = %e, "Readiness check failed");
+            HttpResponse::ServiceUnavailable().json(json!({"status": "unavailable", "db": "down"}))
+        }
+    }
+}
@@ -1,48 +1,93 @@
-use actix_web::middleware::NormalizePath;
-use actix_web::web::Data;
+use actix_web::http::StatusCode;
+use actix_web::middleware::{ErrorHandlers, Logger, NormalizePath, from_fn};
+use actix_web::web::{self, Data};
 use actix_web::{App, HttpServer, Responder, get};
 use dotenvy::dotenv;
 
 mod api;
 mod auth;
+mod concurrency_limit;
 mod config;
+mod cors;
 mod db;
+mod health;
+mod maintenance;
+mod require_https;
 mod model;
 mod models;
+mod path_policy;
+mod rate_limit;
+mod request_id;
 mod routes;
 mod utils;
 mod docs;
+#[cfg(test)]
+mod test_support;
 
 use config::Config;
 use db::init_db;
 
+use crate::utils::json_body;
+use crate::utils::log_redact;
+use crate::utils::notifier::{Notifier, WebhookNotifier};
+use crate::utils::{omit_null, pii, report_limit};
 use crate::utils::username_cache;
 use crate::utils::username_filter;
-use tracing::{info, warn};
+use tracing::{error, info, warn};
+use std::sync::Arc;
 use tracing_appender::rolling;
-// use tracing_subscriber::fmt::writer::BoxMakeWriter;
-use utoipa_swagger_ui::SwaggerUi;
-use crate::docs::ApiDoc;
-use utoipa::OpenApi; // ← needed for ApiDoc::openapi()
+use tracing_subscriber::EnvFilter;
+use utoipa_swagger_ui::{Config as SwaggerConfig, SwaggerUi};
+
+/// Same as the Logger default format, but the request line (`%r`) is replaced by a
+/// redacted one so secrets passed in query strings never reach the log file.
+/// Ends with the `X-Request-Id` the response carries.
+const ACCESS_LOG_FORMAT: &str =
+    r#"%a "%{request_line}xi" %s %b "%{Referer}i" "%{User-Agent}i" %T %{x-request-id}o"#;
 
 #[get("/")]
 async fn index() -> impl Responder {
     "Hello World!"
 }
 
+/// Resolves on Ctrl+C, or SIGTERM as sent by orchestrators on rolling deploys
+async fn shutdown_signal() {
+    let ctrl_c = Box::pin(actix_web::rt::signal::ctrl_c());
+
+    #[cfg(unix)]
+    {
+        use actix_web::rt::signal::unix::{SignalKind, signal};
+
+        let mut term = signal(SignalKind::terminate()).expect("failed to install SIGTERM handler");
+        let term = Box::pin(term.recv());
+        futures::future::select(ctrl_c, term).await;
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = ctrl_c.await;
+    }
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
 
     let config = Config::from_env();
 
-    // Rolling daily log
-    let file_appender = rolling::daily("logs", "app.log");
-    let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
+    // Rolling daily log, or stdout where the platform collects it
+    let (non_blocking, _guard) = if config.log_to_stdout {
+        tracing_appender::non_blocking(std::io::stdout())
+    } else {
+        tracing_appender::non_blocking(rolling::daily("logs", "app.log"))
+    };
+
+    let log_filter = EnvFilter::try_new(&config.log_level)
+        .expect("LOG_LEVEL / RUST_LOG must be valid filters, e.g. info or warn,hrm=debug");
 
     tracing_subscriber::fmt()
         .with_writer(non_blocking)
-        .with_max_level(tracing::Level::DEBUG)
+        .with_env_filter(log_filter)
         .with_ansi(false)
         .with_target(false) // removes module path
         .with_level(true)
@@ -53,13 +98,16 @@ async fn main() -> std::io::Result<()> {
 
     info!("Server starting...");
 
-    let pool = init_db(&config.database_url).await;
+    let pool = init_db(&config.database_url, config.db_max_connections).await;
 
     let pool_for_filter_warmup = pool.clone();
     let pool_for_cache_warmup = pool.clone();
     // 👇 clone what you need BEFORE moving config
     // Clone values for the closure (avoid move issues)
     let server_addr = config.server_addr.clone();
+    let http_workers = config.http_workers;
+    let shutdown_timeout = config.shutdown_timeout_secs;
+    let pool_for_shutdown = pool.clone();
     let config_data = config.clone();
 
     actix_web::rt::spawn(async move {
@@ -77,22 +125,117 @@ async fn main() -> std::io::Result<()> {
         }
     });
 
-    HttpServer::new(move || {
+    let notifier: Data<dyn Notifier> = Data::from(Arc::new(WebhookNotifier::start(
+        config.notify_webhook_url.clone(),
+        config.notify_webhook_secret.clone(),
+    )) as Arc<dyn Notifier>);
+    auth::inactivity::start(pool.clone(), config.inactivity_disable_days);
+    auth::revoked_tokens::start_pruning(pool.clone());
+
+    pii::set_masking(config.mask_pii);
+    if config.mask_pii {
+        info!("PII masking enabled for API responses");
+    }
+
+    omit_null::set_enabled(config.omit_null_fields);
+
+    // the switch lives in the database so every instance follows it
+    if config.maintenance_mode {
+        match maintenance::store(&pool, true, None).await {
+            Ok(()) => warn!("Maintenance mode switched on, mutating requests will be rejected"),
+            Err(e) => error!(error = %e, "Failed to switch on maintenance mode"),
+        }
+    }
+    maintenance::start_sync(pool.clone());
+
+    path_policy::init(routes::known_paths(&config.api_prefix));
+
+    auth::permissions::init(config.permissions_file.as_deref());
+    auth::password::init(
+        config.argon2_memory_kib,
+        config.argon2_iterations,
+        config.argon2_parallelism,
+    );
+    report_limit::init(config.report_max_concurrent);
+    concurrency_limit::init(config.max_in_flight_requests);
+
+    auth::refresh_guard::init(
+        config.refresh_fail_threshold,
+        config.refresh_fail_window_secs,
+        config.refresh_lockout_secs,
+    );
+
+    let server = HttpServer::new(move || {
+        let redact_params = config.log_redact_params.clone();
+
         App::new()
-            .wrap(actix_web::middleware::Logger::default())
+            // unmatched paths are JSON via default_service; wrong methods are fixed up here
+            .wrap(
+                ErrorHandlers::new()
+                    .handler(StatusCode::METHOD_NOT_ALLOWED, path_policy::method_not_allowed),
+            )
+            .wrap(from_fn(concurrency_limit::concurrency_limit_middleware))
+            .wrap(from_fn(maintenance::maintenance_middleware))
+            .wrap(from_fn(require_https::require_https_middleware))
+            // wrong-case paths are redirected, logged and with CORS headers like any response
+            .wrap(from_fn(path_policy::canonical_path_middleware))
+            .wrap(cors::build(&config))
+            // outside everything that may log or fail, inside the access log
+            .wrap(from_fn(request_id::request_id_middleware))
+            .wrap(
+                Logger::new(ACCESS_LOG_FORMAT).custom_request_replace("request_line", move |req| {
+                    log_redact::request_line(req, &redact_params)
+                }),
+            )
+            // outermost, so the access log and routes never see a trailing slash
             .wrap(NormalizePath::trim())
             .service(
                 // SwaggerUi::new("/swagger-ui/{_:.*}")
                 SwaggerUi::new("/swagger-ui/{_:.*}") // ← important: wildcard {_:.*} to match JS/CSS files
-                    .url("/api-doc/openapi.json", ApiDoc::openapi()),
+                    .config(SwaggerConfig::from("/api-doc/openapi.json")),
             )
+            // spec served by our own handler so `servers` can include the request's host
+            .route("/api-doc/openapi.json", web::get().to(docs::serve_openapi))
             .app_data(Data::new(pool.clone()))
             .app_data(Data::new(config.clone()))
+            .app_data(notifier.clone())
+            .app_data(json_body::config(config.max_json_bytes))
             .service(index)
+            // probes, outside the rate-limited scopes
+            .route("/health", web::get().to(health::health))
+            .route("/ready", web::get().to(health::ready))
             // Configure auth + protected routes with rate limiting
             .configure(|cfg| routes::configure(cfg, config_data.clone()))
-    })
-    .bind(server_addr)?
-    .run()
-    .await
+            .default_service(web::to(path_policy::not_found))
+    });
+
+    let server = match http_workers {
+        Some(workers) => server.workers(workers),
+        None => server,
+    };
+
+    // signals are handled here instead of by actix, so the drain shows up in our logs
+    let server = server
+        .shutdown_timeout(shutdown_timeout)
+        .disable_signals()
+        .bind(server_addr)?
+        .run();
+
+    let handle = server.handle();
+    actix_web::rt::spawn(async move {
+        shutdown_signal().await;
+        info!(
+            timeout_secs = shutdown_timeout,
+            "Shutdown signal received, no longer accepting connections; draining in-flight requests"
+        );
+        handle.stop(true).await;
+    });
+
+    server.await?;
+
+    info!("HTTP server stopped, closing database pool");
+    pool_for_shutdown.close().await;
+    info!("Shutdown complete");
+
+    Ok(())
 }
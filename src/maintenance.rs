@@ -0,0 +1,178 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use actix_web::middleware::Next;
+use actix_web::{
+    Error, HttpResponse,
+    body::{BoxBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::Method,
+};
+use serde_json::json;
+use sqlx::{MySql, MySqlPool};
+
+/// This instance's copy of the `maintenance_mode` row. The row is the switch, shared by
+/// every instance; `PUT /admin/maintenance` writes it and each instance re-reads it
+/// every `SYNC_INTERVAL`.
+static MAINTENANCE: AtomicBool = AtomicBool::new(false);
+
+const SYNC_INTERVAL: Duration = Duration::from_secs(5);
+
+pub fn set_enabled(enabled: bool) {
+    MAINTENANCE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    MAINTENANCE.load(Ordering::Relaxed)
+}
+
+/// The stored switch; off until it was first set
+pub async fn load(pool: &MySqlPool) -> Result<bool, sqlx::Error> {
+    let enabled =
+        sqlx::query_scalar::<_, bool>("SELECT enabled FROM maintenance_mode WHERE id = 1")
+            .fetch_optional(pool)
+            .await?;
+    Ok(enabled.unwrap_or(false))
+}
+
+/// Turn the shared switch on or off; other instances follow within `SYNC_INTERVAL`
+pub async fn store<'e, E>(
+    executor: E,
+    enabled: bool,
+    user_id: Option<u64>,
+) -> Result<(), sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = MySql>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO maintenance_mode (id, enabled, updated_by) VALUES (1, ?, ?)
+        ON DUPLICATE KEY UPDATE enabled = VALUES(enabled), updated_by = VALUES(updated_by)
+        "#,
+    )
+    .bind(enabled)
+    .bind(user_id)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Keep this instance's flag in step with the stored switch. A failed read keeps the
+/// last known state.
+pub fn start_sync(pool: MySqlPool) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(SYNC_INTERVAL);
+        loop {
+            interval.tick().await;
+            match load(&pool).await {
+                Ok(enabled) => {
+                    if enabled != is_enabled() {
+                        tracing::warn!(enabled, "Maintenance mode changed");
+                    }
+                    set_enabled(enabled);
+                }
+                Err(e) => tracing::error!(error = %e, "Failed to read maintenance mode"),
+            }
+        }
+    });
+}
+
+/// Reads and the requests an operator needs to get in and switch maintenance off again.
+/// Login lets everyone through here; the handler turns away other roles once it knows them.
+fn is_exempt(req: &ServiceRequest) -> bool {
+    if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return true;
+    }
+
+    let path = req.path();
+    path == "/auth/login" || path.ends_with("/admin/maintenance")
+}
+
+/// While maintenance mode is on, every mutating request is answered with 503
+pub async fn maintenance_middleware<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    if is_enabled() && !is_exempt(&req) {
+        let resp = HttpResponse::ServiceUnavailable().json(json!({"error": "maintenance"}));
+        return Ok(req.into_response(resp.map_into_boxed_body()));
+    }
+
+    next.call(req).await.map(ServiceResponse::map_into_boxed_body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::middleware::from_fn;
+    use actix_web::test::{TestRequest, call_service, init_service};
+    use actix_web::{App, http::StatusCode, web};
+
+    // MAINTENANCE is process-wide; the tests that flip it run as one
+    #[actix_web::test]
+    async fn mutating_requests_get_503_while_reads_pass() {
+        let app = init_service(
+            App::new()
+                .wrap(from_fn(maintenance_middleware))
+                .route("/employee", web::get().to(HttpResponse::Ok))
+                .route("/employee", web::post().to(HttpResponse::Created))
+                .route("/auth/login", web::post().to(HttpResponse::Ok))
+                .route("/auth/refresh", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let status = |req: TestRequest| {
+            let app = &app;
+            async move { call_service(app, req.to_request()).await.status() }
+        };
+
+        set_enabled(true);
+        assert_eq!(
+            status(TestRequest::post().uri("/employee")).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            status(TestRequest::get().uri("/employee")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(TestRequest::post().uri("/auth/login")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(TestRequest::post().uri("/auth/refresh")).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        set_enabled(false);
+        assert_eq!(
+            status(TestRequest::post().uri("/employee")).await,
+            StatusCode::CREATED
+        );
+    }
+
+    #[test]
+    fn only_the_system_role_switches_maintenance() {
+        use crate::auth::permissions::is_allowed;
+        use crate::model::role::Role;
+
+        assert!(is_allowed(Role::System, "system.maintenance"));
+        for role in [Role::Admin, Role::Hr, Role::Employee, Role::ApiUser] {
+            assert!(!is_allowed(role, "system.maintenance"), "{:?}", role);
+        }
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn the_switch_is_shared_through_the_database() {
+        let pool = crate::test_support::pool().await;
+        // another instance has its own pool and its own in-memory flag
+        let other_instance = crate::test_support::pool().await;
+
+        store(&pool, true, None).await.unwrap();
+        assert!(load(&other_instance).await.unwrap());
+
+        store(&pool, false, None).await.unwrap();
+        assert!(!load(&other_instance).await.unwrap());
+    }
+}
@@ -1,6 +1,8 @@
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
-use utoipa::{ToSchema, openapi::schema};
+use utoipa::ToSchema;
+
+use crate::utils::pii;
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 #[schema(
@@ -25,15 +27,19 @@ pub struct Employee {
     pub employee_code: String,
 
     #[schema(example = "John")]
+    #[serde(serialize_with = "pii::name")]
     pub first_name: String,
 
     #[schema(example = "Doe")]
+    #[serde(serialize_with = "pii::name")]
     pub last_name: String,
 
     #[schema(example = "john.doe@company.com")]
+    #[serde(serialize_with = "pii::email")]
     pub email: String,
 
     #[schema(example = "+8801712345678", nullable = true)]
+    #[serde(serialize_with = "pii::opt_phone")]
     pub phone: Option<String>,
 
     #[schema(example = 10)]
@@ -51,5 +57,54 @@ pub struct Employee {
 
     #[schema(example = "active")]
     pub status: String,
+
+    /// Filled only when requested with `?expand=`
+    #[sqlx(skip)]
+    #[serde(flatten, default)]
+    pub names: EmployeeNames,
+}
+
+/// Related names for `?expand=department,job_title`. A field is absent unless
+/// expanded, and `null` when the referenced row no longer exists.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct EmployeeNames {
+    #[schema(example = "Engineering", value_type = Option<String>)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub department_name: Option<Option<String>>,
+
+    #[schema(example = "Software Engineer", value_type = Option<String>)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_title_name: Option<Option<String>>,
 }
 
+/// Employment status stored in `employees.status`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EmployeeStatus {
+    Active,
+    OnLeave,
+    Terminated,
+    Suspended,
+}
+
+impl EmployeeStatus {
+    pub const NAMES: &'static [&'static str] = &["active", "on_leave", "terminated", "suspended"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "active" => Some(EmployeeStatus::Active),
+            "on_leave" => Some(EmployeeStatus::OnLeave),
+            "terminated" => Some(EmployeeStatus::Terminated),
+            "suspended" => Some(EmployeeStatus::Suspended),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmployeeStatus::Active => "active",
+            EmployeeStatus::OnLeave => "on_leave",
+            EmployeeStatus::Terminated => "terminated",
+            EmployeeStatus::Suspended => "suspended",
+        }
+    }
+}
@@ -0,0 +1,78 @@
+/// Leave request lifecycle.
+///
+/// ```text
+/// pending ──► approved ──► cancelled
+///    │
+///    ├──────► rejected
+///    └──────► cancelled
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LeaveStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Cancelled,
+}
+
+impl LeaveStatus {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pending" => Some(LeaveStatus::Pending),
+            "approved" => Some(LeaveStatus::Approved),
+            "rejected" => Some(LeaveStatus::Rejected),
+            "cancelled" => Some(LeaveStatus::Cancelled),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaveStatus::Pending => "pending",
+            LeaveStatus::Approved => "approved",
+            LeaveStatus::Rejected => "rejected",
+            LeaveStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// Statuses a newly submitted request may start in
+    pub fn is_initial(&self) -> bool {
+        matches!(self, LeaveStatus::Pending | LeaveStatus::Approved)
+    }
+
+    /// Single source of truth for legal status changes
+    pub fn can_transition_to(&self, next: LeaveStatus) -> bool {
+        matches!(
+            (self, next),
+            (LeaveStatus::Pending, LeaveStatus::Approved)
+                | (LeaveStatus::Pending, LeaveStatus::Rejected)
+                | (LeaveStatus::Pending, LeaveStatus::Cancelled)
+                | (LeaveStatus::Approved, LeaveStatus::Cancelled)
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LeaveType {
+    Annual,
+    Sick,
+    Unpaid,
+}
+
+impl LeaveType {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "annual" => Some(LeaveType::Annual),
+            "sick" => Some(LeaveType::Sick),
+            "unpaid" => Some(LeaveType::Unpaid),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LeaveType::Annual => "annual",
+            LeaveType::Sick => "sick",
+            LeaveType::Unpaid => "unpaid",
+        }
+    }
+}
@@ -0,0 +1,59 @@
+/// Payroll lifecycle: `draft ──► approved ──► paid`.
+/// Amounts can only be edited while a payroll is still a draft.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PayrollStatus {
+    Draft,
+    Approved,
+    Paid,
+}
+
+impl PayrollStatus {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "draft" => Some(PayrollStatus::Draft),
+            "approved" => Some(PayrollStatus::Approved),
+            "paid" => Some(PayrollStatus::Paid),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayrollStatus::Draft => "draft",
+            PayrollStatus::Approved => "approved",
+            PayrollStatus::Paid => "paid",
+        }
+    }
+
+    pub fn can_transition_to(&self, next: PayrollStatus) -> bool {
+        matches!(
+            (self, next),
+            (PayrollStatus::Draft, PayrollStatus::Approved)
+                | (PayrollStatus::Approved, PayrollStatus::Paid)
+        )
+    }
+}
+
+/// Amounts of a payroll derived from its inputs
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NetSalary {
+    /// base + bonus
+    pub gross: f64,
+    /// `tax_percent` of gross, rounded to cents
+    pub tax: f64,
+    /// gross - tax - deductions
+    pub net: f64,
+}
+
+/// The one place the payroll arithmetic lives. Tax is charged on base + bonus;
+/// other deductions come off afterwards.
+pub fn compute_net_salary(base: f64, bonus: f64, deductions: f64, tax_percent: f64) -> NetSalary {
+    let gross = base + bonus;
+    let tax = (gross * tax_percent).round() / 100.0;
+
+    NetSalary {
+        gross,
+        tax,
+        net: gross - tax - deductions,
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Role {
     Admin = 1,
     Hr = 2,
@@ -18,4 +18,25 @@ impl Role {
             _ => None,
         }
     }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "admin" => Some(Role::Admin),
+            "hr" => Some(Role::Hr),
+            "employee" => Some(Role::Employee),
+            "system" => Some(Role::System),
+            "api_user" => Some(Role::ApiUser),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Hr => "hr",
+            Role::Employee => "employee",
+            Role::System => "system",
+            Role::ApiUser => "api_user",
+        }
+    }
 }
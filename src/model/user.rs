@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+/// Row of `users`; queries select only the columns they need
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[allow(dead_code)]
 pub struct User {
     pub id: u64,
     pub username: String,
@@ -8,6 +8,32 @@ pub struct UserReq {
     pub role_id: u8,
 }
 
+#[derive(Deserialize)]
+pub struct ChangePasswordReq {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// Either field identifies the account; `username` wins if both are given
+#[derive(Deserialize)]
+pub struct ForgotPasswordReq {
+    pub username: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ResetPasswordReq {
+    pub token: String,
+    pub new_password: String,
+}
+
+/// Without `token` the caller's own access token is revoked
+#[derive(Deserialize)]
+pub struct RevokeTokenReq {
+    /// Another access token of the same organization; admin only
+    pub token: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct LoginReqDto {
     pub username: String,
@@ -20,9 +46,13 @@ pub struct UserSql {
     pub username: String,
     pub password: String,
     pub role_id: u8,
-    pub employee_id: Option<u64>
-    // pub employee_id: Option<u64>,
-    // pub is_active: bool,
+    pub employee_id: Option<u64>,
+    /// Organization (tenant) the user belongs to
+    pub org_id: u64,
+    /// Set when HR issued a temporary password
+    pub must_change_password: bool,
+    /// Disabled accounts can't log in or use issued tokens
+    pub is_active: bool,
 }
 
 
@@ -38,6 +68,13 @@ pub struct Claims {
     pub token_type: TokenType,
     /// Present only if this user is linked to an employee record
     pub employee_id: Option<u64>,
+
+    /// Organization (tenant) every query of this token is scoped to
+    pub org_id: u64,
+
+    /// Every endpoint except change-password is blocked while set
+    #[serde(default)]
+    pub must_change_password: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
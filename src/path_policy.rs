@@ -0,0 +1,296 @@
+use actix_web::http::header::{CONTENT_TYPE, HeaderValue, LOCATION};
+use actix_web::middleware::{ErrorHandlerResponse, Next};
+use actix_web::{
+    Error, HttpRequest, HttpResponse,
+    body::{BoxBody, EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+};
+use once_cell::sync::OnceCell;
+use serde_json::json;
+
+/// Route templates (`/api/employee/{id}`) used to suggest a path on 404
+static KNOWN_PATHS: OnceCell<Vec<String>> = OnceCell::new();
+
+pub fn init(paths: Vec<String>) {
+    let _ = KNOWN_PATHS.set(paths);
+}
+
+/// Routes are matched case-insensitively by redirecting: a path that matches a known
+/// route in all but letter case gets a 308 to the route's canonical (lowercase) spelling.
+/// Only the route's literal segments are rewritten; ids and other parameters, the query
+/// string and paths that match no route (swagger assets, typos) are left alone.
+/// Trailing slashes are trimmed separately by `NormalizePath::trim()`.
+pub async fn canonical_path_middleware<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let canonical = KNOWN_PATHS
+        .get()
+        .and_then(|known| canonical_path(req.path(), known));
+
+    if let Some(canonical) = canonical {
+        let location = match req.query_string() {
+            "" => canonical,
+            query => format!("{}?{}", canonical, query),
+        };
+        let res = HttpResponse::PermanentRedirect()
+            .insert_header((LOCATION, location))
+            .finish();
+        return Ok(req.into_response(res));
+    }
+
+    next.call(req).await.map(ServiceResponse::map_into_boxed_body)
+}
+
+/// The canonical spelling of `path` when it matches a route template of `known` only
+/// case-insensitively; `None` when it is already canonical or matches no template.
+/// Templates with more literal segments win, so `/Employee/Count` is `/employee/count`
+/// rather than `/employee/{id}`.
+fn canonical_path(path: &str, known: &[String]) -> Option<String> {
+    if !path.bytes().any(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+
+    let requested: Vec<&str> = segments(path).collect();
+    let template = known
+        .iter()
+        .filter(|t| {
+            segments(t).count() == requested.len()
+                && segments(t)
+                    .zip(&requested)
+                    .all(|(t, r)| t.starts_with('{') || t.eq_ignore_ascii_case(r))
+        })
+        .max_by_key(|t| segments(t).filter(|s| !s.starts_with('{')).count())?;
+
+    let canonical: Vec<&str> = segments(template)
+        .zip(&requested)
+        .map(|(t, r)| if t.starts_with('{') { *r } else { t })
+        .collect();
+    let canonical = format!("/{}", canonical.join("/"));
+
+    (canonical != path).then_some(canonical)
+}
+
+/// Fallback for unmatched requests: JSON 404, with the closest known route when there is one
+pub async fn not_found(req: HttpRequest) -> HttpResponse {
+    let path = req.path();
+    let mut body = json!({"error": "Not Found", "path": path});
+
+    if let Some(suggestion) = suggest(path) {
+        body["suggestion"] = json!(suggestion);
+    }
+
+    HttpResponse::NotFound().json(body)
+}
+
+/// `ErrorHandlers` hook for 405: a known path with the wrong method gets a JSON body
+/// like `not_found` instead of actix's empty one
+pub fn method_not_allowed<B>(res: ServiceResponse<B>) -> actix_web::Result<ErrorHandlerResponse<B>> {
+    let body = json!({
+        "error": "Method Not Allowed",
+        "method": res.request().method().as_str(),
+        "path": res.request().path()
+    })
+    .to_string();
+
+    let res = res.map_body(|head, _| {
+        head.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        EitherBody::right(BoxBody::new(body))
+    });
+
+    Ok(ErrorHandlerResponse::Response(res))
+}
+
+/// Closest route template by edit distance, with the request's id segments filled in.
+/// Ids are compared as `{id}` so `/api/employees/17` is close to `/api/employee/{id}`.
+fn suggest(path: &str) -> Option<String> {
+    let known = KNOWN_PATHS.get()?;
+    let ids: Vec<&str> = segments(path).filter(|s| is_id(s)).collect();
+    let wanted = shape(path);
+
+    let (distance, template) = known
+        .iter()
+        .map(|t| (edit_distance(&wanted, &shape(t)), t))
+        .min_by_key(|(d, _)| *d)?;
+
+    // roughly one typo per four characters, never more than a few
+    let max_distance = (wanted.len() / 4).clamp(1, 4);
+    if distance == 0 || distance > max_distance {
+        return None;
+    }
+
+    // fill placeholders with the ids from the request, in order; give up if counts differ
+    let placeholders = segments(template).filter(|s| s.starts_with('{')).count();
+    if placeholders != ids.len() {
+        return (placeholders == 0).then(|| template.clone());
+    }
+
+    let mut ids = ids.into_iter();
+    let filled: Vec<&str> = segments(template)
+        .map(|s| if s.starts_with('{') { ids.next().unwrap_or(s) } else { s })
+        .collect();
+    Some(format!("/{}", filled.join("/")))
+}
+
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|s| !s.is_empty())
+}
+
+fn is_id(segment: &str) -> bool {
+    segment.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Path with every id segment or placeholder replaced by `{id}`
+fn shape(path: &str) -> String {
+    segments(path)
+        .map(|s| if is_id(s) || s.starts_with('{') { "{id}" } else { s })
+        .fold(String::new(), |acc, s| acc + "/" + s)
+}
+
+/// Levenshtein distance over bytes (route templates are ASCII)
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::middleware::{ErrorHandlers, NormalizePath, from_fn};
+    use actix_web::test::{TestRequest, call_service, init_service, read_body, read_body_json};
+    use actix_web::{App, web};
+    use serde_json::Value;
+
+    const ROUTES: [&str; 3] = ["/employee", "/employee/count", "/employee/{id}"];
+
+    fn known() -> Vec<String> {
+        ROUTES.iter().map(|r| r.to_string()).collect()
+    }
+
+    async fn echo_path(req: HttpRequest) -> HttpResponse {
+        HttpResponse::Ok().body(req.path().to_string())
+    }
+
+    macro_rules! app {
+        () => {{
+            init(known());
+            init_service(
+                App::new()
+                    .wrap(
+                        ErrorHandlers::new()
+                            .handler(StatusCode::METHOD_NOT_ALLOWED, method_not_allowed),
+                    )
+                    .wrap(from_fn(canonical_path_middleware))
+                    .wrap(NormalizePath::trim())
+                    .service(web::resource("/employee").route(web::get().to(HttpResponse::Ok)))
+                    .route("/employee/count", web::get().to(echo_path))
+                    .route("/employee/{id}", web::get().to(echo_path))
+                    .default_service(web::to(not_found)),
+            )
+        }};
+    }
+
+    #[actix_web::test]
+    async fn unknown_path_is_json_404() {
+        let app = app!().await;
+
+        let res = call_service(&app, TestRequest::get().uri("/nothing-here").to_request()).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "application/json");
+
+        let body: Value = read_body_json(res).await;
+        assert_eq!(body["error"], "Not Found");
+        assert_eq!(body["path"], "/nothing-here");
+    }
+
+    #[actix_web::test]
+    async fn wrong_method_is_json_405() {
+        let app = app!().await;
+
+        let res = call_service(&app, TestRequest::delete().uri("/employee").to_request()).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "application/json");
+
+        let body: Value = read_body_json(res).await;
+        assert_eq!(body["error"], "Method Not Allowed");
+        assert_eq!(body["method"], "DELETE");
+    }
+
+    #[actix_web::test]
+    async fn matched_route_is_untouched() {
+        let app = app!().await;
+
+        let res = call_service(&app, TestRequest::get().uri("/employee").to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    async fn get(uri: &str) -> ServiceResponse {
+        let app = app!().await;
+        call_service(&app, TestRequest::get().uri(uri).to_request()).await
+    }
+
+    fn location(res: &ServiceResponse) -> &str {
+        res.headers().get(LOCATION).unwrap().to_str().unwrap()
+    }
+
+    #[actix_web::test]
+    async fn trailing_slash_is_trimmed() {
+        let res = get("/employee/").await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn wrong_case_is_redirected_to_the_route() {
+        let res = get("/Employee/COUNT?Status=Active").await;
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(location(&res), "/employee/count?Status=Active");
+
+        // trimmed first, then redirected in one hop
+        let res = get("/EMPLOYEE/").await;
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(location(&res), "/employee");
+    }
+
+    #[actix_web::test]
+    async fn parameters_keep_their_case() {
+        let res = get("/Employee/AbC-12").await;
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(location(&res), "/employee/AbC-12");
+
+        let res = get("/employee/AbC-12").await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(read_body(res).await, "/employee/AbC-12");
+    }
+
+    #[actix_web::test]
+    async fn unknown_path_is_not_redirected() {
+        let res = get("/Swagger-UI/Index.HTML").await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let res = get("/Employee/1/Unknown").await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn literal_segments_win_over_parameters() {
+        assert_eq!(
+            canonical_path("/Employee/Count", &known()).as_deref(),
+            Some("/employee/count")
+        );
+        assert_eq!(canonical_path("/employee/count", &known()), None);
+    }
+}
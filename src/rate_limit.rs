@@ -0,0 +1,127 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use actix_governor::{KeyExtractor, SimpleKeyExtractionError};
+use actix_web::dev::ServiceRequest;
+use actix_web::http::header::{AUTHORIZATION, HeaderMap};
+
+use crate::auth::jwt::verify_token;
+use crate::config::Config;
+use crate::models::TokenType;
+
+/// Where the client address comes from: the peer, or `X-Forwarded-For` behind a proxy
+#[derive(Clone)]
+pub struct ClientIp {
+    trust_proxy: bool,
+    trusted_proxies: Arc<[IpAddr]>,
+}
+
+impl ClientIp {
+    pub fn new(config: &Config) -> Self {
+        ClientIp {
+            trust_proxy: config.trust_proxy,
+            trusted_proxies: config.trusted_proxies.as_slice().into(),
+        }
+    }
+
+    /// The leftmost `X-Forwarded-For` entry when `TRUST_PROXY` is on and the peer is an
+    /// allowed proxy, else the peer IP. Without `TRUST_PROXY` the header is ignored,
+    /// since any client can send it.
+    pub fn of(&self, req: &ServiceRequest) -> Option<IpAddr> {
+        self.resolve(req.peer_addr().map(|addr| addr.ip()), req.headers())
+    }
+
+    /// `of` for handlers, which get an `HttpRequest`
+    pub fn resolve(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let peer = peer?;
+
+        let from_proxy = self.trust_proxy
+            && (self.trusted_proxies.is_empty() || self.trusted_proxies.contains(&peer));
+        if !from_proxy {
+            return Some(peer);
+        }
+
+        let forwarded = headers
+            .get("X-Forwarded-For")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .and_then(|v| v.trim().parse::<IpAddr>().ok());
+
+        // a malformed header is not a reason to lump the request in with the proxy's
+        Some(forwarded.unwrap_or(peer))
+    }
+}
+
+fn missing_ip() -> SimpleKeyExtractionError<&'static str> {
+    SimpleKeyExtractionError::new("Could not extract client IP address from request")
+}
+
+/// Keys pre-auth endpoints by client IP (see `ClientIp`)
+#[derive(Clone)]
+pub struct ClientIpKeyExtractor {
+    client_ip: ClientIp,
+}
+
+impl ClientIpKeyExtractor {
+    pub fn new(config: &Config) -> Self {
+        ClientIpKeyExtractor {
+            client_ip: ClientIp::new(config),
+        }
+    }
+}
+
+impl KeyExtractor for ClientIpKeyExtractor {
+    type Key = IpAddr;
+    type KeyExtractionError = SimpleKeyExtractionError<&'static str>;
+
+    fn extract(&self, req: &ServiceRequest) -> Result<Self::Key, Self::KeyExtractionError> {
+        self.client_ip.of(req).ok_or_else(missing_ip)
+    }
+}
+
+/// Rate-limit bucket of a request
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum RateKey {
+    User(u64),
+    Ip(IpAddr),
+}
+
+/// Keys authenticated requests by the token's `user_id`, so users behind one NAT don't
+/// share a bucket and a client switching IPs keeps its own. Requests without a valid
+/// access token fall back to the client IP. Runs before `auth_middleware`, hence the
+/// token is verified here too: an unverified `user_id` would allow picking any bucket.
+#[derive(Clone)]
+pub struct UserKeyExtractor {
+    jwt_secret: Arc<str>,
+    client_ip: ClientIp,
+}
+
+impl UserKeyExtractor {
+    pub fn new(config: &Config) -> Self {
+        UserKeyExtractor {
+            jwt_secret: config.jwt_secret.as_str().into(),
+            client_ip: ClientIp::new(config),
+        }
+    }
+}
+
+impl KeyExtractor for UserKeyExtractor {
+    type Key = RateKey;
+    type KeyExtractionError = SimpleKeyExtractionError<&'static str>;
+
+    fn extract(&self, req: &ServiceRequest) -> Result<Self::Key, Self::KeyExtractionError> {
+        let user_id = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .and_then(|token| verify_token(token, &self.jwt_secret, TokenType::Access).ok())
+            .map(|claims| claims.user_id);
+
+        if let Some(user_id) = user_id {
+            return Ok(RateKey::User(user_id));
+        }
+
+        self.client_ip.of(req).map(RateKey::Ip).ok_or_else(missing_ip)
+    }
+}
@@ -0,0 +1,162 @@
+use actix_web::error::InternalError;
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::middleware::Next;
+use actix_web::{
+    Error, HttpMessage,
+    body::{BoxBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+};
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Correlation id of the current request, also available to handlers via extensions
+#[derive(Clone, Debug)]
+#[allow(dead_code)] // for handlers that quote it; none does yet
+pub struct RequestId(pub String);
+
+/// An incoming id is kept (e.g. set by the load balancer) if it is short and plain,
+/// since it ends up in logs and a response header
+fn is_acceptable(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+}
+
+/// Tags every log line of the request with `request_id` and echoes it in `X-Request-Id`,
+/// so a support ticket quoting the header leads straight to the logs
+pub async fn request_id_middleware<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .filter(|id| is_acceptable(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    req.extensions_mut().insert(RequestId(id.clone()));
+    let header = HeaderValue::from_str(&id).ok();
+
+    let span = tracing::info_span!("request", request_id = %id);
+    match next.call(req).instrument(span).await {
+        Ok(mut res) => {
+            if let Some(value) = header {
+                res.headers_mut()
+                    .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+            }
+            Ok(res.map_into_boxed_body())
+        }
+        // errors get the header too; they are what tickets are about. The request
+        // can't be kept around for `ServiceResponse::from_err`: routing needs it unshared.
+        Err(e) => {
+            let mut resp = e.error_response();
+            if let Some(value) = header {
+                resp.headers_mut()
+                    .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+            }
+            Err(InternalError::from_response(e.to_string(), resp).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::dev::Service;
+    use actix_web::test::{TestRequest, call_service, init_service};
+    use actix_web::{App, HttpResponse, middleware::from_fn, web};
+
+    fn response_id(res: &ServiceResponse<impl MessageBody>) -> String {
+        res.headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .expect("X-Request-Id missing")
+            .to_string()
+    }
+
+    #[actix_web::test]
+    async fn echoes_incoming_id() {
+        let app = init_service(
+            App::new()
+                .wrap(from_fn(request_id_middleware))
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::get()
+            .uri("/")
+            .insert_header((REQUEST_ID_HEADER, "lb-1234.abc"))
+            .to_request();
+        let res = call_service(&app, req).await;
+
+        assert_eq!(response_id(&res), "lb-1234.abc");
+    }
+
+    #[actix_web::test]
+    async fn generates_id_when_missing_or_unacceptable() {
+        let app = init_service(
+            App::new()
+                .wrap(from_fn(request_id_middleware))
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let res = call_service(&app, TestRequest::get().uri("/").to_request()).await;
+        assert!(Uuid::parse_str(&response_id(&res)).is_ok());
+
+        let req = TestRequest::get()
+            .uri("/")
+            .insert_header((REQUEST_ID_HEADER, "has spaces"))
+            .to_request();
+        let res = call_service(&app, req).await;
+        assert!(Uuid::parse_str(&response_id(&res)).is_ok());
+    }
+
+    #[actix_web::test]
+    async fn unmatched_route_gets_id_too() {
+        let app = init_service(App::new().wrap(from_fn(request_id_middleware))).await;
+
+        let res = call_service(&app, TestRequest::get().uri("/nope").to_request()).await;
+        assert_eq!(res.status(), 404);
+        assert!(!response_id(&res).is_empty());
+    }
+
+    #[actix_web::test]
+    async fn errors_from_inner_middleware_get_id() {
+        let app = init_service(
+            App::new()
+                .wrap(from_fn(
+                    |_req: ServiceRequest, _next: Next<BoxBody>| async {
+                        Err::<ServiceResponse<BoxBody>, _>(actix_web::error::ErrorForbidden("no"))
+                    },
+                ))
+                .wrap(from_fn(request_id_middleware))
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::get()
+            .uri("/")
+            .insert_header((REQUEST_ID_HEADER, "abc"))
+            .to_request();
+        let err = app.call(req).await.expect_err("inner error expected");
+        let resp = err.error_response();
+
+        assert_eq!(resp.status(), 403);
+        assert_eq!(resp.headers().get(REQUEST_ID_HEADER).unwrap(), "abc");
+    }
+
+    #[test]
+    fn acceptable_ids() {
+        assert!(is_acceptable("abc-123_x.y:z"));
+        assert!(!is_acceptable(""));
+        assert!(!is_acceptable("a b"));
+        assert!(!is_acceptable(&"a".repeat(129)));
+    }
+}
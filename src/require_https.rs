@@ -0,0 +1,136 @@
+use actix_web::middleware::Next;
+use actix_web::{
+    Error, HttpResponse,
+    body::{BoxBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    web::Data,
+};
+use serde_json::json;
+
+use crate::config::Config;
+
+/// Endpoints carrying credentials; only these are checked, so health checks and
+/// ordinary reads keep working behind a plaintext hop
+fn is_sensitive(path: &str) -> bool {
+    path.starts_with("/auth/")
+        || path.ends_with("/change-password")
+        || path.ends_with("/reset-password")
+}
+
+/// Scheme the client used. `X-Forwarded-Proto` is only believed when the
+/// direct peer is one of `TRUSTED_PROXIES`; anyone else could set it.
+fn client_scheme(req: &ServiceRequest, config: &Config) -> String {
+    let from_trusted_proxy = req
+        .peer_addr()
+        .is_some_and(|peer| config.trusted_proxies.contains(&peer.ip()));
+
+    let forwarded = req
+        .headers()
+        .get("X-Forwarded-Proto")
+        .and_then(|h| h.to_str().ok())
+        // a chain of proxies appends; the first value is the client's
+        .and_then(|v| v.split(',').next())
+        .map(|v| v.trim().to_ascii_lowercase());
+
+    match forwarded {
+        Some(proto) if from_trusted_proxy => proto,
+        _ if req.app_config().secure() => "https".to_string(),
+        _ => "http".to_string(),
+    }
+}
+
+/// With `REQUIRE_HTTPS` on, credentials must not arrive over plaintext
+pub async fn require_https_middleware<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let reject = match req.app_data::<Data<Config>>() {
+        Some(config) => {
+            config.require_https
+                && is_sensitive(req.path())
+                && client_scheme(&req, config) != "https"
+        }
+        None => false,
+    };
+
+    if reject {
+        tracing::warn!(path = %req.path(), "Rejected sensitive request over plain HTTP");
+        let resp = HttpResponse::BadRequest().json(json!({"error": "https_required"}));
+        return Ok(req.into_response(resp.map_into_boxed_body()));
+    }
+
+    next.call(req).await.map(ServiceResponse::map_into_boxed_body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::middleware::from_fn;
+    use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+    use actix_web::{App, web};
+
+    const PROXY: &str = "10.0.0.5:443";
+
+    fn config(require_https: &str) -> Config {
+        Config::for_test(&[
+            ("REQUIRE_HTTPS", require_https),
+            ("TRUSTED_PROXIES", "10.0.0.5"),
+        ])
+    }
+
+    macro_rules! app {
+        ($config:expr) => {
+            init_service(
+                App::new()
+                    .app_data(Data::new($config))
+                    .wrap(from_fn(require_https_middleware))
+                    .route("/auth/login", web::post().to(HttpResponse::Ok))
+                    .route("/health", web::get().to(HttpResponse::Ok)),
+            )
+        };
+    }
+
+    fn login(peer: &str, proto: &str) -> TestRequest {
+        TestRequest::post()
+            .uri("/auth/login")
+            .peer_addr(peer.parse().unwrap())
+            .insert_header(("X-Forwarded-Proto", proto))
+    }
+
+    #[actix_web::test]
+    async fn http_forwarded_login_is_rejected() {
+        let app = app!(config("true")).await;
+
+        let res = call_service(&app, login(PROXY, "http").to_request()).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let body: serde_json::Value = read_body_json(res).await;
+        assert_eq!(body, json!({"error": "https_required"}));
+
+        let res = call_service(&app, login(PROXY, "https").to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn forwarded_proto_is_only_trusted_from_proxies() {
+        let app = app!(config("true")).await;
+
+        let res = call_service(&app, login("203.0.113.9:5000", "https").to_request()).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn health_and_disabled_checks_pass() {
+        let app = app!(config("true")).await;
+        let health = TestRequest::get()
+            .uri("/health")
+            .peer_addr(PROXY.parse().unwrap())
+            .insert_header(("X-Forwarded-Proto", "http"))
+            .to_request();
+        assert_eq!(call_service(&app, health).await.status(), StatusCode::OK);
+
+        let app = app!(config("false")).await;
+        let res = call_service(&app, login(PROXY, "http").to_request()).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
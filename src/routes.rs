@@ -1,35 +1,48 @@
 use crate::{
-    api::{attendance, employee, leave_request, payroll},
+    api::{
+        admin, attendance, audit, department, employee, holiday, job_title, leave_balance,
+        leave_request, payroll, timeline, user,
+    },
     auth::{handlers, middleware::auth_middleware},
     config::Config,
+    rate_limit::{ClientIpKeyExtractor, UserKeyExtractor},
 };
 use actix_governor::{
-    Governor, GovernorConfigBuilder, PeerIpKeyExtractor, governor::middleware::NoOpMiddleware,
+    Governor, GovernorConfigBuilder, KeyExtractor, governor::middleware::NoOpMiddleware,
 };
 use actix_web::{middleware::from_fn, web};
 use std::sync::Arc;
 
 pub fn configure(cfg: &mut web::ServiceConfig, config: Config) {
     // Helper to build per-route limiter
-    fn build_limiter(requests_per_min: u32) -> Governor<PeerIpKeyExtractor, NoOpMiddleware> {
+    fn build_limiter<K: KeyExtractor>(
+        requests_per_min: u32,
+        key_extractor: K,
+    ) -> Governor<K, NoOpMiddleware> {
         let per_ms = if requests_per_min == 0 {
             1
         } else {
             60_000 / requests_per_min as u64
         };
         let cfg = GovernorConfigBuilder::default()
-            .per_millisecond(per_ms)
+            .milliseconds_per_request(per_ms)
             .burst_size(requests_per_min)
-            .key_extractor(PeerIpKeyExtractor)
+            .key_extractor(key_extractor)
             .finish()
             .unwrap();
         Governor::new(&cfg)
     }
 
-    let login_limiter = Arc::new(build_limiter(config.rate_login_per_min));
-    let register_limiter = Arc::new(build_limiter(config.rate_register_per_min));
-    let refresh_limiter = Arc::new(build_limiter(config.rate_refresh_per_min));
-    let protected_limiter = Arc::new(build_limiter(config.rate_protected_per_min));
+    // pre-auth endpoints can only be keyed by IP
+    let client_ip = ClientIpKeyExtractor::new(&config);
+    let login_limiter = Arc::new(build_limiter(config.rate_login_per_min, client_ip.clone()));
+    let register_limiter =
+        Arc::new(build_limiter(config.rate_register_per_min, client_ip.clone()));
+    let refresh_limiter = Arc::new(build_limiter(config.rate_refresh_per_min, client_ip));
+    let protected_limiter = Arc::new(build_limiter(
+        config.rate_protected_per_min,
+        UserKeyExtractor::new(&config),
+    ));
 
     // Public routes
     cfg.service(
@@ -53,6 +66,21 @@ pub fn configure(cfg: &mut web::ServiceConfig, config: Config) {
                 web::resource("/logout")
                     .wrap(login_limiter.clone())
                     .route(web::post().to(handlers::logout)),
+            )
+            .service(
+                web::resource("/revoke")
+                    .wrap(login_limiter.clone())
+                    .route(web::post().to(handlers::revoke_token)),
+            )
+            .service(
+                web::resource("/forgot-password")
+                    .wrap(login_limiter.clone())
+                    .route(web::post().to(handlers::forgot_password)),
+            )
+            .service(
+                web::resource("/reset-password")
+                    .wrap(login_limiter.clone())
+                    .route(web::post().to(handlers::reset_password)),
             ),
     );
 
@@ -63,6 +91,11 @@ pub fn configure(cfg: &mut web::ServiceConfig, config: Config) {
              // authentication
             .wrap(protected_limiter) // rate limiting
             .service(handlers::protected)
+            .service(
+                web::resource("/change-password")
+                    .route(web::post().to(handlers::change_password)),
+            )
+            .service(web::resource("/me").route(web::get().to(user::me)))
             .service(
                 web::scope("/employee")
                     // /employee
@@ -71,6 +104,45 @@ pub fn configure(cfg: &mut web::ServiceConfig, config: Config) {
                             .route(web::post().to(employee::create_employee))
                             .route(web::get().to(employee::list_employees)),
                     )
+                    // /employee/count (before /{id})
+                    .service(
+                        web::resource("/count").route(web::get().to(employee::count_employees)),
+                    )
+                    // /employee/filter-options (before /{id})
+                    .service(
+                        web::resource("/filter-options")
+                            .route(web::get().to(employee::employee_filter_options)),
+                    )
+                    // /employee/export (before /{id})
+                    .service(
+                        web::resource("/export").route(web::get().to(employee::export_employees)),
+                    )
+                    // /employee/{id}/photo (uploads are larger than the default payload limit)
+                    .service(
+                        web::resource("/{id}/photo")
+                            .app_data(web::PayloadConfig::new(config.photo_max_upload_bytes))
+                            .route(web::put().to(employee::upload_employee_photo)),
+                    )
+                    // /employee/{id}/timeline
+                    .service(
+                        web::resource("/{id}/timeline")
+                            .route(web::get().to(timeline::employee_timeline)),
+                    )
+                    // /employee/{id}/entitlement
+                    .service(
+                        web::resource("/{id}/entitlement")
+                            .route(web::get().to(leave_balance::employee_entitlement)),
+                    )
+                    // /employee/{id}/restore
+                    .service(
+                        web::resource("/{id}/restore")
+                            .route(web::post().to(employee::restore_employee)),
+                    )
+                    // /employee/{id}/status
+                    .service(
+                        web::resource("/{id}/status")
+                            .route(web::put().to(employee::update_employee_status)),
+                    )
                     // /employee/{id}
                     .service(
                         web::resource("/{id}")
@@ -87,6 +159,37 @@ pub fn configure(cfg: &mut web::ServiceConfig, config: Config) {
                             .route(web::get().to(leave_request::leave_list))
                             .route(web::post().to(leave_request::create_leave)),
                     )
+                    // /leave/balance (before /{id})
+                    .service(
+                        web::resource("/balance").route(web::get().to(leave_balance::leave_balance)),
+                    )
+                    // /leave/bulk-approve (before /{id})
+                    .service(
+                        web::resource("/bulk-approve")
+                            .route(web::put().to(leave_request::bulk_approve_leaves)),
+                    )
+                    // /leave/bulk-reject (before /{id})
+                    .service(
+                        web::resource("/bulk-reject")
+                            .route(web::put().to(leave_request::bulk_reject_leaves)),
+                    )
+                    // /leave/balance/bulk-adjust
+                    .service(
+                        web::resource("/balance/bulk-adjust")
+                            .route(web::post().to(leave_balance::bulk_adjust_balances)),
+                    )
+                    // /leave/count (before /{id})
+                    .service(
+                        web::resource("/count").route(web::get().to(leave_request::count_leaves)),
+                    )
+                    // /leave/stats (before /{id})
+                    .service(
+                        web::resource("/stats").route(web::get().to(leave_request::leave_stats)),
+                    )
+                    // /leave/export (before /{id})
+                    .service(
+                        web::resource("/export").route(web::get().to(leave_request::export_leaves)),
+                    )
                     // /leave/{id}
                     .service(web::resource("/{id}").route(web::get().to(leave_request::get_leave)))
                     // /leave/{id}/approve
@@ -98,15 +201,39 @@ pub fn configure(cfg: &mut web::ServiceConfig, config: Config) {
                     .service(
                         web::resource("/{id}/reject")
                             .route(web::put().to(leave_request::reject_leave)),
+                    )
+                    // /leave/{id}/cancel
+                    .service(
+                        web::resource("/{id}/cancel")
+                            .route(web::put().to(leave_request::cancel_leave)),
                     ),
             )
             .service(
                 web::scope("/attendance")
                     // /attendance
                     .service(
-                        web::resource("")
-                            .route(web::put().to(attendance::check_out))
-                            .route(web::post().to(attendance::check_in)),
+                        web::resource("").route(web::get().to(attendance::list_attendance)),
+                    )
+                    // /attendance/check-in
+                    .service(
+                        web::resource("/check-in").route(web::post().to(attendance::check_in)),
+                    )
+                    // /attendance/check-out
+                    .service(
+                        web::resource("/check-out").route(web::post().to(attendance::check_out)),
+                    )
+                    // /attendance/summary
+                    .service(
+                        web::resource("/summary")
+                            .route(web::get().to(attendance::attendance_summary)),
+                    )
+                    // /attendance/present
+                    .service(
+                        web::resource("/present").route(web::get().to(attendance::present)),
+                    )
+                    // /attendance/{id}
+                    .service(
+                        web::resource("/{id}").route(web::put().to(attendance::correct_attendance)),
                     )
             )
             .service(
@@ -115,16 +242,220 @@ pub fn configure(cfg: &mut web::ServiceConfig, config: Config) {
                     .service(
                         web::resource("")
                             .route(web::post().to(payroll::create_payroll))
-                            .route(web::put().to(payroll::update_payroll))
                             .route(web::get().to(payroll::list_payrolls))
                     )
+                    //payroll/count (before /{id})
+                    .service(web::resource("/count").route(web::get().to(payroll::count_payrolls)))
+                    //payroll/mine (before /{id})
+                    .service(web::resource("/mine").route(web::get().to(payroll::my_payslips)))
+                    //payroll/generate (before /{id})
+                    .service(
+                        web::resource("/generate").route(web::post().to(payroll::generate_payrolls)),
+                    )
+                    //payroll/by-month (before /{id})
+                    .service(
+                        web::resource("/by-month").route(web::get().to(payroll::payroll_by_month)),
+                    )
                     //payroll/{id}
-                    .service(web::resource("/{id}").route(web::get().to(payroll::get_payroll)))
+                    .service(
+                        web::resource("/{id}")
+                            .route(web::get().to(payroll::get_payroll))
+                            .route(web::put().to(payroll::update_payroll)),
+                    )
+                    //payroll/{id}/approve
+                    .service(
+                        web::resource("/{id}/approve")
+                            .route(web::put().to(payroll::approve_payroll)),
+                    )
+                    //payroll/{id}/payslip
+                    .service(
+                        web::resource("/{id}/payslip")
+                            .route(web::get().to(payroll::download_payslip)),
+                    )
+                    //payroll/{id}/pay
+                    .service(
+                        web::resource("/{id}/pay").route(web::put().to(payroll::pay_payroll)),
+                    )
+            )
+            // /department, and /departments where the batch endpoint was first published
+            .service(department_scope("/department"))
+            .service(department_scope("/departments"))
+            .service(
+                web::scope("/admin")
+                    // /admin/users/{user_id}/reset-password
+                    .service(
+                        web::resource("/users/{user_id}/reset-password")
+                            .route(web::post().to(user::reset_user_password)),
+                    )
+                    // /admin/users/{user_id}
+                    .service(
+                        web::resource("/users/{user_id}").route(web::delete().to(user::delete_user)),
+                    )
+                    // /admin/users/{user_id}/active
+                    .service(
+                        web::resource("/users/{user_id}/active")
+                            .route(web::put().to(user::set_user_active)),
+                    )
+                    // /admin/maintenance
+                    .service(
+                        web::resource("/maintenance")
+                            .route(web::get().to(admin::get_maintenance))
+                            .route(web::put().to(admin::set_maintenance)),
+                    )
+                    // /admin/reconcile
+                    .service(web::resource("/reconcile").route(web::get().to(admin::reconcile)))
+                    // /admin/reconcile/fix
+                    .service(
+                        web::resource("/reconcile/fix").route(web::post().to(admin::reconcile_fix)),
+                    ),
+            )
+            .service(
+                web::scope("/audit")
+                    // /audit
+                    .service(web::resource("").route(web::get().to(audit::search_audit)))
+                    // /audit/export
+                    .service(web::resource("/export").route(web::get().to(audit::export_audit))),
+            )
+            // /job-title, and /job-titles where the batch endpoint was first published
+            .service(job_title_scope("/job-title"))
+            .service(job_title_scope("/job-titles"))
+            .service(
+                web::scope("/holidays")
+                    // /holidays
+                    .service(
+                        web::resource("")
+                            .route(web::post().to(holiday::create_holiday))
+                            .route(web::get().to(holiday::list_holidays)),
+                    ),
             )
             ,
     );
 }
 
+/// Department CRUD and batch routes under `path`
+fn department_scope(path: &str) -> actix_web::Scope {
+    web::scope(path)
+        .service(
+            web::resource("")
+                .route(web::get().to(department::list_departments))
+                .route(web::post().to(department::create_department)),
+        )
+        // batch before /{id}
+        .service(
+            web::resource("/batch").route(web::post().to(department::batch_create_departments)),
+        )
+        .service(
+            web::resource("/{id}")
+                .route(web::get().to(department::get_department))
+                .route(web::put().to(department::update_department))
+                .route(web::delete().to(department::delete_department)),
+        )
+}
+
+/// Job title CRUD and batch routes under `path`
+fn job_title_scope(path: &str) -> actix_web::Scope {
+    web::scope(path)
+        .service(
+            web::resource("")
+                .route(web::get().to(job_title::list_job_titles))
+                .route(web::post().to(job_title::create_job_title)),
+        )
+        // batch before /{id}
+        .service(
+            web::resource("/batch").route(web::post().to(job_title::batch_create_job_titles)),
+        )
+        .service(
+            web::resource("/{id}")
+                .route(web::get().to(job_title::get_job_title))
+                .route(web::put().to(job_title::update_job_title))
+                .route(web::delete().to(job_title::delete_job_title)),
+        )
+}
+
+/// Every route template registered in `configure`, used by `path_policy` to suggest a
+/// path on 404. Keep in sync when adding routes.
+pub fn known_paths(api_prefix: &str) -> Vec<String> {
+    let public = [
+        "/health",
+        "/ready",
+        "/auth/login",
+        "/auth/register",
+        "/auth/refresh",
+        "/auth/logout",
+        "/auth/revoke",
+        "/auth/forgot-password",
+        "/auth/reset-password",
+    ];
+    let protected = [
+        "/change-password",
+        "/me",
+        "/employee",
+        "/employee/count",
+        "/employee/filter-options",
+        "/employee/export",
+        "/employee/{id}/photo",
+        "/employee/{id}/timeline",
+        "/employee/{id}/entitlement",
+        "/employee/{id}/restore",
+        "/employee/{id}/status",
+        "/employee/{id}",
+        "/leave",
+        "/leave/balance",
+        "/leave/balance/bulk-adjust",
+        "/leave/bulk-approve",
+        "/leave/bulk-reject",
+        "/leave/count",
+        "/leave/stats",
+        "/leave/export",
+        "/leave/{id}",
+        "/leave/{id}/approve",
+        "/leave/{id}/reject",
+        "/leave/{id}/cancel",
+        "/attendance",
+        "/attendance/check-in",
+        "/attendance/check-out",
+        "/attendance/present",
+        "/attendance/summary",
+        "/attendance/{id}",
+        "/payroll",
+        "/payroll/count",
+        "/payroll/mine",
+        "/payroll/by-month",
+        "/payroll/generate",
+        "/payroll/{id}",
+        "/payroll/{id}/approve",
+        "/payroll/{id}/pay",
+        "/payroll/{id}/payslip",
+        "/department",
+        "/department/batch",
+        "/department/{id}",
+        "/departments",
+        "/departments/batch",
+        "/departments/{id}",
+        "/admin/users/{user_id}",
+        "/admin/users/{user_id}/reset-password",
+        "/admin/users/{user_id}/active",
+        "/admin/maintenance",
+        "/admin/reconcile",
+        "/admin/reconcile/fix",
+        "/audit",
+        "/audit/export",
+        "/job-title",
+        "/job-title/batch",
+        "/job-title/{id}",
+        "/job-titles",
+        "/job-titles/batch",
+        "/job-titles/{id}",
+        "/holidays",
+    ];
+
+    public
+        .iter()
+        .map(|p| p.to_string())
+        .chain(protected.iter().map(|p| format!("{}{}", api_prefix, p)))
+        .collect()
+}
+
 // LOGIN
 //  ├─ access_token (15 min)
 //  └─ refresh_token (7 days)
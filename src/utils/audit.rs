@@ -0,0 +1,46 @@
+use serde_json::Value;
+use sqlx::{Executor, MySql};
+
+/// One row of `audit_log`
+pub struct AuditEntry<'a> {
+    pub org_id: u64,
+    /// `None` for system actions (startup, background jobs)
+    pub actor_user_id: Option<u64>,
+    /// e.g. `leave_request`, `payroll`, `user`
+    pub entity: &'a str,
+    pub entity_id: Option<u64>,
+    /// e.g. `approve`, `reject`, `reset_password`
+    pub action: &'a str,
+    pub details: Option<Value>,
+}
+
+/// ===============================
+/// Audit trail
+/// ===============================
+/// Appends an entry to `audit_log`. Pass the handler's transaction so the entry
+/// is committed (or rolled back) together with the change it describes.
+pub async fn record<'e, E>(executor: E, entry: AuditEntry<'_>) -> actix_web::Result<()>
+where
+    E: Executor<'e, Database = MySql>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO audit_log (org_id, actor_user_id, entity, entity_id, action, details)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(entry.org_id)
+    .bind(entry.actor_user_id)
+    .bind(entry.entity)
+    .bind(entry.entity_id)
+    .bind(entry.action)
+    .bind(entry.details.map(|d| d.to_string()))
+    .execute(executor)
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, entity = entry.entity, action = entry.action, "Failed to write audit log");
+        actix_web::error::ErrorInternalServerError("Internal Server Error")
+    })?;
+
+    Ok(())
+}
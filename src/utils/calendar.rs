@@ -0,0 +1,127 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use moka::future::Cache;
+use once_cell::sync::Lazy;
+use sqlx::MySqlPool;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Company holidays per organization; dropped whenever HR adds one
+static HOLIDAY_CACHE: Lazy<Cache<u64, Arc<HashSet<NaiveDate>>>> = Lazy::new(|| {
+    Cache::builder()
+        .max_capacity(10_000)
+        .time_to_live(Duration::from_secs(3600)) // 1h TTL
+        .build()
+});
+
+/// All holidays of the organization, from the cache or the `holidays` table
+pub async fn holidays(pool: &MySqlPool, org_id: u64) -> actix_web::Result<Arc<HashSet<NaiveDate>>> {
+    if let Some(cached) = HOLIDAY_CACHE.get(&org_id).await {
+        return Ok(cached);
+    }
+
+    let dates = sqlx::query_scalar::<_, NaiveDate>("SELECT date FROM holidays WHERE org_id = ?")
+        .bind(org_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, org_id, "Failed to load holidays");
+            actix_web::error::ErrorInternalServerError("Internal Server Error")
+        })?;
+
+    let set = Arc::new(dates.into_iter().collect::<HashSet<_>>());
+    HOLIDAY_CACHE.insert(org_id, set.clone()).await;
+    Ok(set)
+}
+
+/// Forget the cached holidays of an organization after they change
+pub async fn invalidate(org_id: u64) {
+    HOLIDAY_CACHE.invalidate(&org_id).await;
+}
+
+/// Inclusive count of working days between two dates: Mon-Fri, minus `holidays`.
+/// 0 if `start` is after `end`.
+pub fn business_days_between(start: NaiveDate, end: NaiveDate, holidays: &HashSet<NaiveDate>) -> u32 {
+    start
+        .iter_days()
+        .take_while(|d| *d <= end)
+        .filter(|d| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+        .filter(|d| !holidays.contains(d))
+        .count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    // 2026-03-02 is a Monday
+
+    #[test]
+    fn full_week_counts_weekdays_only() {
+        // Mon 2026-03-02 .. Sun 2026-03-08
+        assert_eq!(
+            business_days_between(date(2026, 3, 2), date(2026, 3, 8), &HashSet::new()),
+            5
+        );
+    }
+
+    #[test]
+    fn weekend_only_range_is_zero() {
+        assert_eq!(
+            business_days_between(date(2026, 3, 7), date(2026, 3, 8), &HashSet::new()),
+            0
+        );
+    }
+
+    #[test]
+    fn holidays_are_skipped() {
+        let holidays = HashSet::from([date(2026, 3, 4), date(2026, 3, 7)]);
+
+        // the Saturday holiday isn't subtracted twice
+        assert_eq!(
+            business_days_between(date(2026, 3, 2), date(2026, 3, 8), &holidays),
+            4
+        );
+    }
+
+    #[test]
+    fn reversed_range_is_zero() {
+        assert_eq!(
+            business_days_between(date(2026, 3, 6), date(2026, 3, 2), &HashSet::new()),
+            0
+        );
+    }
+
+    #[test]
+    fn single_day() {
+        let none = HashSet::new();
+
+        assert_eq!(
+            business_days_between(date(2026, 3, 3), date(2026, 3, 3), &none),
+            1
+        );
+        assert_eq!(
+            business_days_between(date(2026, 3, 7), date(2026, 3, 7), &none),
+            0
+        );
+
+        let holiday = HashSet::from([date(2026, 3, 3)]);
+        assert_eq!(
+            business_days_between(date(2026, 3, 3), date(2026, 3, 3), &holiday),
+            0
+        );
+    }
+
+    #[test]
+    fn spans_months() {
+        // Mon 2026-03-30 .. Fri 2026-04-03
+        assert_eq!(
+            business_days_between(date(2026, 3, 30), date(2026, 4, 3), &HashSet::new()),
+            5
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use actix_web::HttpResponse;
+use actix_web::http::header::CONTENT_DISPOSITION;
+use actix_web::web::Bytes;
+use futures::channel::mpsc;
+use futures::{SinkExt, Stream, StreamExt};
+use sqlx::mysql::MySqlRow;
+use sqlx::{FromRow, MySqlPool};
+
+use crate::utils::db_utils::{SqlValue, bind_values};
+use crate::utils::report_limit::ReportPermit;
+
+/// Rows are buffered up to roughly this many bytes before a chunk is sent
+const CHUNK_BYTES: usize = 16 * 1024;
+
+/// Chunks in flight between the DB task and the response; bounds memory per export
+const CHANNEL_CAPACITY: usize = 4;
+
+/// ===============================
+/// Streaming CSV export
+/// ===============================
+/// Runs `sql` (binding `args`, then the row cap for its trailing `LIMIT ?`) and streams the rows as CSV without
+/// ever holding the full result in memory. At most `max_rows` rows are written;
+/// if there are more, a `# truncated` marker line is appended instead.
+///
+/// The query runs in its own task feeding a bounded channel. When the client
+/// disconnects the receiver is dropped, the next send fails, and the task ends,
+/// dropping the row stream and releasing the connection (and the report `permit`).
+#[allow(clippy::too_many_arguments)]
+pub fn stream_csv<T, F>(
+    permit: ReportPermit,
+    pool: MySqlPool,
+    filename: &str,
+    sql: String,
+    args: Vec<SqlValue>,
+    header: &'static [&'static str],
+    max_rows: u64,
+    to_record: F,
+) -> HttpResponse
+where
+    T: for<'r> FromRow<'r, MySqlRow> + Send + Unpin + 'static,
+    F: Fn(&T) -> Vec<String> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel::<Result<Bytes, std::io::Error>>(CHANNEL_CAPACITY);
+
+    actix_web::rt::spawn(async move {
+        let _permit = permit;
+        // one extra row tells us whether the cap was hit
+        let rows = bind_values(sqlx::query_as::<_, T>(&sql), args)
+            .bind(max_rows + 1)
+            .fetch(&pool);
+
+        write_csv(rows, tx, header, max_rows, to_record).await;
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header((
+            CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        ))
+        .streaming(rx)
+}
+
+/// Writes `rows` to `tx` as CSV in chunks of about `CHUNK_BYTES`, stopping
+/// after `max_rows` rows or as soon as the receiver is gone
+async fn write_csv<T, F>(
+    mut rows: impl Stream<Item = Result<T, sqlx::Error>> + Unpin,
+    mut tx: mpsc::Sender<Result<Bytes, std::io::Error>>,
+    header: &[&str],
+    max_rows: u64,
+    to_record: F,
+) where
+    F: Fn(&T) -> Vec<String>,
+{
+    let mut buf = csv_line(header.iter().copied());
+    let mut written: u64 = 0;
+
+    while let Some(row) = rows.next().await {
+        let row = match row {
+            Ok(row) => row,
+            Err(e) => {
+                tracing::error!(error = %e, "CSV export query failed");
+                // abort the response so the client doesn't mistake it for a complete file
+                let _ = tx.send(Err(std::io::Error::other("export failed"))).await;
+                return;
+            }
+        };
+
+        if written == max_rows {
+            buf.push_str(&format!(
+                "# truncated: export limited to {} rows\n",
+                max_rows
+            ));
+            break;
+        }
+
+        let record = to_record(&row);
+        buf.push_str(&csv_line(record.iter().map(String::as_str)));
+        written += 1;
+
+        if buf.len() >= CHUNK_BYTES
+            && tx
+                .send(Ok(Bytes::from(std::mem::take(&mut buf))))
+                .await
+                .is_err()
+        {
+            tracing::debug!(written, "CSV export client disconnected");
+            return;
+        }
+    }
+
+    if !buf.is_empty() {
+        let _ = tx.send(Ok(Bytes::from(buf))).await;
+    }
+}
+
+/// One CSV record (RFC 4180), terminated by `\n`
+fn csv_line<'a>(fields: impl Iterator<Item = &'a str>) -> String {
+    let mut line = fields.map(escape_field).collect::<Vec<_>>().join(",");
+    line.push('\n');
+    line
+}
+
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    const HEADER: &[&str] = &["id", "name"];
+
+    /// `count` rows, produced lazily; `pulled` counts the ones the writer asked for
+    fn rows(
+        count: u64,
+        pulled: Arc<AtomicU64>,
+    ) -> impl Stream<Item = Result<u64, sqlx::Error>> + Unpin {
+        futures::stream::iter(0..count).map(move |id| {
+            pulled.fetch_add(1, Ordering::SeqCst);
+            Ok(id)
+        })
+    }
+
+    fn record(id: &u64) -> Vec<String> {
+        vec![id.to_string(), format!("employee, {}", id)]
+    }
+
+    #[actix_web::test]
+    async fn large_export_is_sent_in_chunks() {
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let writer = actix_web::rt::spawn(write_csv(
+            rows(50_000, Arc::default()),
+            tx,
+            HEADER,
+            100_000,
+            record,
+        ));
+
+        let chunks: Vec<Bytes> = rx.map(Result::unwrap).collect().await;
+        writer.await.unwrap();
+
+        assert!(chunks.len() > 10, "{} chunk(s)", chunks.len());
+        // no chunk holds much more than CHUNK_BYTES
+        assert!(chunks.iter().all(|c| c.len() < CHUNK_BYTES + 64));
+
+        let csv = String::from_utf8(chunks.concat()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 50_001);
+        assert_eq!(lines[0], "id,name");
+        assert_eq!(lines[1], "0,\"employee, 0\"");
+        assert_eq!(lines[50_000], "49999,\"employee, 49999\"");
+    }
+
+    #[actix_web::test]
+    async fn rows_past_the_cap_are_truncated() {
+        let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let writer =
+            actix_web::rt::spawn(write_csv(rows(11, Arc::default()), tx, HEADER, 10, record));
+
+        let chunks: Vec<Bytes> = rx.map(Result::unwrap).collect().await;
+        writer.await.unwrap();
+
+        let csv = String::from_utf8(chunks.concat()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 12);
+        assert_eq!(lines[11], "# truncated: export limited to 10 rows");
+    }
+
+    #[actix_web::test]
+    async fn disconnect_stops_reading_rows() {
+        let pulled = Arc::new(AtomicU64::new(0));
+        let (tx, mut rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let writer = actix_web::rt::spawn(write_csv(
+            rows(1_000_000, pulled.clone()),
+            tx,
+            HEADER,
+            1_000_000,
+            record,
+        ));
+
+        rx.next().await.unwrap().unwrap();
+        drop(rx);
+        writer.await.unwrap();
+
+        // only the few chunks the bounded channel holds were ever read
+        let pulled = pulled.load(Ordering::SeqCst);
+        assert!(pulled < 20_000, "{} rows read", pulled);
+    }
+}
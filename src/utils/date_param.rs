@@ -0,0 +1,113 @@
+use actix_web::HttpResponse;
+use actix_web::error::InternalError;
+use actix_web::http::StatusCode;
+use chrono::NaiveDate;
+
+/// ===============================
+/// Date query parameters
+/// ===============================
+/// Parses an optional `YYYY-MM-DD` query value. Only the zero-padded ISO form is
+/// accepted (`2026-1-5` and `2026-01-05T00:00` are not), and a bad value yields
+/// 400 `{"error":"invalid_date","field":..,"value":..}` naming the parameter.
+pub fn parse(field: &str, value: Option<&str>) -> actix_web::Result<Option<NaiveDate>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+
+    let bytes = value.as_bytes();
+    let well_formed = bytes.len() == 10 && bytes[4] == b'-' && bytes[7] == b'-';
+
+    match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date) if well_formed => Ok(Some(date)),
+        _ => Err(invalid_date(field, value)),
+    }
+}
+
+/// Parses a `YYYY-MM` query value into the first day of that month, with the
+/// same 400 body as `parse` on a bad value.
+pub fn parse_month(field: &str, value: &str) -> actix_web::Result<NaiveDate> {
+    let bytes = value.as_bytes();
+    let well_formed = bytes.len() == 7 && bytes[4] == b'-';
+
+    match NaiveDate::parse_from_str(&format!("{}-01", value), "%Y-%m-%d") {
+        Ok(date) if well_formed => Ok(date),
+        _ => Err(invalid_date(field, value)),
+    }
+}
+
+fn invalid_date(field: &str, value: &str) -> actix_web::Error {
+    let response = HttpResponse::build(StatusCode::BAD_REQUEST).json(serde_json::json!({
+        "error": "invalid_date",
+        "field": field,
+        "value": value
+    }));
+    InternalError::from_response(format!("invalid date in {}", field), response).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+    use serde_json::{Value, json};
+
+    async fn error_body(err: actix_web::Error) -> (StatusCode, Value) {
+        let response = err.error_response();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[test]
+    fn valid_date_parses() {
+        assert_eq!(
+            parse("from", Some("2026-03-05")).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 3, 5)
+        );
+        assert_eq!(
+            parse("from", Some("2024-02-29")).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 2, 29)
+        );
+    }
+
+    #[test]
+    fn missing_date_is_none() {
+        assert_eq!(parse("from", None).unwrap(), None);
+    }
+
+    #[actix_web::test]
+    async fn malformed_date_is_400_naming_the_field() {
+        let (status, body) = error_body(parse("from", Some("05/03/2026")).unwrap_err()).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body,
+            json!({"error": "invalid_date", "field": "from", "value": "05/03/2026"})
+        );
+    }
+
+    #[test]
+    fn only_strict_iso_dates_are_accepted() {
+        for value in [
+            "2026-1-5",
+            "2026-01-05T00:00",
+            "2026-02-30",
+            "2025-02-29",
+            "",
+            "20260305",
+        ] {
+            assert!(parse("to", Some(value)).is_err(), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn month_parses_to_its_first_day() {
+        assert_eq!(
+            parse_month("month", "2026-03").unwrap(),
+            NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()
+        );
+
+        for value in ["2026-3", "2026-13", "2026-03-01", "march"] {
+            assert!(parse_month("month", value).is_err(), "{:?}", value);
+        }
+    }
+}
@@ -1,13 +1,15 @@
 use serde_json::Value;
 use chrono::{NaiveDate, NaiveDateTime};
-use actix_web::error::ErrorBadRequest;
-use sqlx::MySqlPool;
+use crate::utils::api_error::ApiError;
+use sqlx::mysql::MySqlArguments;
+use sqlx::query::QueryAs;
+use sqlx::{MySql, MySqlPool};
 
 
 /// ===============================
 /// SQL bindable value enum
 /// ===============================
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SqlValue {
     String(String),
     I64(i64),
@@ -32,18 +34,26 @@ pub struct SqlUpdate {
 /// ===============================
 /// Build dynamic UPDATE SQL
 /// ===============================
+/// Keys become column names in the SQL text, so only keys listed in
+/// `allowed_columns` are accepted; anything else is a 400.
 pub fn build_update_sql(
     table: &str,
     payload: &Value,
+    allowed_columns: &[&str],
     id_column: &str,
     id_value: i64,
-) -> Result<SqlUpdate, actix_web::Error> {
+) -> Result<SqlUpdate, ApiError> {
     let obj = payload
         .as_object()
-        .ok_or_else(|| ErrorBadRequest("Payload must be a JSON object"))?;
+        .ok_or_else(|| ApiError::bad_request("invalid_payload", "Payload must be a JSON object"))?;
 
     if obj.is_empty() {
-        return Err(ErrorBadRequest("No fields provided for update"));
+        return Err(ApiError::bad_request("invalid_payload", "No fields provided for update"));
+    }
+
+    if let Some(key) = obj.keys().find(|k| !allowed_columns.contains(&k.as_str())) {
+        return Err(ApiError::bad_request("unknown_field", format!("Unknown field '{}'", key))
+            .with_field(key.as_str()));
     }
 
     // Build SET clause
@@ -60,8 +70,8 @@ pub fn build_update_sql(
 
     let mut values = Vec::with_capacity(obj.len() + 1);
 
-    // Convert JSON values → SqlValue
-    for value in obj.values() {
+    // Convert JSON values → SqlValue (errors name the offending field)
+    for (key, value) in obj {
         match value {
             Value::String(s) => {
                 if let Ok(d) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
@@ -70,6 +80,12 @@ pub fn build_update_sql(
                     NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")
                 {
                     values.push(SqlValue::DateTime(dt));
+                } else if looks_like_date(s) {
+                    // e.g. "2026-02-30": meant as a date, but not a valid one
+                    return Err(ApiError::validation(key.as_str(), format!(
+                        "Field '{}': '{}' is not a valid date (expected YYYY-MM-DD or YYYY-MM-DDTHH:MM:SS)",
+                        key, s
+                    )));
                 } else {
                     values.push(SqlValue::String(s.clone()));
                 }
@@ -79,11 +95,27 @@ pub fn build_update_sql(
                     values.push(SqlValue::I64(i));
                 } else if let Some(f) = n.as_f64() {
                     values.push(SqlValue::F64(f));
+                } else {
+                    return Err(ApiError::validation(key.as_str(), format!(
+                        "Field '{}': number {} is out of range",
+                        key, n
+                    )));
                 }
             }
             Value::Bool(b) => values.push(SqlValue::Bool(*b)),
             Value::Null => values.push(SqlValue::Null),
-            _ => return Err(ErrorBadRequest("Unsupported JSON value type")),
+            Value::Array(_) => {
+                return Err(ApiError::validation(key.as_str(), format!(
+                    "Field '{}': arrays are not supported",
+                    key
+                )));
+            }
+            Value::Object(_) => {
+                return Err(ApiError::validation(key.as_str(), format!(
+                    "Field '{}': nested objects are not supported",
+                    key
+                )));
+            }
         }
     }
 
@@ -94,6 +126,18 @@ pub fn build_update_sql(
 }
 
 
+/// `YYYY-MM-DD` prefix, i.e. the value was clearly meant to be a date
+fn looks_like_date(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() >= 10
+        && b[..4].iter().all(u8::is_ascii_digit)
+        && b[4] == b'-'
+        && b[5..7].iter().all(u8::is_ascii_digit)
+        && b[7] == b'-'
+        && b[8..10].iter().all(u8::is_ascii_digit)
+}
+
+
 /// ===============================
 /// Execute the update
 /// ===============================
@@ -118,3 +162,85 @@ pub async fn execute_update(
     let result = query.execute(pool).await?;
     Ok(result.rows_affected())
 }
+
+
+/// ===============================
+/// Bind dynamic filter values
+/// ===============================
+pub fn bind_values<'q, O>(
+    mut query: QueryAs<'q, MySql, O, MySqlArguments>,
+    values: Vec<SqlValue>,
+) -> QueryAs<'q, MySql, O, MySqlArguments> {
+    for value in values {
+        query = match value {
+            SqlValue::String(v) => query.bind(v),
+            SqlValue::I64(v) => query.bind(v),
+            SqlValue::F64(v) => query.bind(v),
+            SqlValue::Bool(v) => query.bind(v),
+            SqlValue::Date(v) => query.bind(v),
+            SqlValue::DateTime(v) => query.bind(v),
+            SqlValue::Null => query.bind(None::<String>),
+        };
+    }
+    query
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{ResponseError, http::StatusCode};
+    use serde_json::json;
+
+    const ALLOWED: &[&str] = &["first_name", "hire_date"];
+
+    fn rejected_field(payload: Value) -> Option<String> {
+        let err = build_update_sql("employees", &payload, ALLOWED, "id", 1).unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(err.code, "unknown_field");
+        err.field
+    }
+
+    #[test]
+    fn crafted_key_is_rejected() {
+        let key = "first_name = 'x', base_salary = 0 WHERE 1 = 1; --";
+
+        assert_eq!(rejected_field(json!({ key: "x" })), Some(key.to_string()));
+    }
+
+    #[test]
+    fn unknown_key_is_rejected_even_next_to_allowed_ones() {
+        assert_eq!(
+            rejected_field(json!({"first_name": "Ada", "id": 2})),
+            Some("id".to_string())
+        );
+    }
+
+    #[test]
+    fn allowed_keys_become_placeholders() {
+        let update =
+            build_update_sql("employees", &json!({"first_name": "Ada"}), ALLOWED, "id", 7).unwrap();
+
+        assert_eq!(
+            update.sql,
+            "UPDATE employees SET first_name = ? WHERE id = ?"
+        );
+        assert!(
+            matches!(update.values.as_slice(), [SqlValue::String(s), SqlValue::I64(7)] if s == "Ada")
+        );
+    }
+
+    #[test]
+    fn invalid_date_names_the_field() {
+        let err = build_update_sql(
+            "employees",
+            &json!({"hire_date": "2026-02-30"}),
+            ALLOWED,
+            "id",
+            1,
+        )
+        .unwrap_err();
+
+        assert_eq!(err.code, "validation_error");
+        assert_eq!(err.field.as_deref(), Some("hire_date"));
+    }
+}
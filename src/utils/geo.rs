@@ -0,0 +1,125 @@
+/// Mean Earth radius in metres
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Great-circle distance in metres between two WGS84 points (haversine formula)
+pub fn distance_m(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lng2 - lng1).to_radians();
+
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+}
+
+/// Circle check-ins must fall into
+#[derive(Clone, Copy, Debug)]
+pub struct Geofence {
+    pub lat: f64,
+    pub lng: f64,
+    pub radius_m: f64,
+}
+
+impl Geofence {
+    /// Parses `lat,lng,radius_m`, e.g. `23.8103,90.4125,200`
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut parts = spec.split(',').map(|p| p.trim().parse::<f64>());
+        let (lat, lng, radius_m) = (parts.next()?.ok()?, parts.next()?.ok()?, parts.next()?.ok()?);
+        if parts.next().is_some() || !is_valid_point(lat, lng) || radius_m <= 0.0 {
+            return None;
+        }
+        Some(Self { lat, lng, radius_m })
+    }
+
+    pub fn contains(&self, lat: f64, lng: f64) -> bool {
+        distance_m(self.lat, self.lng, lat, lng) <= self.radius_m
+    }
+}
+
+pub fn is_valid_point(lat: f64, lng: f64) -> bool {
+    (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Latitude offset in degrees of a point `metres` due north
+    fn north(metres: f64) -> f64 {
+        (metres / EARTH_RADIUS_M).to_degrees()
+    }
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{} != {} ± {}",
+            actual,
+            expected,
+            tolerance
+        );
+    }
+
+    #[test]
+    fn same_point_is_zero() {
+        assert_eq!(distance_m(23.8103, 90.4125, 23.8103, 90.4125), 0.0);
+    }
+
+    #[test]
+    fn one_degree_of_latitude() {
+        assert_close(distance_m(0.0, 0.0, 1.0, 0.0), 111_194.93, 0.01);
+    }
+
+    #[test]
+    fn london_to_paris() {
+        assert_close(
+            distance_m(51.5074, -0.1278, 48.8566, 2.3522),
+            343_556.06,
+            0.01,
+        );
+        // symmetric
+        assert_close(
+            distance_m(48.8566, 2.3522, 51.5074, -0.1278),
+            343_556.06,
+            0.01,
+        );
+    }
+
+    #[test]
+    fn across_the_antimeridian() {
+        assert_close(distance_m(0.0, 179.9, 0.0, -179.9), 22_238.99, 0.01);
+    }
+
+    #[test]
+    fn geofence_boundary() {
+        let fence = Geofence {
+            lat: 23.8103,
+            lng: 90.4125,
+            radius_m: 200.0,
+        };
+
+        assert!(fence.contains(fence.lat, fence.lng));
+        assert!(fence.contains(fence.lat + north(199.0), fence.lng));
+        assert!(!fence.contains(fence.lat + north(201.0), fence.lng));
+        assert!(!fence.contains(fence.lat - north(201.0), fence.lng));
+    }
+
+    #[test]
+    fn parse_geofence() {
+        let fence = Geofence::parse("23.8103, 90.4125, 200").unwrap();
+        assert_eq!(
+            (fence.lat, fence.lng, fence.radius_m),
+            (23.8103, 90.4125, 200.0)
+        );
+
+        for bad in [
+            "",
+            "23.8,90.4",
+            "23.8,90.4,200,1",
+            "91,90.4,200",
+            "23.8,181,200",
+            "23.8,90.4,0",
+            "a,b,c",
+        ] {
+            assert!(Geofence::parse(bad).is_none(), "{:?}", bad);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use actix_web::{HttpRequest, error::JsonPayloadError, http::StatusCode, web};
+
+use crate::utils::api_error::ApiError;
+
+/// `web::Json` settings for the whole app: bodies above `max_bytes` get 413, and every
+/// other extraction failure an `ApiError` body instead of actix's plain-text message
+pub fn config(max_bytes: usize) -> web::JsonConfig {
+    web::JsonConfig::default()
+        .limit(max_bytes)
+        .error_handler(|err, _req: &HttpRequest| json_error(err).into())
+}
+
+fn json_error(err: JsonPayloadError) -> ApiError {
+    match err {
+        JsonPayloadError::Overflow { limit } | JsonPayloadError::OverflowKnownLength { limit, .. } => {
+            ApiError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+                format!("JSON body must be at most {} bytes", limit),
+            )
+        }
+        JsonPayloadError::ContentType => ApiError::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "unsupported_media_type",
+            "Content-Type must be application/json",
+        ),
+        JsonPayloadError::Deserialize(e) => {
+            let message = e.to_string();
+            match failed_field(&message).map(str::to_string) {
+                Some(field) => ApiError::validation(field, message),
+                None => ApiError::bad_request("invalid_json", message),
+            }
+        }
+        other => ApiError::bad_request("invalid_json", other.to_string()),
+    }
+}
+
+/// serde only names the field for missing and unknown fields, in backticks:
+/// "missing field `email` at line 1 column 42"
+fn failed_field(message: &str) -> Option<&str> {
+    if !(message.starts_with("missing field") || message.starts_with("unknown field")) {
+        return None;
+    }
+
+    message.split('`').nth(1).filter(|field| !field.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{TestRequest, call_service, init_service, read_body_json};
+    use actix_web::{App, HttpResponse};
+    use serde::Deserialize;
+    use serde_json::Value;
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Body {
+        email: String,
+    }
+
+    async fn accept(_body: web::Json<Body>) -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    async fn post(payload: &str) -> (StatusCode, Value) {
+        let app = init_service(
+            App::new()
+                .app_data(config(64))
+                .route("/", web::post().to(accept)),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .uri("/")
+            .insert_header(("Content-Type", "application/json"))
+            .set_payload(payload.to_string())
+            .to_request();
+        let res = call_service(&app, req).await;
+        let status = res.status();
+        (status, read_body_json(res).await)
+    }
+
+    #[actix_web::test]
+    async fn oversized_body_is_413() {
+        let payload = format!(r#"{{"email": "{}"}}"#, "a".repeat(100));
+        let (status, body) = post(&payload).await;
+
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body["code"], "payload_too_large");
+    }
+
+    #[actix_web::test]
+    async fn malformed_json_is_400() {
+        let (status, body) = post(r#"{"email": "#).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "invalid_json");
+        assert!(body.get("field").is_none());
+    }
+
+    #[actix_web::test]
+    async fn missing_field_is_named() {
+        let (status, body) = post("{}").await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "validation_error");
+        assert_eq!(body["field"], "email");
+    }
+
+    #[test]
+    fn failed_field_only_for_field_errors() {
+        assert_eq!(
+            failed_field("missing field `email` at line 1 column 2"),
+            Some("email")
+        );
+        assert_eq!(
+            failed_field("unknown field `foo`, expected `email`"),
+            Some("foo")
+        );
+        assert_eq!(failed_field("unknown variant `x`, expected `a`"), None);
+        assert_eq!(failed_field("EOF while parsing a value"), None);
+    }
+}
@@ -1,3 +1,22 @@
 pub mod username_filter;
 pub mod username_cache;
-pub mod db_utils;
\ No newline at end of file
+pub mod db_utils;
+pub mod pagination;
+pub mod notifier;
+pub mod log_redact;
+pub mod pii;
+pub mod precondition;
+pub mod csv_export;
+pub mod photo;
+pub mod omit_null;
+pub mod date_param;
+pub mod report_limit;
+pub mod audit;
+pub mod sort;
+pub mod calendar;
+pub mod geo;
+pub mod email;
+pub mod phone;
+pub mod payslip_pdf;
+pub mod api_error;
+pub mod json_body;
\ No newline at end of file
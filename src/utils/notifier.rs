@@ -0,0 +1,228 @@
+//! Webhook deliveries are signed when `NOTIFY_WEBHOOK_SECRET` is set:
+//!
+//! ```text
+//! X-Signature-Timestamp: 1767225600
+//! X-Signature: sha256=<hex HMAC-SHA256(secret, "<timestamp>.<raw body>")>
+//! ```
+//!
+//! To verify, a receiver recomputes the HMAC over the timestamp header, a `.` and the raw
+//! request body (before any JSON parsing), compares it to `X-Signature` in constant time,
+//! and rejects timestamps more than a few minutes old so captured requests can't be replayed.
+
+use actix_web::http::header::CONTENT_TYPE;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Sha256;
+use tokio::sync::mpsc::{self, Sender, error::TrySendError};
+
+/// Notifications waiting for the webhook; when full, new ones are refused rather than
+/// piling up behind a slow or unreachable receiver
+const QUEUE_CAPACITY: usize = 1000;
+
+/// A notification waiting to be delivered (email relay / webhook)
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    /// event name, e.g. `leave.submitted`
+    pub event: &'static str,
+    /// recipient email address
+    pub recipient: String,
+    /// user or employee id of the recipient, logged instead of the address
+    pub recipient_id: u64,
+    /// delivered but never logged: it can carry secrets such as reset tokens
+    pub payload: Value,
+}
+
+/// Where handlers hand notifications over; registered as `web::Data<dyn Notifier>`
+/// so tests can put a recorder in its place
+pub trait Notifier: Send + Sync {
+    /// Whether notifications can be delivered at all
+    fn is_available(&self) -> bool;
+
+    /// Queue a notification for delivery; false if it was refused
+    fn notify(&self, notification: Notification) -> bool;
+}
+
+/// `sha256=<hex>` signature of `<timestamp>.<body>`
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Delivers through a background worker POSTing every notification as JSON to the
+/// webhook URL, signed when a secret is set (see the module docs).
+///
+/// The queue lives in memory: whatever is still queued is lost on restart, and a full
+/// queue refuses new notifications. Callers treat delivery as best effort.
+pub struct WebhookNotifier {
+    /// None without a webhook URL: there is nowhere to deliver to
+    queue: Option<Sender<Notification>>,
+}
+
+impl WebhookNotifier {
+    /// Start the delivery worker; without a webhook URL every notification is refused
+    pub fn start(webhook_url: Option<String>, webhook_secret: Option<String>) -> Self {
+        let Some(url) = webhook_url else {
+            tracing::error!(
+                "NOTIFY_WEBHOOK_URL is not set: notifications (password resets, leave requests) can't be delivered"
+            );
+            return WebhookNotifier { queue: None };
+        };
+
+        let (tx, mut rx) = mpsc::channel::<Notification>(QUEUE_CAPACITY);
+
+        actix_web::rt::spawn(async move {
+            let client = awc::Client::default();
+
+            while let Some(notification) = rx.recv().await {
+                // signed bytes must be exactly the bytes sent
+                let body = serde_json::to_vec(&notification).unwrap_or_default();
+                let mut request = client
+                    .post(&url)
+                    .insert_header((CONTENT_TYPE, "application/json"));
+                if let Some(secret) = &webhook_secret {
+                    let timestamp = chrono::Utc::now().timestamp();
+                    request = request
+                        .insert_header(("X-Signature-Timestamp", timestamp.to_string()))
+                        .insert_header(("X-Signature", sign(secret, timestamp, &body)));
+                }
+
+                if let Err(e) = request.send_body(body).await {
+                    tracing::error!(
+                        error = %e,
+                        event = notification.event,
+                        recipient_id = notification.recipient_id,
+                        "Failed to deliver notification"
+                    );
+                }
+            }
+        });
+
+        WebhookNotifier { queue: Some(tx) }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn is_available(&self) -> bool {
+        self.queue.is_some()
+    }
+
+    fn notify(&self, notification: Notification) -> bool {
+        let (event, recipient_id) = (notification.event, notification.recipient_id);
+        let Some(queue) = &self.queue else {
+            tracing::error!(
+                event,
+                recipient_id,
+                "No notification delivery channel, dropping"
+            );
+            return false;
+        };
+
+        match queue.try_send(notification) {
+            Ok(()) => true,
+            Err(e) => {
+                let reason = match e {
+                    TrySendError::Full(_) => "queue full",
+                    TrySendError::Closed(_) => "worker stopped",
+                };
+                tracing::error!(event, recipient_id, reason, "Notification refused");
+                false
+            }
+        }
+    }
+}
+
+/// Test double keeping every notification it is handed
+#[cfg(test)]
+#[derive(Default)]
+pub struct RecordingNotifier {
+    pub sent: std::sync::Mutex<Vec<Notification>>,
+}
+
+#[cfg(test)]
+impl Notifier for RecordingNotifier {
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn notify(&self, notification: Notification) -> bool {
+        self.sent.lock().unwrap().push(notification);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // expected values computed independently:
+    // python3 -c 'import hmac,hashlib; print(hmac.new(b"webhook-secret", b"<ts>.<body>", hashlib.sha256).hexdigest())'
+    #[test]
+    fn signature_matches_known_vector() {
+        assert_eq!(
+            sign(
+                "webhook-secret",
+                1_700_000_000,
+                br#"{"event":"leave_submitted"}"#
+            ),
+            "sha256=25677147f1be8999ba5f02ebe3681f791fc7de891302a38d12ea46143da62577"
+        );
+    }
+
+    #[test]
+    fn empty_body_still_signs_the_timestamp() {
+        assert_eq!(
+            sign("webhook-secret", 1_700_000_000, b""),
+            "sha256=6f81b97e6b0e3fe227d6c62fef0ca2027bd038656e793d47cba551e6e8ff6a5b"
+        );
+    }
+
+    #[test]
+    fn timestamp_and_secret_change_the_signature() {
+        let body = br#"{"event":"leave_submitted"}"#;
+        let signature = sign("webhook-secret", 1_700_000_000, body);
+
+        assert_ne!(sign("webhook-secret", 1_700_000_001, body), signature);
+        assert_ne!(sign("other-secret", 1_700_000_000, body), signature);
+    }
+
+    #[test]
+    fn without_a_webhook_everything_is_refused() {
+        let notifier = WebhookNotifier::start(None, None);
+
+        assert!(!notifier.is_available());
+        assert!(!notifier.notify(notification()));
+    }
+
+    #[test]
+    fn a_full_queue_refuses_instead_of_growing() {
+        let (tx, _rx) = mpsc::channel(2);
+        let notifier = WebhookNotifier { queue: Some(tx) };
+
+        assert!(notifier.notify(notification()));
+        assert!(notifier.notify(notification()));
+        assert!(!notifier.notify(notification()));
+    }
+
+    #[test]
+    fn a_stopped_worker_refuses() {
+        let (tx, rx) = mpsc::channel(2);
+        drop(rx);
+        let notifier = WebhookNotifier { queue: Some(tx) };
+
+        assert!(!notifier.notify(notification()));
+    }
+
+    fn notification() -> Notification {
+        Notification {
+            event: "leave.submitted",
+            recipient: "boss@example.com".to_string(),
+            recipient_id: 7,
+            payload: serde_json::json!({"leave_id": 1}),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set once at startup from `OMIT_NULL_FIELDS`; read by `skip` below
+static OMIT_NULL: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    OMIT_NULL.store(enabled, Ordering::Relaxed);
+}
+
+/// serde `skip_serializing_if` helper for optional response fields:
+/// `None` is left out of the JSON when `OMIT_NULL_FIELDS` is on, `null` otherwise.
+/// Only put it on `Option` fields; required fields are always emitted.
+pub fn skip<T>(value: &Option<T>) -> bool {
+    value.is_none() && OMIT_NULL.load(Ordering::Relaxed)
+}
@@ -0,0 +1,121 @@
+use actix_web::HttpRequest;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Body of the `/count` endpoints: the `total` the matching list call would report
+#[derive(Serialize, ToSchema)]
+pub struct CountResponse {
+    #[schema(example = 42)]
+    pub count: i64,
+}
+
+/// ===============================
+/// RFC 5988 `Link` header builder
+/// ===============================
+/// Produces `first`, `prev`, `next` and `last` links for a paginated list.
+/// `prev` is omitted on the first page and `next` on the last one.
+/// All other query parameters of the request (filters, sorting) are preserved.
+pub fn link_header(
+    base_url: &str,
+    req: &HttpRequest,
+    page: u64,
+    per_page: u64,
+    total: i64,
+) -> String {
+    let per_page = per_page.max(1);
+    let last = (total.max(0) as u64).div_ceil(per_page).max(1);
+
+    // keep every query param except the pagination ones
+    let passthrough: Vec<&str> = req
+        .query_string()
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter(|pair| {
+            let key = pair.split('=').next().unwrap_or("");
+            key != "page" && key != "per_page"
+        })
+        .collect();
+
+    let url = |p: u64| {
+        let mut query = passthrough.join("&");
+        if !query.is_empty() {
+            query.push('&');
+        }
+        format!(
+            "{}{}?{}page={}&per_page={}",
+            base_url.trim_end_matches('/'),
+            req.path(),
+            query,
+            p,
+            per_page
+        )
+    };
+
+    let mut links = vec![format!("<{}>; rel=\"first\"", url(1))];
+
+    if page > 1 {
+        links.push(format!("<{}>; rel=\"prev\"", url((page - 1).min(last))));
+    }
+
+    if page < last {
+        links.push(format!("<{}>; rel=\"next\"", url(page + 1)));
+    }
+
+    links.push(format!("<{}>; rel=\"last\"", url(last)));
+
+    links.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    const BASE: &str = "https://hrm.example.com/";
+
+    fn links(uri: &str, page: u64, per_page: u64, total: i64) -> String {
+        let req = TestRequest::get().uri(uri).to_http_request();
+        link_header(BASE, &req, page, per_page, total)
+    }
+
+    #[test]
+    fn last_page_has_no_next() {
+        let header = links("/api/v1/employee?page=3&per_page=10", 3, 10, 25);
+
+        assert!(!header.contains("rel=\"next\""), "{}", header);
+        assert!(header.contains(
+            "<https://hrm.example.com/api/v1/employee?page=2&per_page=10>; rel=\"prev\""
+        ));
+        assert!(header.contains(
+            "<https://hrm.example.com/api/v1/employee?page=3&per_page=10>; rel=\"last\""
+        ));
+    }
+
+    #[test]
+    fn first_page_has_next_but_no_prev() {
+        let header = links("/api/v1/employee", 1, 10, 25);
+
+        assert_eq!(
+            header,
+            "<https://hrm.example.com/api/v1/employee?page=1&per_page=10>; rel=\"first\", \
+             <https://hrm.example.com/api/v1/employee?page=2&per_page=10>; rel=\"next\", \
+             <https://hrm.example.com/api/v1/employee?page=3&per_page=10>; rel=\"last\""
+        );
+    }
+
+    #[test]
+    fn single_page_has_neither() {
+        let header = links("/api/v1/employee", 1, 10, 0);
+
+        assert!(!header.contains("rel=\"next\""));
+        assert!(!header.contains("rel=\"prev\""));
+        assert!(header.contains("page=1&per_page=10>; rel=\"last\""));
+    }
+
+    #[test]
+    fn filters_are_kept() {
+        let header = links("/api/v1/leave?status=pending&page=1&per_page=5", 1, 5, 12);
+
+        assert!(header.contains("/api/v1/leave?status=pending&page=2&per_page=5>; rel=\"next\""));
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::NaiveDate;
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfLayerReference};
+
+pub const PDF_CONTENT_TYPE: &str = "application/pdf";
+
+/// What goes on a payslip
+pub struct Payslip<'a> {
+    pub employee_name: &'a str,
+    pub employee_code: &'a str,
+    pub month: NaiveDate,
+    pub base_salary: f64,
+    pub bonus: f64,
+    pub deductions: f64,
+    pub tax: f64,
+    pub net_salary: f64,
+    pub status: &'a str,
+}
+
+/// ===============================
+/// Payslip PDF
+/// ===============================
+/// One A4 page with the built-in Helvetica fonts, so nothing has to be embedded.
+pub fn render(payslip: &Payslip) -> Result<Vec<u8>, printpdf::Error> {
+    let (doc, page, layer) = PdfDocument::new("Payslip", Mm(210.0), Mm(297.0), "Payslip");
+    let regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+    let layer = doc.get_page(page).get_layer(layer);
+
+    layer.use_text("Payslip", 22.0, Mm(20.0), Mm(270.0), &bold);
+    layer.use_text(
+        payslip.month.format("%B %Y").to_string(),
+        13.0,
+        Mm(20.0),
+        Mm(261.0),
+        &regular,
+    );
+
+    row(&layer, &regular, 240.0, "Employee", payslip.employee_name);
+    row(&layer, &regular, 232.0, "Employee code", payslip.employee_code);
+    row(&layer, &regular, 224.0, "Status", payslip.status);
+
+    row(&layer, &regular, 204.0, "Base salary", &money(payslip.base_salary));
+    row(&layer, &regular, 196.0, "Bonus", &money(payslip.bonus));
+    row(&layer, &regular, 188.0, "Tax", &format!("-{}", money(payslip.tax)));
+    row(&layer, &regular, 180.0, "Deductions", &format!("-{}", money(payslip.deductions)));
+    row(&layer, &bold, 166.0, "Net salary", &money(payslip.net_salary));
+
+    doc.save_to_bytes()
+}
+
+fn row(layer: &PdfLayerReference, font: &IndirectFontRef, y: f32, label: &str, value: &str) {
+    layer.use_text(label, 11.0, Mm(20.0), Mm(y), font);
+    layer.use_text(value, 11.0, Mm(90.0), Mm(y), font);
+}
+
+fn money(amount: f64) -> String {
+    format!("{:.2}", amount)
+}
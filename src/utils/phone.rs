@@ -0,0 +1,7 @@
+/// Digits with an optional leading `+`, 7 to 15 digits long (the E.164 maximum).
+/// Separators are not accepted so stored numbers stay in one comparable form.
+pub fn is_valid_phone(phone: &str) -> bool {
+    let digits = phone.strip_prefix('+').unwrap_or(phone);
+
+    (7..=15).contains(&digits.len()) && digits.bytes().all(|b| b.is_ascii_digit())
+}
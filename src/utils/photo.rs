@@ -0,0 +1,91 @@
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageDecoder, ImageReader, Limits};
+
+/// Stored photos are always JPEG
+pub const PHOTO_CONTENT_TYPE: &str = "image/jpeg";
+
+const JPEG_QUALITY: u8 = 85;
+
+/// Refuse to even allocate for images claiming to be larger than this
+const MAX_SOURCE_DIMENSION: u32 = 10_000;
+
+/// ===============================
+/// Employee photo normalization
+/// ===============================
+/// Decodes the upload (sniffing the real format, ignoring the declared content type),
+/// applies the EXIF orientation, shrinks it to fit `max_dimension` and re-encodes it
+/// as JPEG. Re-encoding drops EXIF and any other embedded metadata.
+///
+/// CPU heavy: call it from `web::block`.
+pub fn normalize(bytes: &[u8], max_dimension: u32) -> Result<Vec<u8>, String> {
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder().map_err(|e| e.to_string())?;
+    let orientation = decoder.orientation().map_err(|e| e.to_string())?;
+
+    let mut img = DynamicImage::from_decoder(decoder).map_err(|e| e.to_string())?;
+    img.apply_orientation(orientation);
+
+    if img.width() > max_dimension || img.height() > max_dimension {
+        img = img.resize(
+            max_dimension,
+            max_dimension,
+            image::imageops::FilterType::Lanczos3,
+        );
+    }
+
+    let mut out = Vec::new();
+    JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY)
+        .encode_image(&img.to_rgb8())
+        .map_err(|e| e.to_string())?;
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, RgbImage};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut out = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(RgbImage::new(width, height))
+            .write_to(&mut out, ImageFormat::Png)
+            .unwrap();
+        out.into_inner()
+    }
+
+    fn decoded(jpeg: &[u8]) -> DynamicImage {
+        assert_eq!(image::guess_format(jpeg).unwrap(), ImageFormat::Jpeg);
+        image::load_from_memory(jpeg).unwrap()
+    }
+
+    #[test]
+    fn oversized_image_is_shrunk_keeping_its_aspect_ratio() {
+        let photo = decoded(&normalize(&png(1200, 600), 300).unwrap());
+
+        assert_eq!((photo.width(), photo.height()), (300, 150));
+    }
+
+    #[test]
+    fn small_image_keeps_its_size() {
+        let photo = decoded(&normalize(&png(120, 80), 300).unwrap());
+
+        assert_eq!((photo.width(), photo.height()), (120, 80));
+    }
+
+    #[test]
+    fn non_image_is_an_error() {
+        assert!(normalize(b"%PDF-1.7 not a photo", 300).is_err());
+        assert!(normalize(b"", 300).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use serde::Serializer;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set once at startup from `MASK_PII`; read by the `serialize_with` helpers below
+static MASK_PII: AtomicBool = AtomicBool::new(false);
+
+pub fn set_masking(enabled: bool) {
+    MASK_PII.store(enabled, Ordering::Relaxed);
+}
+
+fn enabled() -> bool {
+    MASK_PII.load(Ordering::Relaxed)
+}
+
+/// "John" → "J***"
+fn mask_keep_first(value: &str) -> String {
+    match value.chars().next() {
+        Some(c) => format!("{}***", c),
+        None => String::new(),
+    }
+}
+
+/// "john.doe@company.com" → "j***@***.com"
+fn mask_email_value(value: &str) -> String {
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            let tld = domain.rsplit_once('.').map(|(_, tld)| tld).unwrap_or("");
+            format!("{}@***.{}", mask_keep_first(local), tld)
+        }
+        None => mask_keep_first(value),
+    }
+}
+
+/// "+8801712345678" → "***78"
+fn mask_phone_value(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    let tail: String = chars[chars.len().saturating_sub(2)..].iter().collect();
+    format!("***{}", tail)
+}
+
+/* =========================
+serde `serialize_with` helpers
+========================= */
+
+pub fn name<S: Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    if enabled() {
+        serializer.serialize_str(&mask_keep_first(value))
+    } else {
+        serializer.serialize_str(value)
+    }
+}
+
+pub fn opt_name<S: Serializer>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(v) => name(v, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn email<S: Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    if enabled() {
+        serializer.serialize_str(&mask_email_value(value))
+    } else {
+        serializer.serialize_str(value)
+    }
+}
+
+pub fn opt_phone<S: Serializer>(value: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(v) if enabled() => serializer.serialize_str(&mask_phone_value(v)),
+        Some(v) => serializer.serialize_str(v),
+        None => serializer.serialize_none(),
+    }
+}
+
+/* =========================
+Plain value helpers (for output that doesn't go through serde, e.g. CSV exports)
+========================= */
+
+pub fn mask_name(value: &str) -> String {
+    if enabled() { mask_keep_first(value) } else { value.to_string() }
+}
+
+pub fn mask_email(value: &str) -> String {
+    if enabled() { mask_email_value(value) } else { value.to_string() }
+}
+
+pub fn mask_phone(value: &str) -> String {
+    if enabled() { mask_phone_value(value) } else { value.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::employee::{Employee, EmployeeNames};
+    use serde_json::json;
+
+    fn employee() -> Employee {
+        Employee {
+            id: 1,
+            employee_code: "EMP-001".to_string(),
+            first_name: "John".to_string(),
+            last_name: "Doe".to_string(),
+            email: "john.doe@company.com".to_string(),
+            phone: Some("+8801712345678".to_string()),
+            department_id: 10,
+            job_title_id: 3,
+            hire_date: chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            status: "active".to_string(),
+            names: EmployeeNames::default(),
+        }
+    }
+
+    fn pii_fields(employee: &Employee) -> serde_json::Value {
+        let value = serde_json::to_value(employee).unwrap();
+        json!([
+            value["first_name"],
+            value["last_name"],
+            value["email"],
+            value["phone"]
+        ])
+    }
+
+    // one test for both states: the switch is process-wide
+    #[test]
+    fn employee_output_is_masked_only_when_enabled() {
+        assert_eq!(
+            pii_fields(&employee()),
+            json!(["John", "Doe", "john.doe@company.com", "+8801712345678"])
+        );
+
+        set_masking(true);
+        let masked = pii_fields(&employee());
+        let csv_email = mask_email("john.doe@company.com");
+        set_masking(false);
+
+        assert_eq!(masked, json!(["J***", "D***", "j***@***.com", "***78"]));
+        assert_eq!(csv_email, "j***@***.com");
+        assert_eq!(mask_email("john.doe@company.com"), "john.doe@company.com");
+    }
+}
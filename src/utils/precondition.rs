@@ -0,0 +1,73 @@
+use std::time::SystemTime;
+
+use actix_web::HttpRequest;
+use actix_web::http::StatusCode;
+use actix_web::http::header::{Header, IfUnmodifiedSince};
+use chrono::{DateTime, Utc};
+
+use crate::utils::api_error::ApiError;
+
+/// ===============================
+/// `If-Unmodified-Since` support
+/// ===============================
+/// Returns the header's timestamp, or `None` when it is absent.
+/// An unparsable date is ignored, as RFC 9110 requires.
+pub fn if_unmodified_since(req: &HttpRequest) -> Option<DateTime<Utc>> {
+    IfUnmodifiedSince::parse(req)
+        .ok()
+        .map(|h| DateTime::<Utc>::from(SystemTime::from(h.0)))
+}
+
+/// 412 Precondition Failed if the row's `updated_at` is later than `since`.
+/// HTTP dates only have one-second resolution, so sub-second differences are ignored.
+pub fn ensure_unmodified(
+    since: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+) -> actix_web::Result<()> {
+    match (since, updated_at) {
+        (Some(since), Some(updated_at)) if updated_at.timestamp() > since.timestamp() => {
+            Err(precondition_failed())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// JSON 412 error, e.g. when a guarded statement matched no rows
+pub fn precondition_failed() -> actix_web::Error {
+    ApiError::new(
+        StatusCode::PRECONDITION_FAILED,
+        "precondition_failed",
+        "Resource was modified after If-Unmodified-Since",
+    )
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn at(secs: i64, millis: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(secs, 0).unwrap() + Duration::milliseconds(millis)
+    }
+
+    #[test]
+    fn later_change_is_412() {
+        let err = ensure_unmodified(Some(at(1_000, 0)), Some(at(1_001, 0))).unwrap_err();
+
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::PRECONDITION_FAILED
+        );
+    }
+
+    #[test]
+    fn unchanged_or_unguarded_rows_pass() {
+        assert!(ensure_unmodified(Some(at(1_000, 0)), Some(at(1_000, 0))).is_ok());
+        assert!(ensure_unmodified(Some(at(1_000, 0)), Some(at(999, 0))).is_ok());
+        // HTTP dates drop the milliseconds the row still has
+        assert!(ensure_unmodified(Some(at(1_000, 0)), Some(at(1_000, 900))).is_ok());
+        assert!(ensure_unmodified(None, Some(at(1_001, 0))).is_ok());
+        assert!(ensure_unmodified(Some(at(1_000, 0)), None).is_ok());
+    }
+}
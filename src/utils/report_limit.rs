@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use actix_web::HttpResponse;
+use actix_web::error::InternalError;
+use actix_web::http::StatusCode;
+use once_cell::sync::OnceCell;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Sized once at startup from `REPORT_MAX_CONCURRENT`
+static REPORTS: OnceCell<ReportLimit> = OnceCell::new();
+
+pub fn init(max_concurrent: usize) {
+    let _ = REPORTS.set(ReportLimit::new(max_concurrent));
+}
+
+/// A report slot; released when dropped. Exports move it into their streaming
+/// task so the slot stays taken until the last chunk is sent.
+pub struct ReportPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+/// At most `max_concurrent` reports at a time
+pub struct ReportLimit {
+    semaphore: Arc<Semaphore>,
+}
+
+impl ReportLimit {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+        }
+    }
+
+    /// Takes a slot without waiting, or 503 `{"error":"too_busy"}` when all are busy
+    pub fn try_acquire(&self) -> actix_web::Result<ReportPermit> {
+        match self.semaphore.clone().try_acquire_owned() {
+            Ok(permit) => Ok(ReportPermit { _permit: permit }),
+            Err(_) => {
+                tracing::warn!("Report rejected, concurrency limit reached");
+                let response = HttpResponse::build(StatusCode::SERVICE_UNAVAILABLE)
+                    .insert_header(("Retry-After", "5"))
+                    .json(serde_json::json!({ "error": "too_busy" }));
+                Err(InternalError::from_response("too_busy", response).into())
+            }
+        }
+    }
+}
+
+/// ===============================
+/// Report / export concurrency cap
+/// ===============================
+/// Takes a slot of the app-wide limit without waiting. When all are busy the caller gets
+/// 503 `{"error":"too_busy"}` so report storms can't starve interactive requests.
+pub fn try_acquire() -> actix_web::Result<ReportPermit> {
+    REPORTS
+        .get()
+        .expect("report_limit::init not called")
+        .try_acquire()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+
+    #[actix_web::test]
+    async fn report_past_the_limit_is_rejected() {
+        let limit = ReportLimit::new(2);
+
+        let first = limit.try_acquire().unwrap();
+        let _second = limit.try_acquire().unwrap();
+        let err = limit.try_acquire().err().unwrap();
+
+        let response = err.error_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers().get("Retry-After").unwrap(), "5");
+        let body = to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, r#"{"error":"too_busy"}"#);
+
+        // a finished report frees its slot
+        drop(first);
+        assert!(limit.try_acquire().is_ok());
+    }
+}
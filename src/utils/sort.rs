@@ -0,0 +1,65 @@
+/// ===============================
+/// List sort order
+/// ===============================
+/// Default ORDER BY of a list endpoint, configured as `column` or `column:asc|desc`.
+/// Only whitelisted columns are accepted since the column name ends up in the SQL.
+#[derive(Clone, Debug)]
+pub struct SortOrder {
+    column: &'static str,
+    descending: bool,
+}
+
+impl SortOrder {
+    pub fn parse(spec: &str, allowed: &[&'static str]) -> Option<Self> {
+        let (column, direction) = spec.trim().split_once(':').unwrap_or((spec.trim(), "asc"));
+
+        let descending = match direction.to_ascii_lowercase().as_str() {
+            "asc" => false,
+            "desc" => true,
+            _ => return None,
+        };
+
+        let column = allowed.iter().copied().find(|c| *c == column)?;
+        Some(SortOrder { column, descending })
+    }
+
+    /// `ORDER BY <column> <dir>, id <dir>`. The `id` tiebreaker keeps rows with equal
+    /// sort keys in the same order on every page.
+    pub fn order_by(&self) -> String {
+        let dir = if self.descending { "DESC" } else { "ASC" };
+        if self.column == "id" {
+            format!("ORDER BY id {}", dir)
+        } else {
+            format!("ORDER BY {} {}, id {}", self.column, dir, dir)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLUMNS: &[&str] = &["id", "month", "net_salary"];
+
+    #[test]
+    fn ties_are_broken_by_id_in_the_same_direction() {
+        let desc = SortOrder::parse("month:desc", COLUMNS).unwrap();
+        assert_eq!(desc.order_by(), "ORDER BY month DESC, id DESC");
+
+        let asc = SortOrder::parse("net_salary", COLUMNS).unwrap();
+        assert_eq!(asc.order_by(), "ORDER BY net_salary ASC, id ASC");
+    }
+
+    #[test]
+    fn id_needs_no_tiebreaker() {
+        let sort = SortOrder::parse("id:DESC", COLUMNS).unwrap();
+
+        assert_eq!(sort.order_by(), "ORDER BY id DESC");
+    }
+
+    #[test]
+    fn unknown_column_or_direction_is_refused() {
+        assert!(SortOrder::parse("salary:desc", COLUMNS).is_none());
+        assert!(SortOrder::parse("month:sideways", COLUMNS).is_none());
+    }
+}
@@ -21,6 +21,11 @@ pub async fn mark_taken(username: &str) {
         .await;
 }
 
+/// Drop a username after its user was deleted, so it can be registered again
+pub async fn forget(username: &str) {
+    USERNAME_CACHE.invalidate(&username.to_lowercase()).await;
+}
+
 /// Check if username is taken
 pub async fn is_taken(username: &str) -> bool {
     USERNAME_CACHE